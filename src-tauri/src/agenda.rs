@@ -0,0 +1,255 @@
+use chrono::NaiveDate;
+use rusqlite::Result as SqliteResult;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use crate::db::{Database, DEFAULT_PRIORITY_WINDOW_DAYS};
+use crate::models::*;
+use crate::utils::{get_tracker_tree, local_day};
+
+/// How far ahead assignments and planned topics show up.
+const WEEK_DAYS: i64 = 7;
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum AgendaBucket {
+    Overdue,
+    Today,
+    ThisWeek,
+}
+
+/// Declared in the order items of the same day are ranked.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum AgendaKind {
+    Test,
+    Assignment,
+    PlannedTopic,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AgendaItem {
+    pub bucket: AgendaBucket,
+    pub kind: AgendaKind,
+    /// The test or assignment ID, or the topic ID for planned topics.
+    pub id: String,
+    pub title: String,
+    pub tracker_id: String,
+    pub tracker_name: String,
+    pub subject_name: Option<String>,
+    /// The test type, the test a topic is planned for, or assignment status.
+    pub detail: Option<String>,
+    /// The local date it's due or planned for, as YYYY-MM-DD.
+    pub day: String,
+    /// Negative when overdue.
+    pub days_until: i64,
+    /// The test type's weight, so a final outranks a quiz on the same day.
+    pub weight: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Agenda {
+    pub today: String,
+    pub items: Vec<AgendaItem>,
+}
+
+fn bucket_for(days_until: i64) -> AgendaBucket {
+    match days_until {
+        d if d < 0 => AgendaBucket::Overdue,
+        0 => AgendaBucket::Today,
+        _ => AgendaBucket::ThisWeek,
+    }
+}
+
+/// Overdue first, then today, then the rest of the week; within each, the
+/// soonest first, tests before assignments before planned topics, and
+/// heavier test types first.
+pub fn rank_agenda(items: &mut [AgendaItem]) {
+    items.sort_by(|a, b| {
+        (a.bucket, a.days_until, a.kind)
+            .cmp(&(b.bucket, b.days_until, b.kind))
+            .then(b.weight.total_cmp(&a.weight))
+    });
+}
+
+fn tracker_items(db: &Database, tracker: &Tracker, today: NaiveDate, test_types: &[TestType]) -> SqliteResult<Vec<AgendaItem>> {
+    let tree = get_tracker_tree(db, &tracker.id)?;
+    let subject_names: HashMap<&str, &str> = tree
+        .iter()
+        .map(|s| (s.subject.id.as_str(), s.subject.name.as_str()))
+        .collect();
+    let topics: HashMap<&str, (&Topic, &str)> = tree
+        .iter()
+        .flat_map(|s| s.units.iter().flat_map(move |u| u.topics.iter().map(move |t| (t.id.as_str(), (t, s.subject.name.as_str())))))
+        .collect();
+    let item = |kind, id: &str, title: &str, day: NaiveDate| {
+        let days_until = (day - today).num_days();
+        AgendaItem {
+            bucket: bucket_for(days_until),
+            kind,
+            id: id.to_string(),
+            title: title.to_string(),
+            tracker_id: tracker.id.clone(),
+            tracker_name: tracker.name.clone(),
+            subject_name: None,
+            detail: None,
+            day: day.format("%Y-%m-%d").to_string(),
+            days_until,
+            weight: 0.0,
+        }
+    };
+
+    let mut items = Vec::new();
+
+    let tests = db.get_tests_by_tracker(&tracker.id)?;
+    for test in &tests {
+        let test_type = test_types.iter().find(|t| t.id == test.test_type);
+        let window = test_type.map_or(DEFAULT_PRIORITY_WINDOW_DAYS, |t| t.priority_window_days);
+        let day = local_day(test.scheduled_date);
+        let days_until = (day - today).num_days();
+        if (0..=i64::from(window)).contains(&days_until) {
+            items.push(AgendaItem {
+                detail: Some(test_type.map_or_else(|| test.test_type.clone(), |t| t.name.clone())),
+                weight: test_type.map_or(1.0, |t| t.weight),
+                ..item(AgendaKind::Test, &test.id, &test.name, day)
+            });
+        }
+    }
+
+    for assignment in db.get_assignments_by_tracker(&tracker.id)? {
+        let day = local_day(assignment.due_date);
+        if assignment.status.is_open() && (day - today).num_days() < WEEK_DAYS {
+            items.push(AgendaItem {
+                subject_name: assignment
+                    .subject_id
+                    .as_deref()
+                    .and_then(|id| subject_names.get(id))
+                    .map(|name| name.to_string()),
+                detail: Some(assignment.status.as_str().to_string()),
+                ..item(AgendaKind::Assignment, &assignment.id, &assignment.name, day)
+            });
+        }
+    }
+
+    // A missed day only counts as overdue until the plan is rebalanced
+    // and the topic lands on a later day
+    let plan = db.get_plan_items(&tracker.id)?;
+    let today_text = today.format("%Y-%m-%d").to_string();
+    let replanned: HashSet<&str> = plan
+        .iter()
+        .filter(|p| p.day.as_deref().is_some_and(|d| d >= today_text.as_str()))
+        .map(|p| p.topic_id.as_str())
+        .collect();
+    for planned in &plan {
+        let (Some(day), Some((topic, subject_name))) = (
+            planned.day.as_deref().and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok()),
+            topics.get(planned.topic_id.as_str()),
+        ) else {
+            continue;
+        };
+        let days_until = (day - today).num_days();
+        let overdue = days_until < 0 && !replanned.contains(topic.id.as_str());
+        if topic.completed || !(overdue || (0..WEEK_DAYS).contains(&days_until)) {
+            continue;
+        }
+        items.push(AgendaItem {
+            subject_name: Some(subject_name.to_string()),
+            detail: planned
+                .test_id
+                .as_ref()
+                .and_then(|id| tests.iter().find(|t| &t.id == id))
+                .map(|t| format!("for {}", t.name)),
+            ..item(AgendaKind::PlannedTopic, &topic.id, &topic.name, day)
+        });
+    }
+
+    Ok(items)
+}
+
+/// Everything due or planned from `today` to a week out across the given
+/// trackers, plus overdue assignments and missed study days, ranked.
+pub fn build_agenda(db: &Database, trackers: &[Tracker], today: NaiveDate) -> SqliteResult<Agenda> {
+    let test_types = db.get_all_test_types()?;
+    let mut items = Vec::new();
+    for tracker in trackers {
+        items.extend(tracker_items(db, tracker, today, &test_types)?);
+    }
+    rank_agenda(&mut items);
+    Ok(Agenda {
+        today: today.format("%Y-%m-%d").to_string(),
+        items,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::planner::rebuild_plan;
+    use chrono::{Local, TimeZone};
+    use std::path::PathBuf;
+
+    fn at(day: u32, hour: u32) -> i64 {
+        Local.with_ymd_and_hms(2025, 3, day, hour, 0, 0).unwrap().timestamp_millis()
+    }
+
+    #[test]
+    fn test_agenda() {
+        let db = Database::new(PathBuf::from(":memory:")).unwrap();
+        db.create_semester("sem".into(), "Sem".into(), 0).unwrap();
+        db.create_tracker("tr".into(), "sem".into(), "Sem 4".into(), None, None, 0).unwrap();
+        db.create_subject("os".into(), "tr".into(), "OS".into(), 0).unwrap();
+        db.create_unit("u1".into(), "os".into(), "Processes".into(), 0, 0).unwrap();
+        db.create_topic("t1".into(), "u1".into(), "Threads".into(), 0, 0).unwrap();
+        db.create_topic("t2".into(), "u1".into(), "Scheduling".into(), 1, 0).unwrap();
+
+        db.create_test("quiz".into(), "tr".into(), "Quiz".into(), "class_test".into(), at(12, 20), 0).unwrap();
+        db.create_test("esa".into(), "tr".into(), "ESA".into(), "esa".into(), at(12, 20), 0).unwrap();
+        db.create_test("far".into(), "tr".into(), "Far off".into(), "isa".into(), at(30, 20), 0).unwrap();
+        db.create_test_coverage("c1".into(), "esa".into(), Some("u1".into()), None).unwrap();
+
+        let assignment = |id: &str, due| AssignmentInput {
+            subject_id: Some("os".into()),
+            name: id.into(),
+            description: None,
+            due_date: due,
+            topic_ids: Vec::new(),
+        };
+        db.create_assignment("late".into(), "tr".into(), &assignment("Late report", at(8, 23)), 0).unwrap();
+        db.create_assignment("done".into(), "tr".into(), &assignment("Done", at(9, 23)), 0).unwrap();
+        db.set_assignment_status("done", AssignmentStatus::Submitted, 0).unwrap();
+
+        // Planned on the 8th with one topic a day, then not rebalanced
+        let settings = PlanSettings {
+            capacity_unit: CapacityUnit::Topics,
+            daily_capacity: 1,
+            minutes_per_topic: 30,
+            rest_days: Vec::new(),
+        };
+        db.set_plan_settings("tr", &settings, 0).unwrap();
+        rebuild_plan(&db, "tr", &settings, local_day(at(8, 9)), 0).unwrap();
+
+        let trackers = db.get_trackers_by_semester("sem").unwrap();
+        let agenda = build_agenda(&db, &trackers, local_day(at(10, 9))).unwrap();
+        let ranked: Vec<(AgendaBucket, &str)> = agenda.items.iter().map(|i| (i.bucket, i.title.as_str())).collect();
+        assert_eq!(ranked, vec![
+            (AgendaBucket::Overdue, "Late report"),
+            (AgendaBucket::Overdue, "Threads"),
+            (AgendaBucket::Overdue, "Scheduling"),
+            (AgendaBucket::ThisWeek, "ESA"),
+            (AgendaBucket::ThisWeek, "Quiz"),
+        ]);
+        assert_eq!(agenda.items[1].detail.as_deref(), Some("for ESA"));
+        assert_eq!(agenda.today, "2025-03-10");
+
+        // Once rebalanced, the topics are planned from today instead
+        db.toggle_topic_completion("t1", 0).unwrap();
+        rebuild_plan(&db, "tr", &settings, local_day(at(10, 9)), 0).unwrap();
+        let agenda = build_agenda(&db, &trackers, local_day(at(10, 9))).unwrap();
+        let planned: Vec<(AgendaBucket, &str)> = agenda
+            .items
+            .iter()
+            .filter(|i| i.kind == AgendaKind::PlannedTopic)
+            .map(|i| (i.bucket, i.title.as_str()))
+            .collect();
+        assert_eq!(planned, vec![(AgendaBucket::Today, "Scheduling")]);
+    }
+}
@@ -0,0 +1,6 @@
+// A console program, so no windows_subsystem attribute here: it has to
+// print to the terminal it was started from.
+
+fn main() {
+    std::process::exit(studyapp_lib::run_cli(std::env::args().skip(1).collect()))
+}
@@ -0,0 +1,485 @@
+use chrono::{Local, NaiveDate, NaiveDateTime, TimeZone};
+use serde::Serialize;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use crate::db::Database;
+use crate::exporters::ExportFormat;
+use crate::ical::test_timestamp;
+use crate::importers::SyllabusFormat;
+use crate::models::*;
+use crate::service::StudyService;
+use crate::utils::local_day;
+
+/// The bundle identifier from tauri.conf.json; the app keeps its data in a
+/// directory of this name.
+const APP_IDENTIFIER: &str = "com.studyapp.app";
+
+const USAGE: &str = "\
+Usage: studyapp-cli [--db PATH] [--json] <command> [args]
+
+Commands:
+  semesters                          List semesters
+  trackers [SEMESTER]                List trackers, optionally of one semester
+  topics TRACKER                     List a tracker's topics
+  progress TRACKER                   Print completion per subject and unit
+  toggle TOPIC                       Mark a topic done, or not done again
+  tests TRACKER                      List a tracker's tests
+  schedule TRACKER NAME TYPE DATE    Schedule a test. DATE is YYYY-MM-DD or
+    [--unit ID]... [--topic ID]...   YYYY-MM-DDTHH:MM in local time
+  import SEMESTER FILE               Create a tracker from a syllabus file,
+    [--format F] [--name NAME]       or from stdin when FILE is -. F is json,
+                                     text, markdown or csv; without it JSON
+                                     and the text formats are detected. Text
+                                     formats name the tracker NAME, or after
+                                     the file
+  export TRACKER [--format F] [FILE] Export as json, text, markdown, csv or
+                                     html to FILE, or to stdout
+  help                               Show this message
+
+The database defaults to the one the app uses. Set STUDYAPP_DB or pass
+--db to use another. Output is tab-separated unless --json is given.";
+
+// ============================================================================
+// ARGUMENTS
+// ============================================================================
+
+#[derive(Debug)]
+enum Command {
+    Semesters,
+    Trackers { semester_id: Option<String> },
+    Topics { tracker_id: String },
+    Progress { tracker_id: String },
+    Toggle { topic_id: String },
+    Tests { tracker_id: String },
+    Schedule {
+        tracker_id: String,
+        name: String,
+        test_type: String,
+        scheduled_date: i64,
+        coverage: Vec<TestCoverageInput>,
+    },
+    Import {
+        semester_id: String,
+        file: String,
+        format: Option<ImportFormat>,
+        name: Option<String>,
+    },
+    Export { tracker_id: String, format: ExportFormat, file: Option<String> },
+    Help,
+}
+
+/// What `import` reads: a syllabus JSON file or one of the text formats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ImportFormat {
+    Json,
+    Syllabus(SyllabusFormat),
+}
+
+#[derive(Debug)]
+struct Invocation {
+    db_path: Option<PathBuf>,
+    json: bool,
+    command: Command,
+}
+
+/// Parses `--flag VALUE` options anywhere on the line; everything else is
+/// positional.
+fn parse_args(args: &[String]) -> Result<Invocation, String> {
+    let mut db_path = None;
+    let mut json = false;
+    let mut format = None;
+    let mut tracker_name = None;
+    let mut coverage = Vec::new();
+    let mut positional = Vec::new();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = |flag: &str| args.next().cloned().ok_or_else(|| format!("{} needs a value", flag));
+        match arg.as_str() {
+            "--db" => db_path = Some(PathBuf::from(value("--db")?)),
+            "--json" => json = true,
+            "--format" => format = Some(value("--format")?.to_lowercase()),
+            "--name" => tracker_name = Some(value("--name")?),
+            "--unit" => coverage.push(TestCoverageInput { unit_id: Some(value("--unit")?), topic_id: None }),
+            "--topic" => coverage.push(TestCoverageInput { unit_id: None, topic_id: Some(value("--topic")?) }),
+            "-h" | "--help" => positional.insert(0, "help".to_string()),
+            flag if flag.starts_with("--") => return Err(format!("Unknown option '{}'", flag)),
+            _ => positional.push(arg.clone()),
+        }
+    }
+
+    let mut positional = positional.into_iter();
+    let name = positional.next().unwrap_or_else(|| "help".to_string());
+    let mut required = |what: &str| positional.next().ok_or_else(|| format!("'{}' needs {}", name, what));
+    let command = match name.as_str() {
+        "semesters" => Command::Semesters,
+        "trackers" => Command::Trackers { semester_id: positional.next() },
+        "topics" => Command::Topics { tracker_id: required("a tracker ID")? },
+        "progress" => Command::Progress { tracker_id: required("a tracker ID")? },
+        "toggle" => Command::Toggle { topic_id: required("a topic ID")? },
+        "tests" => Command::Tests { tracker_id: required("a tracker ID")? },
+        "schedule" => Command::Schedule {
+            tracker_id: required("a tracker ID")?,
+            name: required("a test name")?,
+            test_type: required("a test type")?,
+            scheduled_date: parse_date(&required("a date")?)?,
+            coverage,
+        },
+        "import" => Command::Import {
+            semester_id: required("a semester ID")?,
+            file: required("a file, or - for stdin")?,
+            format: format.map(|f| parse_import_format(&f)).transpose()?,
+            name: tracker_name,
+        },
+        "export" => Command::Export {
+            tracker_id: required("a tracker ID")?,
+            format: format.map(|f| parse_export_format(&f)).transpose()?.unwrap_or(ExportFormat::Json),
+            file: positional.next(),
+        },
+        "help" => Command::Help,
+        other => return Err(format!("Unknown command '{}'", other)),
+    };
+    if let Some(extra) = positional.next() {
+        return Err(format!("Unexpected argument '{}'", extra));
+    }
+
+    Ok(Invocation { db_path, json, command })
+}
+
+fn parse_export_format(name: &str) -> Result<ExportFormat, String> {
+    serde_json::from_value(serde_json::Value::String(name.to_string()))
+        .map_err(|_| format!("Unknown export format '{}'", name))
+}
+
+fn parse_import_format(name: &str) -> Result<ImportFormat, String> {
+    if name == "json" {
+        return Ok(ImportFormat::Json);
+    }
+    serde_json::from_value(serde_json::Value::String(name.to_string()))
+        .map(ImportFormat::Syllabus)
+        .map_err(|_| format!("Unknown import format '{}'", name))
+}
+
+/// A bare date is stored at 8 PM local time, like tests made in the app.
+fn parse_date(value: &str) -> Result<i64, String> {
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(test_timestamp(date));
+    }
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M")
+        .ok()
+        .and_then(|dt| Local.from_local_datetime(&dt).earliest())
+        .map(|dt| dt.timestamp_millis())
+        .ok_or_else(|| format!("Invalid date '{}', expected YYYY-MM-DD or YYYY-MM-DDTHH:MM", value))
+}
+
+/// Where the app keeps its database: Tauri's app local data directory.
+fn default_db_path() -> Option<PathBuf> {
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+    let base = if cfg!(windows) {
+        std::env::var_os("LOCALAPPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        home.map(|h| h.join("Library").join("Application Support"))
+    } else {
+        std::env::var_os("XDG_DATA_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| home.map(|h| h.join(".local").join("share")))
+    };
+    base.map(|dir| dir.join(APP_IDENTIFIER).join("studyapp.db"))
+}
+
+// ============================================================================
+// COMMANDS
+// ============================================================================
+
+/// Pretty JSON with `--json`, otherwise the text rendering.
+fn output<T: Serialize>(json: bool, value: &T, text: impl FnOnce(&T) -> String) -> Result<String, String> {
+    if json {
+        serde_json::to_string_pretty(value).map_err(|e| e.to_string())
+    } else {
+        Ok(text(value))
+    }
+}
+
+fn read_input(file: &str) -> Result<String, String> {
+    if file == "-" {
+        let mut input = String::new();
+        std::io::stdin().read_to_string(&mut input).map_err(|e| e.to_string())?;
+        Ok(input)
+    } else {
+        std::fs::read_to_string(file).map_err(|e| format!("Failed to read {}: {}", file, e))
+    }
+}
+
+/// Runs one command and returns what to print on stdout.
+fn execute(service: &StudyService, command: Command, json: bool) -> Result<String, String> {
+    match command {
+        Command::Help => Ok(USAGE.to_string()),
+        Command::Semesters => {
+            let semesters = service.get_all_semesters()?;
+            output(json, &semesters, |semesters| {
+                semesters.iter().map(|s| format!("{}\t{}\n", s.id, s.name)).collect()
+            })
+        }
+        Command::Trackers { semester_id } => {
+            let semester_ids = match semester_id {
+                Some(id) => vec![id],
+                None => service.get_all_semesters()?.into_iter().map(|s| s.id).collect(),
+            };
+            let mut summaries = Vec::new();
+            for id in &semester_ids {
+                summaries.extend(service.semester_overview(id)?.trackers);
+            }
+            output(json, &summaries, |summaries| {
+                summaries
+                    .iter()
+                    .map(|s| {
+                        format!(
+                            "{}\t{}\t{}/{}\t{:.1}%\n",
+                            s.tracker.id, s.tracker.name, s.completed_topics, s.total_topics, s.percentage
+                        )
+                    })
+                    .collect()
+            })
+        }
+        Command::Topics { tracker_id } => {
+            let tree = service.get_tracker_tree(&tracker_id)?;
+            output(json, &tree, |tree| {
+                let mut text = String::new();
+                for subject in tree {
+                    for unit in &subject.units {
+                        for topic in &unit.topics {
+                            text.push_str(&format!(
+                                "{}\t[{}]\t{} > {} > {}\n",
+                                topic.id,
+                                if topic.completed { "x" } else { " " },
+                                subject.subject.name,
+                                unit.unit.name,
+                                topic.name
+                            ));
+                        }
+                    }
+                }
+                text
+            })
+        }
+        Command::Progress { tracker_id } => {
+            let tracker = service.get_tracker(&tracker_id)?.ok_or("Tracker not found")?;
+            let progress = service.tracker_progress(&tracker_id)?;
+            output(json, &progress, |progress| {
+                let mut text = format!(
+                    "{}\t{}/{}\t{:.1}%\n",
+                    tracker.name, progress.completed_topics, progress.total_topics, progress.percentage
+                );
+                for subject in &progress.subjects {
+                    text.push_str(&format!(
+                        "  {}\t{}/{}\t{:.1}%\n",
+                        subject.subject_name, subject.completed_topics, subject.total_topics, subject.percentage
+                    ));
+                    for unit in &subject.units {
+                        text.push_str(&format!(
+                            "    {}\t{}/{}\t{:.1}%\n",
+                            unit.unit_name, unit.completed_topics, unit.total_topics, unit.percentage
+                        ));
+                    }
+                }
+                text
+            })
+        }
+        Command::Toggle { topic_id } => {
+            let topic = service.toggle_topic(&topic_id)?.ok_or("Topic not found")?;
+            output(json, &topic, |topic| {
+                format!("{}\t{}\t{}\n", topic.id, if topic.completed { "done" } else { "not done" }, topic.name)
+            })
+        }
+        Command::Tests { tracker_id } => {
+            service.get_tracker(&tracker_id)?.ok_or("Tracker not found")?;
+            let tests = service.get_tests_by_tracker(&tracker_id)?;
+            output(json, &tests, |tests| {
+                tests
+                    .iter()
+                    .map(|t| format!("{}\t{}\t{}\t{}\n", t.id, local_day(t.scheduled_date), t.test_type, t.name))
+                    .collect()
+            })
+        }
+        Command::Schedule { tracker_id, name, test_type, scheduled_date, coverage } => {
+            service.get_tracker(&tracker_id)?.ok_or("Tracker not found")?;
+            let test = service.schedule_test(tracker_id, name, test_type, scheduled_date, coverage)?;
+            output(json, &test, |test| format!("{}\n", test.id))
+        }
+        Command::Import { semester_id, file, format, name } => {
+            let input = read_input(&file)?;
+            // A JSON syllabus is an object; anything else is syllabus text
+            let is_json = format.map_or_else(|| input.trim_start().starts_with('{'), |f| f == ImportFormat::Json);
+            let tracker = if is_json {
+                let value: serde_json::Value = serde_json::from_str(&input)
+                    .map_err(|e| format!("Invalid JSON: {}", e))?;
+                service.import_syllabus(semester_id, value)?
+            } else {
+                let name = match name {
+                    Some(name) => name,
+                    None if file == "-" => return Err("Pass --name to name a tracker read from stdin".to_string()),
+                    None => PathBuf::from(&file)
+                        .file_stem()
+                        .map_or_else(|| file.clone(), |stem| stem.to_string_lossy().into_owned()),
+                };
+                let format = match format {
+                    Some(ImportFormat::Syllabus(format)) => Some(format),
+                    _ => None,
+                };
+                service.create_tracker(semester_id, name, &input, format)?
+            };
+            output(json, &tracker, |tracker| format!("{}\t{}\n", tracker.id, tracker.name))
+        }
+        Command::Export { tracker_id, format, file } => {
+            let exported = service.export_tracker(&tracker_id, format)?;
+            match file {
+                Some(path) => {
+                    std::fs::write(&path, &exported.content).map_err(|e| format!("Failed to write {}: {}", path, e))?;
+                    Ok(String::new())
+                }
+                None => Ok(exported.content),
+            }
+        }
+    }
+}
+
+/// Runs the command line and returns the process exit code: 0 on success,
+/// 1 when the command fails and 2 for usage errors.
+pub fn run(args: Vec<String>) -> i32 {
+    let invocation = match parse_args(&args) {
+        Ok(invocation) => invocation,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            return 2;
+        }
+    };
+    if matches!(invocation.command, Command::Help) {
+        println!("{}", USAGE);
+        return 0;
+    }
+
+    let Some(db_path) = invocation
+        .db_path
+        .or_else(|| std::env::var_os("STUDYAPP_DB").map(PathBuf::from))
+        .or_else(default_db_path)
+    else {
+        eprintln!("Couldn't find the app's data directory. Pass --db or set STUDYAPP_DB.");
+        return 1;
+    };
+    if let Some(parent) = db_path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        if let Err(e) = std::fs::create_dir_all(parent) {
+            eprintln!("Failed to create {}: {}", parent.display(), e);
+            return 1;
+        }
+    }
+
+    let result = Database::new(db_path.clone())
+        .map_err(|e| format!("Failed to open the database at {}: {}", db_path.display(), e))
+        .and_then(|db| execute(&StudyService::new(&db), invocation.command, invocation.json));
+    match result {
+        Ok(text) => {
+            let mut stdout = std::io::stdout();
+            if !text.is_empty() {
+                // A closed pipe, as with `| head`, isn't worth an error
+                let _ = writeln!(stdout, "{}", text.trim_end_matches('\n'));
+            }
+            0
+        }
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let invocation = parse_args(&args("--json schedule tr ISA-1 isa 2025-03-14 --unit u1 --topic t2 --db /tmp/x.db")).unwrap();
+        assert!(invocation.json);
+        assert_eq!(invocation.db_path, Some(PathBuf::from("/tmp/x.db")));
+        let Command::Schedule { name, scheduled_date, coverage, .. } = invocation.command else {
+            panic!("expected schedule");
+        };
+        assert_eq!(name, "ISA-1");
+        assert_eq!(local_day(scheduled_date), NaiveDate::from_ymd_opt(2025, 3, 14).unwrap());
+        assert_eq!(coverage.len(), 2);
+
+        let export = parse_args(&args("export tr --format Markdown out.md")).unwrap();
+        assert!(matches!(
+            export.command,
+            Command::Export { format: ExportFormat::Markdown, file: Some(ref file), .. } if file == "out.md"
+        ));
+        assert!(matches!(parse_args(&[]).unwrap().command, Command::Help));
+        assert!(parse_args(&args("toggle")).is_err());
+        assert!(parse_args(&args("export tr --format pdf")).is_err());
+        let import = parse_args(&args("import sem - --format CSV --name OS")).unwrap();
+        assert!(matches!(
+            import.command,
+            Command::Import { format: Some(ImportFormat::Syllabus(SyllabusFormat::Csv)), name: Some(ref name), .. } if name == "OS"
+        ));
+        assert!(parse_args(&args("import sem os.txt --format html")).is_err());
+        assert!(parse_args(&args("schedule tr Quiz isa 14/03/2025")).is_err());
+        assert!(parse_args(&args("semesters extra")).is_err());
+    }
+
+    #[test]
+    fn test_commands() {
+        let db = Database::new(PathBuf::from(":memory:")).unwrap();
+        db.create_semester("sem".into(), "Sem 4".into(), 0).unwrap();
+
+        let dir = std::env::temp_dir().join(format!("studyapp-cli-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let syllabus = dir.join("os.json");
+        std::fs::write(
+            &syllabus,
+            r#"{"version": "1.0", "name": "OS", "subjects": [{"name": "OS", "units": [{"name": "Processes", "topics": ["Threads", "Scheduling"]}]}]}"#,
+        )
+        .unwrap();
+
+        let service = StudyService::new(&db);
+        let run = |line: String| execute(&service, parse_args(&args(&line)).unwrap().command, false);
+        let imported = run(format!("import sem {}", syllabus.display())).unwrap();
+        let tracker_id = imported.split('\t').next().unwrap().to_string();
+        assert_eq!(run("trackers".into()).unwrap(), format!("{}\tOS\t0/2\t0.0%\n", tracker_id));
+
+        let topics = run(format!("topics {}", tracker_id)).unwrap();
+        let threads = topics.lines().next().unwrap().split('\t').next().unwrap().to_string();
+        assert!(topics.lines().next().unwrap().ends_with("[ ]\tOS > Processes > Threads"));
+        assert!(run(format!("toggle {}", threads)).unwrap().contains("\tdone\t"));
+        assert!(run(format!("progress {}", tracker_id)).unwrap().starts_with("OS\t1/2\t50.0%\n"));
+
+        run(format!("schedule {} Quiz class_test 2025-03-14", tracker_id)).unwrap();
+        assert!(run(format!("schedule {} Quiz pop_quiz 2025-03-14", tracker_id)).is_err());
+        assert!(run(format!("tests {}", tracker_id)).unwrap().contains("\t2025-03-14\tclass_test\tQuiz"));
+
+        let exported = dir.join("os.md");
+        run(format!("export {} --format markdown {}", tracker_id, exported.display())).unwrap();
+        assert!(std::fs::read_to_string(&exported).unwrap().contains("- [x] Threads"));
+        assert!(run("progress missing".into()).unwrap_err().contains("Tracker not found"));
+
+        // Text syllabi are detected, or read as the format given, and named
+        // after the file
+        let networks = dir.join("networks.txt");
+        std::fs::write(&networks, "# Networks\n## Routing\n- BGP").unwrap();
+        let imported = run(format!("import sem {}", networks.display())).unwrap();
+        assert!(imported.ends_with("\tnetworks\n"));
+        let tracker_id = imported.split('\t').next().unwrap();
+        assert!(run(format!("topics {}", tracker_id)).unwrap().ends_with("[ ]\tNetworks > Routing > BGP\n"));
+        let forced = run(format!("import sem {} --format text --name Nets", networks.display())).unwrap();
+        let tracker_id = forced.split('\t').next().unwrap();
+        assert!(run(format!("progress {}", tracker_id)).unwrap().contains("# Networks"));
+        assert!(run(format!("import sem {} --format json", networks.display())).unwrap_err().starts_with("Invalid JSON"));
+        assert!(run("import sem - --format csv".into()).unwrap_err().contains("--name"));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
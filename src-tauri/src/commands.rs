@@ -1,19 +1,48 @@
 use crate::models::*;
-use crate::db::Database;
-use crate::utils::{parse_syllabus, calculate_tracker_progress, get_days_remaining, format_time_remaining, is_within_priority_window};
-use uuid::Uuid;
-use std::sync::Mutex;
-use std::time::SystemTime;
+use crate::importers::SyllabusFormat;
+use crate::exporters::{ExportFormat, ExportedFile};
+use crate::ical::{CalendarImportPreview, CalendarScope};
+use crate::diff::SyllabusDiff;
+use crate::agenda::Agenda;
+use crate::integrity::{DatabaseReport, RepairReport};
+use crate::planner::StudyPlan;
+use crate::grades::{GradeReport, SemesterGrades, SemesterTargets, SubjectTarget};
+use crate::merge::{MergePlan, MergeSummary};
+use crate::syllabus::{SyllabusExport, ImportPreview, ImportSource};
+use crate::pool::{DbIncident, DbPool};
+use crate::service::{AssignmentDetails, StudyService, TestDetails, TrackerData};
+use std::sync::Arc;
 
 pub struct AppState {
-    pub db: Mutex<Database>,
+    pub pool: Arc<DbPool>,
 }
 
-fn get_current_timestamp() -> i64 {
-    SystemTime::now()
-        .duration_since(SystemTime::UNIX_EPOCH)
-        .unwrap()
-        .as_millis() as i64
+impl AppState {
+    /// Runs `f` with a service on a read connection on the blocking thread
+    /// pool, so heavy reads neither block the UI nor wait on writes.
+    pub async fn read<T, F>(&self, f: F) -> Result<T, String>
+    where
+        T: Send + 'static,
+        F: FnOnce(&StudyService) -> Result<T, String> + Send + 'static,
+    {
+        let pool = self.pool.clone();
+        tauri::async_runtime::spawn_blocking(move || f(&StudyService::new(&*pool.read()?)))
+            .await
+            .map_err(|e| e.to_string())?
+    }
+
+    /// Runs `f` with a service on the single writer on the blocking thread
+    /// pool.
+    pub async fn write<T, F>(&self, f: F) -> Result<T, String>
+    where
+        T: Send + 'static,
+        F: FnOnce(&StudyService) -> Result<T, String> + Send + 'static,
+    {
+        let pool = self.pool.clone();
+        tauri::async_runtime::spawn_blocking(move || f(&StudyService::new(&*pool.write()?)))
+            .await
+            .map_err(|e| e.to_string())?
+    }
 }
 
 // ============================================================================
@@ -21,24 +50,28 @@ fn get_current_timestamp() -> i64 {
 // ============================================================================
 
 #[tauri::command]
-pub fn create_semester(state: tauri::State<AppState>, name: String) -> Result<Semester, String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    let id = Uuid::new_v4().to_string();
-    let now = get_current_timestamp();
-    db.create_semester(id, name, now)
-        .map_err(|e| e.to_string())
+pub async fn create_semester(state: tauri::State<'_, AppState>, name: String) -> Result<Semester, String> {
+    state.write(move |service| service.create_semester(name)).await
 }
 
 #[tauri::command]
-pub fn get_all_semesters(state: tauri::State<AppState>) -> Result<Vec<Semester>, String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    db.get_all_semesters().map_err(|e| e.to_string())
+pub async fn get_all_semesters(state: tauri::State<'_, AppState>) -> Result<Vec<Semester>, String> {
+    state.read(move |service| service.get_all_semesters()).await
 }
 
 #[tauri::command]
-pub fn delete_semester(state: tauri::State<AppState>, id: String) -> Result<(), String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    db.delete_semester(&id).map_err(|e| e.to_string())
+pub async fn delete_semester(state: tauri::State<'_, AppState>, id: String) -> Result<(), String> {
+    state.write(move |service| service.delete_semester(&id)).await
+}
+
+/// Topic progress of every tracker in a semester plus its upcoming tests.
+#[tauri::command]
+pub async fn get_semester_overview(
+    state: tauri::State<'_, AppState>,
+    #[allow(non_snake_case)]
+    semesterId: String,
+) -> Result<SemesterOverview, String> {
+    state.read(move |service| service.semester_overview(&semesterId)).await
 }
 
 // ============================================================================
@@ -46,76 +79,30 @@ pub fn delete_semester(state: tauri::State<AppState>, id: String) -> Result<(),
 // ============================================================================
 
 #[tauri::command]
-pub fn create_new_tracker(
-    state: tauri::State<AppState>,
+pub async fn create_new_tracker(
+    state: tauri::State<'_, AppState>,
     #[allow(non_snake_case)]
     semesterId: String,
     name: String,
     #[allow(non_snake_case)]
     syllabusText: String,
+    format: Option<SyllabusFormat>,
 ) -> Result<Tracker, String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-
-    // Parse syllabus
-    let parsed_entries = parse_syllabus(&syllabusText)?;
-
-    // Create tracker
-    let tracker_id = Uuid::new_v4().to_string();
-    let now = get_current_timestamp();
-    let _tracker = db
-        .create_tracker(tracker_id.clone(), semesterId, name, None, None, now)
-        .map_err(|e| e.to_string())?;
-
-    // Create subjects, units, and topics
-    for (_subject_idx, entry) in parsed_entries.iter().enumerate() {
-        let subject_id = Uuid::new_v4().to_string();
-        db.create_subject(subject_id.clone(), tracker_id.clone(), entry.subject_name.clone(), now)
-            .map_err(|e| e.to_string())?;
-
-        for (unit_idx, unit) in entry.units.iter().enumerate() {
-            let unit_id = Uuid::new_v4().to_string();
-            db.create_unit(
-                unit_id.clone(),
-                subject_id.clone(),
-                unit.unit_name.clone(),
-                unit_idx as i32,
-                now,
-            )
-            .map_err(|e| e.to_string())?;
-
-            for (topic_idx, topic) in unit.topics.iter().enumerate() {
-                let topic_id = Uuid::new_v4().to_string();
-                db.create_topic(topic_id, unit_id.clone(), topic.clone(), topic_idx as i32, now)
-                    .map_err(|e| e.to_string())?;
-            }
-        }
-    }
-
-    // Update tracker statistics
-    db.update_tracker_statistics(&tracker_id, now)
-        .map_err(|e| e.to_string())?;
-
-    // Return updated tracker
-    db.get_tracker(&tracker_id)
-        .map_err(|e| e.to_string())?
-        .ok_or_else(|| "Failed to retrieve created tracker".to_string())
+    state.write(move |service| service.create_tracker(semesterId, name, &syllabusText, format)).await
 }
 
 #[tauri::command]
-pub fn get_trackers_by_semester(
-    state: tauri::State<AppState>, 
+pub async fn get_trackers_by_semester(
+    state: tauri::State<'_, AppState>,
     #[allow(non_snake_case)]
     semesterId: String
 ) -> Result<Vec<Tracker>, String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    db.get_trackers_by_semester(&semesterId)
-        .map_err(|e| e.to_string())
+    state.read(move |service| service.get_trackers_by_semester(&semesterId)).await
 }
 
 #[tauri::command]
-pub fn get_tracker(state: tauri::State<AppState>, id: String) -> Result<Option<Tracker>, String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    db.get_tracker(&id).map_err(|e| e.to_string())
+pub async fn get_tracker(state: tauri::State<'_, AppState>, id: String) -> Result<Option<Tracker>, String> {
+    state.read(move |service| service.get_tracker(&id)).await
 }
 
 // ============================================================================
@@ -123,86 +110,18 @@ pub fn get_tracker(state: tauri::State<AppState>, id: String) -> Result<Option<T
 // ============================================================================
 
 #[tauri::command]
-pub fn get_tracker_data(
-    state: tauri::State<AppState>,
+pub async fn get_tracker_data(
+    state: tauri::State<'_, AppState>,
     #[allow(non_snake_case)]
     trackerId: String,
 ) -> Result<TrackerData, String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-
-    let subjects = db.get_subjects_by_tracker(&trackerId)
-        .map_err(|e| e.to_string())?;
-
-    let mut subjects_data = Vec::new();
-    for subject in subjects {
-        let units = db.get_units_by_subject(&subject.id)
-            .map_err(|e| e.to_string())?;
-
-        let mut units_data = Vec::new();
-        for unit in units {
-            let topics = db.get_topics_by_unit(&unit.id)
-                .map_err(|e| e.to_string())?;
-
-            units_data.push(UnitData {
-                unit: unit.clone(),
-                topics,
-            });
-        }
-
-        subjects_data.push(SubjectData {
-            subject: subject.clone(),
-            units: units_data,
-        });
-    }
-
-    let progress = calculate_tracker_progress(&db, &trackerId)
-        .map_err(|e| e.to_string())?;
-
-    let tests = db.get_tests_by_tracker(&trackerId)
-        .map_err(|e| e.to_string())?;
-
-    let mut priority_tests = Vec::new();
-    for test in tests.iter() {
-        if is_within_priority_window(test.scheduled_date) {
-            let coverage = db.get_test_coverage(&test.id)
-                .map_err(|e| e.to_string())?;
-
-            // Collect all topics covered by this test
-            let mut covered_topics = Vec::new();
-            for cov in coverage.iter() {
-                if let Some(unit_id) = &cov.unit_id {
-                    // Entire unit covered
-                    let unit_topics = db.get_topics_by_unit(unit_id)
-                        .map_err(|e| e.to_string())?;
-                    covered_topics.extend(unit_topics.iter().map(|t| t.name.clone()));
-                } else if let Some(topic_id) = &cov.topic_id {
-                    // Specific topic covered
-                    if let Some(topic) = db.get_topic(topic_id)
-                        .map_err(|e| e.to_string())? {
-                        covered_topics.push(topic.name);
-                    }
-                }
-            }
-
-            priority_tests.push(PriorityTest {
-                test: test.clone(),
-                coverage: coverage.clone(),
-                days_remaining: get_days_remaining(test.scheduled_date),
-                time_remaining: format_time_remaining(test.scheduled_date),
-                covered_topics,
-            });
-        }
-    }
-
-    // Sort by days remaining (closer tests first)
-    priority_tests.sort_by_key(|t| t.days_remaining);
+    state.read(move |service| service.get_tracker_data(&trackerId)).await
+}
 
-    Ok(TrackerData {
-        subjects: subjects_data,
-        progress,
-        all_tests: tests,
-        priority_tests,
-    })
+/// Recounts every tracker's cached totals. Returns how many were wrong.
+#[tauri::command]
+pub async fn recompute_all_statistics(state: tauri::State<'_, AppState>) -> Result<usize, String> {
+    state.write(move |service| service.recompute_all_statistics()).await
 }
 
 // ============================================================================
@@ -210,15 +129,12 @@ pub fn get_tracker_data(
 // ============================================================================
 
 #[tauri::command]
-pub fn toggle_topic(
-    state: tauri::State<AppState>, 
+pub async fn toggle_topic(
+    state: tauri::State<'_, AppState>,
     #[allow(non_snake_case)]
     topicId: String
 ) -> Result<Option<Topic>, String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    let now = get_current_timestamp();
-    db.toggle_topic_completion(&topicId, now)
-        .map_err(|e| e.to_string())
+    state.write(move |service| service.toggle_topic(&topicId)).await
 }
 
 // ============================================================================
@@ -226,8 +142,8 @@ pub fn toggle_topic(
 // ============================================================================
 
 #[tauri::command]
-pub fn schedule_test(
-    state: tauri::State<AppState>,
+pub async fn schedule_test(
+    state: tauri::State<'_, AppState>,
     #[allow(non_snake_case)]
     trackerId: String,
     name: String,
@@ -238,81 +154,413 @@ pub fn schedule_test(
     #[allow(non_snake_case)]
     coverageData: Vec<TestCoverageInput>,
 ) -> Result<Test, String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-
-    let test_type_enum = TestType::from_str(&testType)
-        .ok_or_else(|| "Invalid test type".to_string())?;
-
-    let test_id = Uuid::new_v4().to_string();
-    let now = get_current_timestamp();
-
-    let test = db.create_test(
-        test_id.clone(),
-        trackerId,
-        name,
-        &test_type_enum,
-        scheduledDate,
-        now,
-    )
-    .map_err(|e| e.to_string())?;
-
-    // Create coverage entries
-    for cov_input in coverageData {
-        let cov_id = Uuid::new_v4().to_string();
-        db.create_test_coverage(cov_id, test_id.clone(), cov_input.unit_id, cov_input.topic_id)
-            .map_err(|e| e.to_string())?;
-    }
+    state.write(move |service| service.schedule_test(trackerId, name, testType, scheduledDate, coverageData)).await
+}
+
+#[tauri::command]
+pub async fn reschedule_test(
+    state: tauri::State<'_, AppState>,
+    #[allow(non_snake_case)]
+    testId: String,
+    #[allow(non_snake_case)]
+    scheduledDate: i64,
+) -> Result<Test, String> {
+    state.write(move |service| service.reschedule_test(&testId, scheduledDate)).await
+}
 
-    Ok(test)
+#[tauri::command]
+pub async fn rename_test(
+    state: tauri::State<'_, AppState>,
+    #[allow(non_snake_case)]
+    testId: String,
+    name: String,
+) -> Result<Test, String> {
+    state.write(move |service| service.rename_test(&testId, &name)).await
 }
 
 #[tauri::command]
-pub fn get_tests_by_tracker(
-    state: tauri::State<AppState>, 
+pub async fn set_test_coverage(
+    state: tauri::State<'_, AppState>,
+    #[allow(non_snake_case)]
+    testId: String,
+    #[allow(non_snake_case)]
+    coverageData: Vec<TestCoverageInput>,
+) -> Result<Test, String> {
+    state.write(move |service| service.set_test_coverage(&testId, coverageData)).await
+}
+
+#[tauri::command]
+pub async fn get_tests_by_tracker(
+    state: tauri::State<'_, AppState>,
     #[allow(non_snake_case)]
     trackerId: String
 ) -> Result<Vec<Test>, String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    db.get_tests_by_tracker(&trackerId)
-        .map_err(|e| e.to_string())
+    state.read(move |service| service.get_tests_by_tracker(&trackerId)).await
 }
 
 #[tauri::command]
-pub fn get_test_details(
-    state: tauri::State<AppState>,
+pub async fn get_test_details(
+    state: tauri::State<'_, AppState>,
     #[allow(non_snake_case)]
     testId: String,
 ) -> Result<Option<TestDetails>, String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-
-    if let Some(test) = db.get_test(&testId).map_err(|e| e.to_string())? {
-        let coverage = db.get_test_coverage(&testId)
-            .map_err(|e| e.to_string())?;
-
-        let mut covered_topics = Vec::new();
-        for cov in coverage.iter() {
-            if let Some(unit_id) = &cov.unit_id {
-                let unit_topics = db.get_topics_by_unit(unit_id)
-                    .map_err(|e| e.to_string())?;
-                covered_topics.extend(unit_topics.iter().map(|t| t.name.clone()));
-            } else if let Some(topic_id) = &cov.topic_id {
-                if let Some(topic) = db.get_topic(topic_id)
-                    .map_err(|e| e.to_string())? {
-                    covered_topics.push(topic.name);
-                }
-            }
-        }
-
-        Ok(Some(TestDetails {
-            days_remaining: get_days_remaining(test.scheduled_date),
-            time_remaining: format_time_remaining(test.scheduled_date),
-            test,
-            coverage,
-            covered_topics,
-        }))
-    } else {
-        Ok(None)
-    }
+    state.read(move |service| service.get_test_details(&testId)).await
+}
+
+/// Exports the tests of a tracker, a semester or every tracker as an
+/// iCalendar file.
+#[tauri::command]
+pub async fn export_tests_calendar(
+    state: tauri::State<'_, AppState>,
+    scope: CalendarScope,
+) -> Result<ExportedFile, String> {
+    state.read(move |service| service.export_tests_calendar(&scope)).await
+}
+
+/// Reads an exam timetable and proposes a test per event, without saving
+/// anything. The user reviews types, coverage and duplicates, then passes
+/// the chosen tests to `import_calendar_tests`.
+#[tauri::command]
+pub async fn preview_calendar_import(
+    state: tauri::State<'_, AppState>,
+    #[allow(non_snake_case)]
+    trackerId: String,
+    ics: String,
+) -> Result<CalendarImportPreview, String> {
+    state.read(move |service| service.preview_calendar_import(&trackerId, &ics)).await
+}
+
+#[tauri::command]
+pub async fn import_calendar_tests(
+    state: tauri::State<'_, AppState>,
+    #[allow(non_snake_case)]
+    trackerId: String,
+    tests: Vec<NewTest>,
+) -> Result<Vec<Test>, String> {
+    state.write(move |service| service.import_calendar_tests(&trackerId, tests)).await
+}
+
+// ============================================================================
+// TEST TYPE COMMANDS
+// ============================================================================
+
+#[tauri::command]
+pub async fn get_test_types(state: tauri::State<'_, AppState>) -> Result<Vec<TestType>, String> {
+    state.read(move |service| service.get_test_types()).await
+}
+
+#[tauri::command]
+pub async fn create_test_type(
+    state: tauri::State<'_, AppState>,
+    name: String,
+    color: Option<String>,
+    #[allow(non_snake_case)]
+    priorityWindowDays: i32,
+    weight: f64,
+) -> Result<TestType, String> {
+    state.write(move |service| service.create_test_type(&name, color, priorityWindowDays, weight)).await
+}
+
+#[tauri::command]
+pub async fn update_test_type(
+    state: tauri::State<'_, AppState>,
+    id: String,
+    name: String,
+    color: Option<String>,
+    #[allow(non_snake_case)]
+    priorityWindowDays: i32,
+    weight: f64,
+) -> Result<TestType, String> {
+    state.write(move |service| service.update_test_type(&id, &name, color.as_deref(), priorityWindowDays, weight)).await
+}
+
+/// Deletes a test type no test uses. Tests keep their type's ID, so
+/// deleting a type in use would leave them unlabelled.
+#[tauri::command]
+pub async fn delete_test_type(state: tauri::State<'_, AppState>, id: String) -> Result<(), String> {
+    state.write(move |service| service.delete_test_type(&id)).await
+}
+
+// ============================================================================
+// ASSIGNMENT COMMANDS
+// ============================================================================
+
+#[tauri::command]
+pub async fn create_assignment(
+    state: tauri::State<'_, AppState>,
+    #[allow(non_snake_case)]
+    trackerId: String,
+    assignment: AssignmentInput,
+) -> Result<Assignment, String> {
+    state.write(move |service| service.create_assignment(trackerId, assignment)).await
+}
+
+#[tauri::command]
+pub async fn get_assignments_by_tracker(
+    state: tauri::State<'_, AppState>,
+    #[allow(non_snake_case)]
+    trackerId: String,
+) -> Result<Vec<Assignment>, String> {
+    state.read(move |service| service.get_assignments_by_tracker(&trackerId)).await
+}
+
+#[tauri::command]
+pub async fn get_assignment_details(
+    state: tauri::State<'_, AppState>,
+    #[allow(non_snake_case)]
+    assignmentId: String,
+) -> Result<Option<AssignmentDetails>, String> {
+    state.read(move |service| service.get_assignment_details(&assignmentId)).await
+}
+
+#[tauri::command]
+pub async fn update_assignment(
+    state: tauri::State<'_, AppState>,
+    #[allow(non_snake_case)]
+    assignmentId: String,
+    assignment: AssignmentInput,
+) -> Result<Assignment, String> {
+    state.write(move |service| service.update_assignment(&assignmentId, assignment)).await
+}
+
+#[tauri::command]
+pub async fn set_assignment_status(
+    state: tauri::State<'_, AppState>,
+    #[allow(non_snake_case)]
+    assignmentId: String,
+    status: AssignmentStatus,
+) -> Result<Assignment, String> {
+    state.write(move |service| service.set_assignment_status(&assignmentId, status)).await
+}
+
+/// Records the marks for an assignment, which also marks it as graded.
+#[tauri::command]
+pub async fn grade_assignment(
+    state: tauri::State<'_, AppState>,
+    #[allow(non_snake_case)]
+    assignmentId: String,
+    #[allow(non_snake_case)]
+    marksObtained: f64,
+    #[allow(non_snake_case)]
+    maxMarks: f64,
+) -> Result<Assignment, String> {
+    state.write(move |service| service.grade_assignment(&assignmentId, marksObtained, maxMarks)).await
+}
+
+#[tauri::command]
+pub async fn delete_assignment(
+    state: tauri::State<'_, AppState>,
+    #[allow(non_snake_case)]
+    assignmentId: String,
+) -> Result<(), String> {
+    state.write(move |service| service.delete_assignment(&assignmentId)).await
+}
+
+// ============================================================================
+// GRADE COMMANDS
+// ============================================================================
+
+/// Records the marks scored on a test. Passing `None` for both clears them.
+#[tauri::command]
+pub async fn record_test_marks(
+    state: tauri::State<'_, AppState>,
+    #[allow(non_snake_case)]
+    testId: String,
+    #[allow(non_snake_case)]
+    marksObtained: Option<f64>,
+    #[allow(non_snake_case)]
+    maxMarks: Option<f64>,
+) -> Result<Test, String> {
+    state.write(move |service| service.record_test_marks(&testId, marksObtained, maxMarks)).await
+}
+
+/// Sets the subject a test's marks count towards, or none.
+#[tauri::command]
+pub async fn set_test_subject(
+    state: tauri::State<'_, AppState>,
+    #[allow(non_snake_case)]
+    testId: String,
+    #[allow(non_snake_case)]
+    subjectId: Option<String>,
+) -> Result<Test, String> {
+    state.write(move |service| service.set_test_subject(&testId, subjectId.as_deref())).await
+}
+
+#[tauri::command]
+pub async fn set_subject_grading(
+    state: tauri::State<'_, AppState>,
+    #[allow(non_snake_case)]
+    subjectId: String,
+    credits: Option<f64>,
+    #[allow(non_snake_case)]
+    evaluationSchemeId: Option<String>,
+) -> Result<Subject, String> {
+    state.write(move |service| service.set_subject_grading(&subjectId, credits, evaluationSchemeId.as_deref())).await
+}
+
+#[tauri::command]
+pub async fn get_grading_scales(state: tauri::State<'_, AppState>) -> Result<Vec<GradingScale>, String> {
+    state.read(move |service| service.get_grading_scales()).await
+}
+
+#[tauri::command]
+pub async fn create_grading_scale(
+    state: tauri::State<'_, AppState>,
+    scale: GradingScaleInput,
+) -> Result<GradingScale, String> {
+    state.write(move |service| service.create_grading_scale(scale)).await
+}
+
+#[tauri::command]
+pub async fn update_grading_scale(
+    state: tauri::State<'_, AppState>,
+    id: String,
+    scale: GradingScaleInput,
+) -> Result<GradingScale, String> {
+    state.write(move |service| service.update_grading_scale(&id, scale)).await
+}
+
+#[tauri::command]
+pub async fn delete_grading_scale(state: tauri::State<'_, AppState>, id: String) -> Result<(), String> {
+    state.write(move |service| service.delete_grading_scale(&id)).await
+}
+
+#[tauri::command]
+pub async fn get_evaluation_schemes(state: tauri::State<'_, AppState>) -> Result<Vec<EvaluationScheme>, String> {
+    state.read(move |service| service.get_evaluation_schemes()).await
+}
+
+#[tauri::command]
+pub async fn create_evaluation_scheme(
+    state: tauri::State<'_, AppState>,
+    scheme: EvaluationSchemeInput,
+) -> Result<EvaluationScheme, String> {
+    state.write(move |service| service.create_evaluation_scheme(scheme)).await
+}
+
+#[tauri::command]
+pub async fn update_evaluation_scheme(
+    state: tauri::State<'_, AppState>,
+    id: String,
+    scheme: EvaluationSchemeInput,
+) -> Result<EvaluationScheme, String> {
+    state.write(move |service| service.update_evaluation_scheme(&id, scheme)).await
+}
+
+#[tauri::command]
+pub async fn delete_evaluation_scheme(state: tauri::State<'_, AppState>, id: String) -> Result<(), String> {
+    state.write(move |service| service.delete_evaluation_scheme(&id)).await
+}
+
+/// Subject grades and the SGPA for one semester.
+#[tauri::command]
+pub async fn get_semester_grades(
+    state: tauri::State<'_, AppState>,
+    #[allow(non_snake_case)]
+    semesterId: String,
+) -> Result<SemesterGrades, String> {
+    state.read(move |service| service.get_semester_grades(&semesterId)).await
+}
+
+/// Grades for every semester with the cumulative CGPA.
+#[tauri::command]
+pub async fn get_grade_report(state: tauri::State<'_, AppState>) -> Result<GradeReport, String> {
+    state.read(move |service| service.get_grade_report()).await
+}
+
+/// What a subject needs on its remaining tests to earn `targetGrade`.
+#[tauri::command]
+pub async fn calculate_grade_target(
+    state: tauri::State<'_, AppState>,
+    #[allow(non_snake_case)]
+    subjectId: String,
+    #[allow(non_snake_case)]
+    targetGrade: String,
+) -> Result<SubjectTarget, String> {
+    state.read(move |service| service.calculate_grade_target(&subjectId, &targetGrade)).await
+}
+
+/// The same target grade worked out for every subject in a semester.
+#[tauri::command]
+pub async fn calculate_semester_targets(
+    state: tauri::State<'_, AppState>,
+    #[allow(non_snake_case)]
+    semesterId: String,
+    #[allow(non_snake_case)]
+    targetGrade: String,
+) -> Result<SemesterTargets, String> {
+    state.read(move |service| service.calculate_semester_targets(&semesterId, &targetGrade)).await
+}
+
+// ============================================================================
+// STUDY PLAN COMMANDS
+// ============================================================================
+
+#[tauri::command]
+pub async fn get_study_plan(
+    state: tauri::State<'_, AppState>,
+    #[allow(non_snake_case)]
+    trackerId: String,
+) -> Result<Option<StudyPlan>, String> {
+    state.read(move |service| service.get_study_plan(&trackerId)).await
+}
+
+/// Saves the daily capacity and plans every incomplete topic before its
+/// test. From then on the plan rebalances as topics and tests change.
+#[tauri::command]
+pub async fn generate_study_plan(
+    state: tauri::State<'_, AppState>,
+    #[allow(non_snake_case)]
+    trackerId: String,
+    settings: PlanSettings,
+) -> Result<StudyPlan, String> {
+    state.write(move |service| service.generate_study_plan(&trackerId, settings)).await
+}
+
+#[tauri::command]
+pub async fn delete_study_plan(
+    state: tauri::State<'_, AppState>,
+    #[allow(non_snake_case)]
+    trackerId: String,
+) -> Result<(), String> {
+    state.write(move |service| service.delete_study_plan(&trackerId)).await
+}
+
+// ============================================================================
+// AGENDA COMMANDS
+// ============================================================================
+
+/// Today's ranked agenda across every tracker in a semester, or across all
+/// semesters when none is given.
+#[tauri::command]
+pub async fn get_today(
+    state: tauri::State<'_, AppState>,
+    #[allow(non_snake_case)]
+    semesterId: Option<String>,
+) -> Result<Agenda, String> {
+    state.read(move |service| service.get_today(semesterId.as_deref())).await
+}
+
+// ============================================================================
+// DATABASE COMMANDS
+// ============================================================================
+
+/// Connections recovered after a command panicked, since the app started.
+#[tauri::command]
+pub fn get_database_incidents(state: tauri::State<AppState>) -> Result<Vec<DbIncident>, String> {
+    Ok(state.pool.incidents())
+}
+
+/// SQLite's integrity and foreign key checks plus the app's own invariants.
+#[tauri::command]
+pub async fn check_database(state: tauri::State<'_, AppState>) -> Result<DatabaseReport, String> {
+    state.read(move |service| service.check_database()).await
+}
+
+/// Fixes what `check_database` found, where that can be done safely.
+#[tauri::command]
+pub async fn repair_database(state: tauri::State<'_, AppState>) -> Result<RepairReport, String> {
+    state.write(move |service| service.repair_database()).await
 }
 
 // ============================================================================
@@ -320,17 +568,13 @@ pub fn get_test_details(
 // ============================================================================
 
 #[tauri::command]
-pub fn get_theme(state: tauri::State<AppState>) -> Result<String, String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    db.get_theme_preference().map_err(|e| e.to_string())
+pub async fn get_theme(state: tauri::State<'_, AppState>) -> Result<String, String> {
+    state.read(move |service| service.get_theme()).await
 }
 
 #[tauri::command]
-pub fn set_theme(state: tauri::State<AppState>, theme: String) -> Result<String, String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    db.set_theme_preference(&theme)
-        .map_err(|e| e.to_string())?;
-    Ok(theme)
+pub async fn set_theme(state: tauri::State<'_, AppState>, theme: String) -> Result<String, String> {
+    state.write(move |service| service.set_theme(theme)).await
 }
 
 // ============================================================================
@@ -338,41 +582,32 @@ pub fn set_theme(state: tauri::State<AppState>, theme: String) -> Result<String,
 // ============================================================================
 
 #[tauri::command]
-pub fn create_subject(
-    state: tauri::State<AppState>,
+pub async fn create_subject(
+    state: tauri::State<'_, AppState>,
     #[allow(non_snake_case)]
     trackerId: String,
     name: String,
 ) -> Result<Subject, String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    let id = Uuid::new_v4().to_string();
-    let now = get_current_timestamp();
-    db.create_subject(id, trackerId, name, now)
-        .map_err(|e| e.to_string())
+    state.write(move |service| service.create_subject(trackerId, name)).await
 }
 
 #[tauri::command]
-pub fn update_subject(
-    state: tauri::State<AppState>,
+pub async fn update_subject(
+    state: tauri::State<'_, AppState>,
     #[allow(non_snake_case)]
     subjectId: String,
     name: String,
 ) -> Result<(), String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    let now = get_current_timestamp();
-    db.update_subject(&subjectId, &name, now)
-        .map_err(|e| e.to_string())
+    state.write(move |service| service.update_subject(&subjectId, &name)).await
 }
 
 #[tauri::command]
-pub fn delete_subject(
-    state: tauri::State<AppState>,
+pub async fn delete_subject(
+    state: tauri::State<'_, AppState>,
     #[allow(non_snake_case)]
     subjectId: String,
 ) -> Result<(), String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    db.delete_subject(&subjectId)
-        .map_err(|e| e.to_string())
+    state.write(move |service| service.delete_subject(&subjectId)).await
 }
 
 // ============================================================================
@@ -380,219 +615,152 @@ pub fn delete_subject(
 // ============================================================================
 
 #[tauri::command]
-pub fn create_topic(
-    state: tauri::State<AppState>,
+pub async fn create_topic(
+    state: tauri::State<'_, AppState>,
     #[allow(non_snake_case)]
     unitId: String,
     name: String,
 ) -> Result<Topic, String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    let id = Uuid::new_v4().to_string();
-    let now = get_current_timestamp();
-    
-    // Get the max order for this unit
-    let topics = db.get_topics_by_unit(&unitId).map_err(|e| e.to_string())?;
-    let order = topics.iter().map(|t| t.order).max().unwrap_or(-1) + 1;
-    
-    db.create_topic(id, unitId, name, order, now)
-        .map_err(|e| e.to_string())
+    state.write(move |service| service.create_topic(unitId, name)).await
 }
 
 #[tauri::command]
-pub fn update_topic(
-    state: tauri::State<AppState>,
+pub async fn update_topic(
+    state: tauri::State<'_, AppState>,
     #[allow(non_snake_case)]
     topicId: String,
     name: String,
 ) -> Result<(), String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    let now = get_current_timestamp();
-    db.update_topic(&topicId, &name, now)
-        .map_err(|e| e.to_string())
+    state.write(move |service| service.update_topic(&topicId, &name)).await
 }
 
 #[tauri::command]
-pub fn delete_topic(
-    state: tauri::State<AppState>,
+pub async fn delete_topic(
+    state: tauri::State<'_, AppState>,
     #[allow(non_snake_case)]
     topicId: String,
 ) -> Result<(), String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    db.delete_topic(&topicId)
-        .map_err(|e| e.to_string())
+    state.write(move |service| service.delete_topic(&topicId)).await
 }
 
 // ============================================================================
 // SYLLABUS EXPORT/IMPORT COMMANDS
 // ============================================================================
 
-#[derive(serde::Serialize, serde::Deserialize)]
-pub struct SyllabusExport {
-    name: String,
-    description: Option<String>,
-    color: Option<String>,
-    version: String,
-    subjects: Vec<SyllabusSubject>,
+#[tauri::command]
+pub async fn export_syllabus(
+    state: tauri::State<'_, AppState>,
+    #[allow(non_snake_case)]
+    trackerId: String,
+) -> Result<SyllabusExport, String> {
+    state.read(move |service| service.export_syllabus(&trackerId)).await
 }
 
-#[derive(serde::Serialize, serde::Deserialize)]
-pub struct SyllabusSubject {
-    name: String,
-    units: Vec<SyllabusUnit>,
+/// Renders a tracker as JSON, a Markdown checklist, CSV or a printable HTML
+/// sheet. Unlike `export_syllabus`, the Markdown, CSV and HTML formats
+/// include completion.
+#[tauri::command]
+pub async fn export_tracker(
+    state: tauri::State<'_, AppState>,
+    #[allow(non_snake_case)]
+    trackerId: String,
+    format: ExportFormat,
+) -> Result<ExportedFile, String> {
+    state.read(move |service| service.export_tracker(&trackerId, format)).await
 }
 
-#[derive(serde::Serialize, serde::Deserialize)]
-pub struct SyllabusUnit {
-    name: String,
-    topics: Vec<String>,
+#[tauri::command]
+pub async fn import_syllabus(
+    state: tauri::State<'_, AppState>,
+    #[allow(non_snake_case)]
+    semesterId: String,
+    syllabus: serde_json::Value,
+) -> Result<Tracker, String> {
+    state.write(move |service| service.import_syllabus(semesterId, syllabus)).await
 }
 
 #[tauri::command]
-pub fn export_syllabus(
-    state: tauri::State<AppState>,
+pub async fn preview_merge(
+    state: tauri::State<'_, AppState>,
     #[allow(non_snake_case)]
     trackerId: String,
-) -> Result<SyllabusExport, String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    
-    // Get tracker info
-    let tracker = db.get_tracker(&trackerId)
-        .map_err(|e| e.to_string())?
-        .ok_or("Tracker not found")?;
-    
-    // Get all subjects
-    let subjects = db.get_subjects_by_tracker(&trackerId)
-        .map_err(|e| e.to_string())?;
-    
-    let mut syllabus_subjects = Vec::new();
-    
-    for subject in subjects {
-        let units = db.get_units_by_subject(&subject.id)
-            .map_err(|e| e.to_string())?;
-        
-        let mut syllabus_units = Vec::new();
-        
-        for unit in units {
-            let topics = db.get_topics_by_unit(&unit.id)
-                .map_err(|e| e.to_string())?;
-            
-            let topic_names: Vec<String> = topics.iter()
-                .map(|t| t.name.clone())
-                .collect();
-            
-            syllabus_units.push(SyllabusUnit {
-                name: unit.name,
-                topics: topic_names,
-            });
-        }
-        
-        syllabus_subjects.push(SyllabusSubject {
-            name: subject.name,
-            units: syllabus_units,
-        });
-    }
-    
-    Ok(SyllabusExport {
-        name: tracker.name,
-        description: tracker.description,
-        color: tracker.color,
-        version: "1.0".to_string(),
-        subjects: syllabus_subjects,
-    })
+    syllabus: serde_json::Value,
+) -> Result<MergePlan, String> {
+    state.read(move |service| service.preview_merge(&trackerId, syllabus)).await
 }
 
 #[tauri::command]
-pub fn import_syllabus(
-    state: tauri::State<AppState>,
+pub async fn merge_syllabus(
+    state: tauri::State<'_, AppState>,
     #[allow(non_snake_case)]
-    semesterId: String,
-    syllabus: SyllabusExport,
+    trackerId: String,
+    syllabus: serde_json::Value,
+    fingerprint: String,
+    #[allow(non_snake_case)]
+    deleteRemoved: bool,
 ) -> Result<Tracker, String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    let now = get_current_timestamp();
-    
-    // Create tracker
-    let tracker_id = Uuid::new_v4().to_string();
-    let tracker = db.create_tracker(
-        tracker_id.clone(),
-        semesterId,
-        syllabus.name,
-        syllabus.description,
-        syllabus.color,
-        now,
-    ).map_err(|e| e.to_string())?;
-    
-    // Create subjects, units, and topics
-    for subject_data in syllabus.subjects {
-        let subject_id = Uuid::new_v4().to_string();
-        db.create_subject(subject_id.clone(), tracker_id.clone(), subject_data.name, now)
-            .map_err(|e| e.to_string())?;
-        
-        for (unit_order, unit_data) in subject_data.units.iter().enumerate() {
-            let unit_id = Uuid::new_v4().to_string();
-            db.create_unit(
-                unit_id.clone(),
-                subject_id.clone(),
-                unit_data.name.clone(),
-                unit_order as i32,
-                now,
-            ).map_err(|e| e.to_string())?;
-            
-            for (topic_order, topic_name) in unit_data.topics.iter().enumerate() {
-                let topic_id = Uuid::new_v4().to_string();
-                db.create_topic(
-                    topic_id,
-                    unit_id.clone(),
-                    topic_name.clone(),
-                    topic_order as i32,
-                    now,
-                ).map_err(|e| e.to_string())?;
-            }
-        }
-    }
-    
-    // Update tracker statistics
-    db.update_tracker_statistics(&tracker_id, now)
-        .map_err(|e| e.to_string())?;
-    
-    Ok(tracker)
+    state.write(move |service| service.merge_syllabus(&trackerId, syllabus, &fingerprint, deleteRemoved)).await
 }
 
 // ============================================================================
-// HELPER STRUCTS (for API responses)
+// TEXT EDITING COMMANDS
 // ============================================================================
 
-#[derive(serde::Serialize)]
-pub struct SubjectData {
-    pub subject: Subject,
-    pub units: Vec<UnitData>,
+#[tauri::command]
+pub async fn get_tracker_as_text(
+    state: tauri::State<'_, AppState>,
+    #[allow(non_snake_case)]
+    trackerId: String,
+) -> Result<String, String> {
+    state.read(move |service| service.get_tracker_as_text(&trackerId)).await
+}
+
+#[tauri::command]
+pub async fn preview_tracker_text(
+    state: tauri::State<'_, AppState>,
+    #[allow(non_snake_case)]
+    trackerId: String,
+    text: String,
+) -> Result<MergePlan, String> {
+    state.read(move |service| service.preview_tracker_text(&trackerId, &text)).await
 }
 
-#[derive(serde::Serialize)]
-pub struct UnitData {
-    pub unit: Unit,
-    pub topics: Vec<Topic>,
+/// Replaces the tracker's outline with `text`, keeping the IDs and
+/// completion of matched lines and deleting lines that were removed.
+/// `fingerprint` comes from the preview the user approved.
+#[tauri::command]
+pub async fn apply_tracker_text(
+    state: tauri::State<'_, AppState>,
+    #[allow(non_snake_case)]
+    trackerId: String,
+    text: String,
+    fingerprint: String,
+) -> Result<MergeSummary, String> {
+    state.write(move |service| service.apply_tracker_text(&trackerId, &text, &fingerprint)).await
 }
 
-#[derive(serde::Serialize)]
-pub struct TrackerData {
-    pub subjects: Vec<SubjectData>,
-    pub progress: TrackerProgress,
-    pub all_tests: Vec<Test>,
-    pub priority_tests: Vec<PriorityTest>,
+#[tauri::command]
+pub async fn diff_trackers(
+    state: tauri::State<'_, AppState>,
+    #[allow(non_snake_case)]
+    oldTrackerId: String,
+    #[allow(non_snake_case)]
+    newTrackerId: String,
+) -> Result<SyllabusDiff, String> {
+    state.read(move |service| service.diff_trackers(&oldTrackerId, &newTrackerId)).await
 }
 
-#[derive(serde::Serialize)]
-pub struct TestDetails {
-    pub test: Test,
-    pub coverage: Vec<TestCoverage>,
-    pub covered_topics: Vec<String>,
-    pub days_remaining: i32,
-    pub time_remaining: String,
+#[tauri::command]
+pub async fn diff_tracker_with_syllabus(
+    state: tauri::State<'_, AppState>,
+    #[allow(non_snake_case)]
+    trackerId: String,
+    syllabus: serde_json::Value,
+) -> Result<SyllabusDiff, String> {
+    state.read(move |service| service.diff_tracker_with_syllabus(&trackerId, syllabus)).await
 }
 
-#[derive(serde::Deserialize)]
-pub struct TestCoverageInput {
-    pub unit_id: Option<String>,
-    pub topic_id: Option<String>,
+#[tauri::command]
+pub fn preview_import(source: ImportSource) -> Result<ImportPreview, String> {
+    Ok(crate::syllabus::preview_import(source))
 }
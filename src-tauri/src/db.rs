@@ -1,23 +1,74 @@
-use rusqlite::{Connection, Result as SqliteResult, params};
-use std::path::PathBuf;
+use rusqlite::{Connection, OpenFlags, Result as SqliteResult, params};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 use crate::models::*;
 
+pub const DEFAULT_PRIORITY_WINDOW_DAYS: i32 = 7;
+
+/// ID, name, color and weight of the test types every database starts with.
+const BUILTIN_TEST_TYPES: [(&str, &str, &str, f64); 4] = [
+    ("lab_practical", "Lab Practical", "#10b981", 1.0),
+    ("class_test", "Class Test", "#3b82f6", 1.0),
+    ("isa", "ISA", "#f59e0b", 2.0),
+    ("esa", "ESA", "#ef4444", 3.0),
+];
+
+/// Live subject, unit and topic counts per tracker.
+const TRACKER_COUNTS_SQL: &str = r#"SELECT tr.id, COUNT(DISTINCT s.id) AS subjects, COUNT(DISTINCT u.id) AS units, COUNT(t.id) AS topics
+    FROM trackers tr
+    LEFT JOIN subjects s ON s.tracker_id = tr.id
+    LEFT JOIN units u ON u.subject_id = s.id
+    LEFT JOIN topics t ON t.unit_id = u.id
+    GROUP BY tr.id"#;
+
+/// How long a connection waits on a lock held by another one.
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Room for every cached per-request query, so none evicts another (the
+/// default holds 16).
+const STATEMENT_CACHE_CAPACITY: usize = 64;
+
 pub struct Database {
     conn: Connection,
 }
 
 impl Database {
+    /// Opens the read-write connection and brings the schema up to date.
     pub fn new(db_path: PathBuf) -> SqliteResult<Self> {
         let conn = Connection::open(&db_path)?;
         conn.execute_batch("PRAGMA foreign_keys = ON")?;
+        // WAL lets read connections run while the writer is busy. In-memory
+        // databases stay in "memory" mode, which is fine for tests.
+        conn.pragma_update_and_check(None, "journal_mode", "WAL", |row| row.get::<_, String>(0))?;
+        conn.pragma_update(None, "synchronous", "NORMAL")?;
+        conn.busy_timeout(BUSY_TIMEOUT)?;
+        conn.set_prepared_statement_cache_capacity(STATEMENT_CACHE_CAPACITY);
         let db = Database { conn };
         db.init_schema()?;
         Ok(db)
     }
 
+    /// Opens a read-only connection to a database `new` already set up.
+    pub fn open_reader(db_path: &Path) -> SqliteResult<Self> {
+        let conn = Connection::open_with_flags(
+            db_path,
+            OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX | OpenFlags::SQLITE_OPEN_URI,
+        )?;
+        conn.busy_timeout(BUSY_TIMEOUT)?;
+        conn.set_prepared_statement_cache_capacity(STATEMENT_CACHE_CAPACITY);
+        Ok(Database { conn })
+    }
+
     fn init_schema(&self) -> SqliteResult<()> {
         // First, handle trackers table migration/creation
         self.migrate_trackers_table()?;
+
+        // Counts cached before the triggers existed may have drifted
+        let has_count_triggers: bool = self.conn.query_row(
+            "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'trigger' AND name = 'trg_topics_insert')",
+            [],
+            |row| row.get(0),
+        )?;
         
         // Then create other tables with IF NOT EXISTS
         self.conn.execute_batch(
@@ -33,6 +84,8 @@ impl Database {
                 id TEXT PRIMARY KEY,
                 tracker_id TEXT NOT NULL,
                 name TEXT NOT NULL,
+                credits REAL,
+                evaluation_scheme_id TEXT,
                 created_at INTEGER NOT NULL,
                 updated_at INTEGER NOT NULL,
                 FOREIGN KEY (tracker_id) REFERENCES trackers(id) ON DELETE CASCADE
@@ -59,12 +112,24 @@ impl Database {
                 FOREIGN KEY (unit_id) REFERENCES units(id) ON DELETE CASCADE
             );
 
+            CREATE TABLE IF NOT EXISTS test_types (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                color TEXT,
+                priority_window_days INTEGER NOT NULL,
+                weight REAL NOT NULL,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL
+            );
+
             CREATE TABLE IF NOT EXISTS tests (
                 id TEXT PRIMARY KEY,
                 tracker_id TEXT NOT NULL,
                 name TEXT NOT NULL,
                 test_type TEXT NOT NULL,
                 scheduled_date INTEGER NOT NULL,
+                marks_obtained REAL,
+                max_marks REAL,
                 created_at INTEGER NOT NULL,
                 updated_at INTEGER NOT NULL,
                 FOREIGN KEY (tracker_id) REFERENCES trackers(id) ON DELETE CASCADE
@@ -80,20 +145,275 @@ impl Database {
                 FOREIGN KEY (topic_id) REFERENCES topics(id) ON DELETE CASCADE
             );
 
+            CREATE TABLE IF NOT EXISTS assignments (
+                id TEXT PRIMARY KEY,
+                tracker_id TEXT NOT NULL,
+                subject_id TEXT,
+                name TEXT NOT NULL,
+                description TEXT,
+                due_date INTEGER NOT NULL,
+                status TEXT NOT NULL DEFAULT 'todo',
+                marks_obtained REAL,
+                max_marks REAL,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL,
+                FOREIGN KEY (tracker_id) REFERENCES trackers(id) ON DELETE CASCADE,
+                FOREIGN KEY (subject_id) REFERENCES subjects(id) ON DELETE SET NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS assignment_topics (
+                assignment_id TEXT NOT NULL,
+                topic_id TEXT NOT NULL,
+                PRIMARY KEY (assignment_id, topic_id),
+                FOREIGN KEY (assignment_id) REFERENCES assignments(id) ON DELETE CASCADE,
+                FOREIGN KEY (topic_id) REFERENCES topics(id) ON DELETE CASCADE
+            );
+
+            CREATE TABLE IF NOT EXISTS grading_scales (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS grade_bands (
+                scale_id TEXT NOT NULL,
+                letter TEXT NOT NULL,
+                min_percentage REAL NOT NULL,
+                grade_points REAL NOT NULL,
+                PRIMARY KEY (scale_id, letter),
+                FOREIGN KEY (scale_id) REFERENCES grading_scales(id) ON DELETE CASCADE
+            );
+
+            CREATE TABLE IF NOT EXISTS evaluation_schemes (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                grading_scale_id TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL,
+                FOREIGN KEY (grading_scale_id) REFERENCES grading_scales(id)
+            );
+
+            CREATE TABLE IF NOT EXISTS evaluation_components (
+                scheme_id TEXT NOT NULL,
+                test_type_id TEXT NOT NULL,
+                weight REAL NOT NULL,
+                best_of INTEGER,
+                PRIMARY KEY (scheme_id, test_type_id),
+                FOREIGN KEY (scheme_id) REFERENCES evaluation_schemes(id) ON DELETE CASCADE,
+                FOREIGN KEY (test_type_id) REFERENCES test_types(id) ON DELETE RESTRICT
+            );
+
+            CREATE TABLE IF NOT EXISTS plan_settings (
+                tracker_id TEXT PRIMARY KEY,
+                capacity_unit TEXT NOT NULL,
+                daily_capacity INTEGER NOT NULL,
+                minutes_per_topic INTEGER NOT NULL,
+                rest_days TEXT NOT NULL DEFAULT '',
+                updated_at INTEGER NOT NULL,
+                FOREIGN KEY (tracker_id) REFERENCES trackers(id) ON DELETE CASCADE
+            );
+
+            CREATE TABLE IF NOT EXISTS plan_items (
+                id TEXT PRIMARY KEY,
+                tracker_id TEXT NOT NULL,
+                topic_id TEXT NOT NULL,
+                test_id TEXT,
+                day TEXT,
+                minutes INTEGER NOT NULL,
+                created_at INTEGER NOT NULL,
+                FOREIGN KEY (tracker_id) REFERENCES trackers(id) ON DELETE CASCADE,
+                FOREIGN KEY (topic_id) REFERENCES topics(id) ON DELETE CASCADE,
+                FOREIGN KEY (test_id) REFERENCES tests(id) ON DELETE SET NULL
+            );
+
             CREATE TABLE IF NOT EXISTS theme_preference (
                 key TEXT PRIMARY KEY,
                 value TEXT NOT NULL
             );
 
+            -- Keep the cached tracker counts in step with every insert,
+            -- delete and move. Deletes subtract in BEFORE triggers while the
+            -- children can still be counted; by the time cascades fire the
+            -- children's triggers, the parent is gone and they match nothing.
+            CREATE TRIGGER IF NOT EXISTS trg_subjects_insert AFTER INSERT ON subjects BEGIN
+                UPDATE trackers SET total_subjects = total_subjects + 1 WHERE id = NEW.tracker_id;
+            END;
+            CREATE TRIGGER IF NOT EXISTS trg_subjects_delete BEFORE DELETE ON subjects BEGIN
+                UPDATE trackers SET
+                    total_subjects = total_subjects - 1,
+                    total_units = total_units - (SELECT COUNT(*) FROM units WHERE subject_id = OLD.id),
+                    total_topics = total_topics - (SELECT COUNT(*) FROM topics t JOIN units u ON u.id = t.unit_id WHERE u.subject_id = OLD.id)
+                WHERE id = OLD.tracker_id;
+            END;
+            CREATE TRIGGER IF NOT EXISTS trg_subjects_move AFTER UPDATE OF tracker_id ON subjects
+            WHEN OLD.tracker_id IS NOT NEW.tracker_id BEGIN
+                UPDATE trackers SET
+                    total_subjects = total_subjects + (CASE WHEN id = NEW.tracker_id THEN 1 ELSE -1 END),
+                    total_units = total_units + (CASE WHEN id = NEW.tracker_id THEN 1 ELSE -1 END)
+                        * (SELECT COUNT(*) FROM units WHERE subject_id = NEW.id),
+                    total_topics = total_topics + (CASE WHEN id = NEW.tracker_id THEN 1 ELSE -1 END)
+                        * (SELECT COUNT(*) FROM topics t JOIN units u ON u.id = t.unit_id WHERE u.subject_id = NEW.id)
+                WHERE id IN (OLD.tracker_id, NEW.tracker_id);
+            END;
+
+            CREATE TRIGGER IF NOT EXISTS trg_units_insert AFTER INSERT ON units BEGIN
+                UPDATE trackers SET total_units = total_units + 1
+                WHERE id = (SELECT tracker_id FROM subjects WHERE id = NEW.subject_id);
+            END;
+            CREATE TRIGGER IF NOT EXISTS trg_units_delete BEFORE DELETE ON units BEGIN
+                UPDATE trackers SET
+                    total_units = total_units - 1,
+                    total_topics = total_topics - (SELECT COUNT(*) FROM topics WHERE unit_id = OLD.id)
+                WHERE id = (SELECT tracker_id FROM subjects WHERE id = OLD.subject_id);
+            END;
+            CREATE TRIGGER IF NOT EXISTS trg_units_move AFTER UPDATE OF subject_id ON units
+            WHEN OLD.subject_id IS NOT NEW.subject_id BEGIN
+                UPDATE trackers SET
+                    total_units = total_units - 1,
+                    total_topics = total_topics - (SELECT COUNT(*) FROM topics WHERE unit_id = NEW.id)
+                WHERE id = (SELECT tracker_id FROM subjects WHERE id = OLD.subject_id);
+                UPDATE trackers SET
+                    total_units = total_units + 1,
+                    total_topics = total_topics + (SELECT COUNT(*) FROM topics WHERE unit_id = NEW.id)
+                WHERE id = (SELECT tracker_id FROM subjects WHERE id = NEW.subject_id);
+            END;
+
+            CREATE TRIGGER IF NOT EXISTS trg_topics_insert AFTER INSERT ON topics BEGIN
+                UPDATE trackers SET total_topics = total_topics + 1
+                WHERE id = (SELECT s.tracker_id FROM units u JOIN subjects s ON s.id = u.subject_id WHERE u.id = NEW.unit_id);
+            END;
+            CREATE TRIGGER IF NOT EXISTS trg_topics_delete BEFORE DELETE ON topics BEGIN
+                UPDATE trackers SET total_topics = total_topics - 1
+                WHERE id = (SELECT s.tracker_id FROM units u JOIN subjects s ON s.id = u.subject_id WHERE u.id = OLD.unit_id);
+            END;
+            CREATE TRIGGER IF NOT EXISTS trg_topics_move AFTER UPDATE OF unit_id ON topics
+            WHEN OLD.unit_id IS NOT NEW.unit_id BEGIN
+                UPDATE trackers SET total_topics = total_topics - 1
+                WHERE id = (SELECT s.tracker_id FROM units u JOIN subjects s ON s.id = u.subject_id WHERE u.id = OLD.unit_id);
+                UPDATE trackers SET total_topics = total_topics + 1
+                WHERE id = (SELECT s.tracker_id FROM units u JOIN subjects s ON s.id = u.subject_id WHERE u.id = NEW.unit_id);
+            END;
+
             CREATE INDEX IF NOT EXISTS idx_trackers_semester ON trackers(semester_id);
             CREATE INDEX IF NOT EXISTS idx_subjects_tracker ON subjects(tracker_id);
             CREATE INDEX IF NOT EXISTS idx_units_subject ON units(subject_id);
             CREATE INDEX IF NOT EXISTS idx_topics_unit ON topics(unit_id);
             CREATE INDEX IF NOT EXISTS idx_tests_tracker ON tests(tracker_id);
             CREATE INDEX IF NOT EXISTS idx_test_coverage_test ON test_coverage(test_id);
+            CREATE INDEX IF NOT EXISTS idx_assignments_tracker ON assignments(tracker_id);
+            CREATE INDEX IF NOT EXISTS idx_plan_items_tracker_day ON plan_items(tracker_id, day);
             "#,
         )?;
-        
+
+        // Columns added after the first release
+        self.add_missing_columns("subjects", &[("credits", "REAL"), ("evaluation_scheme_id", "TEXT")])?;
+        let added = self.add_missing_columns(
+            "tests",
+            &[
+                ("marks_obtained", "REAL"),
+                ("max_marks", "REAL"),
+                ("subject_id", "TEXT REFERENCES subjects(id) ON DELETE SET NULL"),
+                ("revision", "INTEGER NOT NULL DEFAULT 0"),
+            ],
+        )?;
+        if added.contains(&"subject_id") {
+            // Until now marks counted for the subjects their coverage lay in
+            self.assign_test_subjects_from_coverage(None)?;
+        }
+
+        self.seed_test_types()?;
+        self.seed_grading()?;
+
+        if !has_count_triggers {
+            self.recompute_all_statistics()?;
+        }
+
+        Ok(())
+    }
+
+    /// Adds the columns `table` lacks and returns the names of those added.
+    fn add_missing_columns<'a>(&self, table: &str, columns: &[(&'a str, &str)]) -> SqliteResult<Vec<&'a str>> {
+        let mut stmt = self.conn.prepare(&format!("PRAGMA table_info({})", table))?;
+        let existing: Vec<String> = stmt
+            .query_map([], |row| row.get::<_, String>(1))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut added = Vec::new();
+        for (name, column_type) in columns {
+            if !existing.iter().any(|c| c == name) {
+                self.conn.execute(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, name, column_type), [])?;
+                added.push(*name);
+            }
+        }
+        Ok(added)
+    }
+
+    fn is_empty(&self, table: &str) -> SqliteResult<bool> {
+        self.conn
+            .query_row(&format!("SELECT NOT EXISTS (SELECT 1 FROM {})", table), [], |row| row.get(0))
+    }
+
+    /// Adds the built-in test types to a new database. Their IDs are the
+    /// strings tests stored before types were user-defined. Seeding only an
+    /// empty table keeps deleted built-ins from coming back on restart.
+    fn seed_test_types(&self) -> SqliteResult<()> {
+        if !self.is_empty("test_types")? {
+            return Ok(());
+        }
+        for (id, name, color, weight) in BUILTIN_TEST_TYPES {
+            self.conn.execute(
+                "INSERT INTO test_types (id, name, color, priority_window_days, weight, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, 0, 0)",
+                params![id, name, color, DEFAULT_PRIORITY_WINDOW_DAYS, weight],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Adds a 10-point grading scale and an even ISA/ESA split to a new
+    /// database, so grades work before anything is configured.
+    fn seed_grading(&self) -> SqliteResult<()> {
+        if !self.is_empty("grading_scales")? {
+            return Ok(());
+        }
+        let bands = [("S", 90.0, 10.0), ("A", 80.0, 9.0), ("B", 70.0, 8.0), ("C", 60.0, 7.0), ("D", 50.0, 6.0), ("E", 40.0, 5.0), ("F", 0.0, 0.0)];
+        self.create_grading_scale(
+            "ten_point".to_string(),
+            &GradingScaleInput {
+                name: "10-point (S to F)".to_string(),
+                bands: bands
+                    .iter()
+                    .map(|(letter, min_percentage, grade_points)| GradeBand {
+                        letter: letter.to_string(),
+                        min_percentage: *min_percentage,
+                        grade_points: *grade_points,
+                    })
+                    .collect(),
+            },
+            0,
+        )?;
+
+        // Only refer to the built-in types that are still there
+        let components: Vec<EvaluationComponent> = ["isa", "esa"]
+            .iter()
+            .filter(|id| matches!(self.get_test_type(id), Ok(Some(_))))
+            .map(|id| EvaluationComponent {
+                test_type_id: id.to_string(),
+                weight: 50.0,
+                best_of: None,
+            })
+            .collect();
+        if !components.is_empty() {
+            self.create_evaluation_scheme(
+                "isa_esa".to_string(),
+                &EvaluationSchemeInput {
+                    name: "ISA 50 + ESA 50".to_string(),
+                    grading_scale_id: "ten_point".to_string(),
+                    components,
+                },
+                0,
+            )?;
+        }
         Ok(())
     }
 
@@ -146,6 +466,42 @@ impl Database {
         Ok(())
     }
 
+    /// Makes sure a connection is usable after a panic interrupted whatever
+    /// it was doing: rolls back a transaction left open and runs a quick
+    /// integrity check. Returns what's wrong if the check fails.
+    pub fn check_connection(&self) -> Result<(), String> {
+        if !self.conn.is_autocommit() {
+            self.conn.execute_batch("ROLLBACK").map_err(|e| e.to_string())?;
+        }
+        let mut stmt = self.conn.prepare("PRAGMA quick_check").map_err(|e| e.to_string())?;
+        let problems = stmt
+            .query_map([], |row| row.get::<_, String>(0))
+            .and_then(|rows| rows.collect::<SqliteResult<Vec<_>>>())
+            .map_err(|e| e.to_string())?;
+        if problems == ["ok"] {
+            Ok(())
+        } else {
+            Err(problems.join("; "))
+        }
+    }
+
+    /// Runs `f` inside a transaction, rolling back if it returns an error.
+    pub fn transaction<T, E>(&self, f: impl FnOnce(&Database) -> Result<T, E>) -> Result<T, E>
+    where
+        E: From<rusqlite::Error>,
+    {
+        let tx = self.conn.unchecked_transaction()?;
+        let result = f(self)?;
+        tx.commit()?;
+        Ok(result)
+    }
+
+    /// Drops cached statements so benchmarks can measure uncached reads.
+    #[cfg(test)]
+    pub fn flush_statement_cache(&self) {
+        self.conn.flush_prepared_statement_cache();
+    }
+
     // ========================================================================
     // SEMESTER OPERATIONS
     // ========================================================================
@@ -166,7 +522,7 @@ impl Database {
     pub fn get_all_semesters(&self) -> SqliteResult<Vec<Semester>> {
         let mut stmt = self
             .conn
-            .prepare("SELECT id, name, created_at, updated_at FROM semesters ORDER BY created_at DESC")?;
+            .prepare_cached("SELECT id, name, created_at, updated_at FROM semesters ORDER BY created_at DESC")?;
         let semesters = stmt.query_map([], |row| {
             Ok(Semester {
                 id: row.get(0)?,
@@ -208,7 +564,7 @@ impl Database {
     }
 
     pub fn get_trackers_by_semester(&self, semester_id: &str) -> SqliteResult<Vec<Tracker>> {
-        let mut stmt = self.conn.prepare(
+        let mut stmt = self.conn.prepare_cached(
             "SELECT id, semester_id, name, description, color, total_subjects, total_units, total_topics, created_at, updated_at FROM trackers WHERE semester_id = ?1 ORDER BY created_at DESC"
         )?;
         let trackers = stmt.query_map(params![semester_id], |row| {
@@ -231,7 +587,7 @@ impl Database {
     pub fn get_tracker(&self, id: &str) -> SqliteResult<Option<Tracker>> {
         let mut stmt = self
             .conn
-            .prepare("SELECT id, semester_id, name, description, color, total_subjects, total_units, total_topics, created_at, updated_at FROM trackers WHERE id = ?1")?;
+            .prepare_cached("SELECT id, semester_id, name, description, color, total_subjects, total_units, total_topics, created_at, updated_at FROM trackers WHERE id = ?1")?;
         let tracker = stmt.query_row(params![id], |row| {
             Ok(Tracker {
                 id: row.get(0)?,
@@ -267,27 +623,59 @@ impl Database {
             id,
             tracker_id,
             name,
+            credits: None,
+            evaluation_scheme_id: None,
             created_at: now,
             updated_at: now,
         })
     }
 
+    fn row_to_subject(row: &rusqlite::Row) -> SqliteResult<Subject> {
+        Ok(Subject {
+            id: row.get(0)?,
+            tracker_id: row.get(1)?,
+            name: row.get(2)?,
+            credits: row.get(3)?,
+            evaluation_scheme_id: row.get(4)?,
+            created_at: row.get(5)?,
+            updated_at: row.get(6)?,
+        })
+    }
+
     pub fn get_subjects_by_tracker(&self, tracker_id: &str) -> SqliteResult<Vec<Subject>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT id, tracker_id, name, created_at, updated_at FROM subjects WHERE tracker_id = ?1 ORDER BY created_at ASC"
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT id, tracker_id, name, credits, evaluation_scheme_id, created_at, updated_at FROM subjects WHERE tracker_id = ?1 ORDER BY created_at ASC"
         )?;
-        let subjects = stmt.query_map(params![tracker_id], |row| {
-            Ok(Subject {
-                id: row.get(0)?,
-                tracker_id: row.get(1)?,
-                name: row.get(2)?,
-                created_at: row.get(3)?,
-                updated_at: row.get(4)?,
-            })
-        })?;
+        let subjects = stmt.query_map(params![tracker_id], Self::row_to_subject)?;
         subjects.collect()
     }
 
+    pub fn get_subject(&self, id: &str) -> SqliteResult<Option<Subject>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT id, tracker_id, name, credits, evaluation_scheme_id, created_at, updated_at FROM subjects WHERE id = ?1"
+        )?;
+        match stmt.query_row(params![id], Self::row_to_subject) {
+            Ok(s) => Ok(Some(s)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Sets the credits a subject is worth and how its grade is computed.
+    pub fn set_subject_grading(
+        &self,
+        id: &str,
+        credits: Option<f64>,
+        evaluation_scheme_id: Option<&str>,
+        now: i64,
+    ) -> SqliteResult<()> {
+        self.conn.execute(
+            "UPDATE subjects SET credits = ?1, evaluation_scheme_id = ?2, updated_at = ?3 WHERE id = ?4",
+            params![credits, evaluation_scheme_id, now, id],
+        )?;
+        Ok(())
+    }
+
     pub fn update_subject(&self, id: &str, name: &str, now: i64) -> SqliteResult<()> {
         self.conn.execute(
             "UPDATE subjects SET name = ?1, updated_at = ?2 WHERE id = ?3",
@@ -323,23 +711,53 @@ impl Database {
         })
     }
 
+    fn row_to_unit(row: &rusqlite::Row) -> SqliteResult<Unit> {
+        Ok(Unit {
+            id: row.get(0)?,
+            subject_id: row.get(1)?,
+            name: row.get(2)?,
+            order: row.get(3)?,
+            created_at: row.get(4)?,
+            updated_at: row.get(5)?,
+        })
+    }
+
     pub fn get_units_by_subject(&self, subject_id: &str) -> SqliteResult<Vec<Unit>> {
-        let mut stmt = self.conn.prepare(
+        let mut stmt = self.conn.prepare_cached(
             "SELECT id, subject_id, name, \"order\", created_at, updated_at FROM units WHERE subject_id = ?1 ORDER BY \"order\" ASC"
         )?;
-        let units = stmt.query_map(params![subject_id], |row| {
-            Ok(Unit {
-                id: row.get(0)?,
-                subject_id: row.get(1)?,
-                name: row.get(2)?,
-                order: row.get(3)?,
-                created_at: row.get(4)?,
-                updated_at: row.get(5)?,
-            })
-        })?;
+        let units = stmt.query_map(params![subject_id], Self::row_to_unit)?;
+        units.collect()
+    }
+
+    /// Every unit of a tracker in one query, in no particular order.
+    pub fn get_units_by_tracker(&self, tracker_id: &str) -> SqliteResult<Vec<Unit>> {
+        let mut stmt = self.conn.prepare_cached(
+            r#"SELECT u.id, u.subject_id, u.name, u."order", u.created_at, u.updated_at
+               FROM units u
+               JOIN subjects s ON s.id = u.subject_id
+               WHERE s.tracker_id = ?1"#
+        )?;
+        let units = stmt.query_map(params![tracker_id], Self::row_to_unit)?;
         units.collect()
     }
 
+    pub fn update_unit(&self, id: &str, subject_id: &str, name: &str, order: i32, now: i64) -> SqliteResult<()> {
+        self.conn.execute(
+            "UPDATE units SET subject_id = ?1, name = ?2, \"order\" = ?3, updated_at = ?4 WHERE id = ?5",
+            params![subject_id, name, order, now, id],
+        )?;
+        Ok(())
+    }
+
+    pub fn delete_unit(&self, id: &str) -> SqliteResult<()> {
+        self.conn.execute(
+            "DELETE FROM units WHERE id = ?1",
+            params![id],
+        )?;
+        Ok(())
+    }
+
     // ========================================================================
     // TOPIC OPERATIONS
     // ========================================================================
@@ -360,41 +778,44 @@ impl Database {
         })
     }
 
+    fn row_to_topic(row: &rusqlite::Row) -> SqliteResult<Topic> {
+        Ok(Topic {
+            id: row.get(0)?,
+            unit_id: row.get(1)?,
+            name: row.get(2)?,
+            completed: row.get::<_, i32>(3)? != 0,
+            order: row.get(4)?,
+            created_at: row.get(5)?,
+            updated_at: row.get(6)?,
+        })
+    }
+
     pub fn get_topics_by_unit(&self, unit_id: &str) -> SqliteResult<Vec<Topic>> {
-        let mut stmt = self.conn.prepare(
+        let mut stmt = self.conn.prepare_cached(
             "SELECT id, unit_id, name, completed, \"order\", created_at, updated_at FROM topics WHERE unit_id = ?1 ORDER BY \"order\" ASC"
         )?;
-        let topics = stmt.query_map(params![unit_id], |row| {
-            Ok(Topic {
-                id: row.get(0)?,
-                unit_id: row.get(1)?,
-                name: row.get(2)?,
-                completed: row.get::<_, i32>(3)? != 0,
-                order: row.get(4)?,
-                created_at: row.get(5)?,
-                updated_at: row.get(6)?,
-            })
-        })?;
+        let topics = stmt.query_map(params![unit_id], Self::row_to_topic)?;
+        topics.collect()
+    }
+
+    /// Every topic of a tracker in one query, in no particular order.
+    pub fn get_topics_by_tracker(&self, tracker_id: &str) -> SqliteResult<Vec<Topic>> {
+        let mut stmt = self.conn.prepare_cached(
+            r#"SELECT t.id, t.unit_id, t.name, t.completed, t."order", t.created_at, t.updated_at
+               FROM topics t
+               JOIN units u ON u.id = t.unit_id
+               JOIN subjects s ON s.id = u.subject_id
+               WHERE s.tracker_id = ?1"#
+        )?;
+        let topics = stmt.query_map(params![tracker_id], Self::row_to_topic)?;
         topics.collect()
     }
 
     pub fn get_topic(&self, id: &str) -> SqliteResult<Option<Topic>> {
-        let mut stmt = self.conn.prepare(
+        let mut stmt = self.conn.prepare_cached(
             "SELECT id, unit_id, name, completed, \"order\", created_at, updated_at FROM topics WHERE id = ?1"
         )?;
-        let topic = stmt.query_row(params![id], |row| {
-            Ok(Topic {
-                id: row.get(0)?,
-                unit_id: row.get(1)?,
-                name: row.get(2)?,
-                completed: row.get::<_, i32>(3)? != 0,
-                order: row.get(4)?,
-                created_at: row.get(5)?,
-                updated_at: row.get(6)?,
-            })
-        });
-
-        match topic {
+        match stmt.query_row(params![id], Self::row_to_topic) {
             Ok(t) => Ok(Some(t)),
             Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
             Err(e) => Err(e),
@@ -417,6 +838,20 @@ impl Database {
         }
     }
 
+    pub fn get_tracker_id_for_topic(&self, topic_id: &str) -> SqliteResult<Option<String>> {
+        let tracker_id = self.conn.query_row(
+            "SELECT s.tracker_id FROM topics t JOIN units u ON u.id = t.unit_id JOIN subjects s ON s.id = u.subject_id WHERE t.id = ?1",
+            params![topic_id],
+            |row| row.get(0),
+        );
+
+        match tracker_id {
+            Ok(id) => Ok(Some(id)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
     pub fn update_topic(&self, id: &str, name: &str, now: i64) -> SqliteResult<()> {
         self.conn.execute(
             "UPDATE topics SET name = ?1, updated_at = ?2 WHERE id = ?3",
@@ -425,6 +860,14 @@ impl Database {
         Ok(())
     }
 
+    pub fn move_topic(&self, id: &str, unit_id: &str, order: i32, now: i64) -> SqliteResult<()> {
+        self.conn.execute(
+            "UPDATE topics SET unit_id = ?1, \"order\" = ?2, updated_at = ?3 WHERE id = ?4",
+            params![unit_id, order, now, id],
+        )?;
+        Ok(())
+    }
+
     pub fn delete_topic(&self, id: &str) -> SqliteResult<()> {
         self.conn.execute(
             "DELETE FROM topics WHERE id = ?1",
@@ -442,94 +885,277 @@ impl Database {
         id: String,
         tracker_id: String,
         name: String,
-        test_type: &TestType,
+        test_type: String,
         scheduled_date: i64,
         now: i64,
     ) -> SqliteResult<Test> {
         self.conn.execute(
             "INSERT INTO tests (id, tracker_id, name, test_type, scheduled_date, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-            params![&id, &tracker_id, &name, test_type.as_str(), scheduled_date, now, now],
+            params![&id, &tracker_id, &name, &test_type, scheduled_date, now, now],
         )?;
         Ok(Test {
             id,
             tracker_id,
             name,
-            test_type: test_type.clone(),
+            test_type,
             scheduled_date,
+            marks_obtained: None,
+            max_marks: None,
+            subject_id: None,
+            revision: 0,
             created_at: now,
             updated_at: now,
         })
     }
 
+    fn row_to_test(row: &rusqlite::Row) -> SqliteResult<Test> {
+        Ok(Test {
+            id: row.get(0)?,
+            tracker_id: row.get(1)?,
+            name: row.get(2)?,
+            test_type: row.get(3)?,
+            scheduled_date: row.get(4)?,
+            marks_obtained: row.get(5)?,
+            max_marks: row.get(6)?,
+            created_at: row.get(7)?,
+            updated_at: row.get(8)?,
+            subject_id: row.get(9)?,
+            revision: row.get(10)?,
+        })
+    }
+
     pub fn get_tests_by_tracker(&self, tracker_id: &str) -> SqliteResult<Vec<Test>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT id, tracker_id, name, test_type, scheduled_date, created_at, updated_at FROM tests WHERE tracker_id = ?1 ORDER BY scheduled_date ASC"
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT id, tracker_id, name, test_type, scheduled_date, marks_obtained, max_marks, created_at, updated_at, subject_id, revision FROM tests WHERE tracker_id = ?1 ORDER BY scheduled_date ASC"
         )?;
-        let tests = stmt.query_map(params![tracker_id], |row| {
-            let test_type_str: String = row.get(3)?;
-            Ok(Test {
-                id: row.get(0)?,
-                tracker_id: row.get(1)?,
-                name: row.get(2)?,
-                test_type: TestType::from_str(&test_type_str).unwrap_or(TestType::ClassTest),
-                scheduled_date: row.get(4)?,
-                created_at: row.get(5)?,
-                updated_at: row.get(6)?,
-            })
-        })?;
+        let tests = stmt.query_map(params![tracker_id], Self::row_to_test)?;
         tests.collect()
     }
 
-    pub fn get_test(&self, id: &str) -> SqliteResult<Option<Test>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT id, tracker_id, name, test_type, scheduled_date, created_at, updated_at FROM tests WHERE id = ?1"
+    /// Tests whose marks count towards a subject.
+    pub fn get_tests_by_subject(&self, subject_id: &str) -> SqliteResult<Vec<Test>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT id, tracker_id, name, test_type, scheduled_date, marks_obtained, max_marks, created_at, updated_at, subject_id, revision FROM tests WHERE subject_id = ?1 ORDER BY scheduled_date ASC"
         )?;
-        let test = stmt.query_row(params![id], |row| {
-            let test_type_str: String = row.get(3)?;
-            Ok(Test {
-                id: row.get(0)?,
-                tracker_id: row.get(1)?,
-                name: row.get(2)?,
-                test_type: TestType::from_str(&test_type_str).unwrap_or(TestType::ClassTest),
-                scheduled_date: row.get(4)?,
-                created_at: row.get(5)?,
-                updated_at: row.get(6)?,
-            })
-        });
+        let tests = stmt.query_map(params![subject_id], Self::row_to_test)?;
+        tests.collect()
+    }
 
-        match test {
+    pub fn get_test(&self, id: &str) -> SqliteResult<Option<Test>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT id, tracker_id, name, test_type, scheduled_date, marks_obtained, max_marks, created_at, updated_at, subject_id, revision FROM tests WHERE id = ?1"
+        )?;
+        match stmt.query_row(params![id], Self::row_to_test) {
             Ok(t) => Ok(Some(t)),
             Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
             Err(e) => Err(e),
         }
     }
 
+    // Changes that show in a calendar event raise the test's revision
+
+    pub fn reschedule_test(&self, id: &str, scheduled_date: i64, now: i64) -> SqliteResult<()> {
+        self.conn.execute(
+            "UPDATE tests SET scheduled_date = ?1, revision = revision + 1, updated_at = ?2 WHERE id = ?3",
+            params![scheduled_date, now, id],
+        )?;
+        Ok(())
+    }
+
+    pub fn rename_test(&self, id: &str, name: &str, now: i64) -> SqliteResult<()> {
+        self.conn.execute(
+            "UPDATE tests SET name = ?1, revision = revision + 1, updated_at = ?2 WHERE id = ?3",
+            params![name, now, id],
+        )?;
+        Ok(())
+    }
+
+    /// Marks a test as changed after its coverage was replaced.
+    pub fn bump_test_revision(&self, id: &str, now: i64) -> SqliteResult<()> {
+        self.conn.execute(
+            "UPDATE tests SET revision = revision + 1, updated_at = ?1 WHERE id = ?2",
+            params![now, id],
+        )?;
+        Ok(())
+    }
+
+    pub fn set_test_subject(&self, id: &str, subject_id: Option<&str>, now: i64) -> SqliteResult<()> {
+        self.conn.execute(
+            "UPDATE tests SET subject_id = ?1, updated_at = ?2 WHERE id = ?3",
+            params![subject_id, now, id],
+        )?;
+        Ok(())
+    }
+
+    /// Gives tests without a subject the subject their coverage lies in,
+    /// when it lies in exactly one. `None` does this for every test.
+    pub fn assign_test_subjects_from_coverage(&self, test_id: Option<&str>) -> SqliteResult<()> {
+        self.conn.execute(
+            r#"UPDATE tests SET subject_id = (
+                   SELECT MIN(u.subject_id)
+                   FROM test_coverage c
+                   LEFT JOIN topics tp ON tp.id = c.topic_id
+                   JOIN units u ON u.id = COALESCE(c.unit_id, tp.unit_id)
+                   WHERE c.test_id = tests.id
+                   HAVING COUNT(DISTINCT u.subject_id) = 1
+               )
+               WHERE subject_id IS NULL AND (?1 IS NULL OR id = ?1)"#,
+            params![test_id],
+        )?;
+        Ok(())
+    }
+
+    pub fn set_test_marks(&self, id: &str, marks: Option<(f64, f64)>, now: i64) -> SqliteResult<()> {
+        let (obtained, max) = marks.unzip();
+        self.conn.execute(
+            "UPDATE tests SET marks_obtained = ?1, max_marks = ?2, updated_at = ?3 WHERE id = ?4",
+            params![obtained, max, now, id],
+        )?;
+        Ok(())
+    }
+
     // ========================================================================
-    // TEST COVERAGE OPERATIONS
+    // TEST TYPE OPERATIONS
     // ========================================================================
 
-    pub fn create_test_coverage(
+    pub fn create_test_type(
         &self,
         id: String,
-        test_id: String,
-        unit_id: Option<String>,
-        topic_id: Option<String>,
-    ) -> SqliteResult<TestCoverage> {
+        name: String,
+        color: Option<String>,
+        priority_window_days: i32,
+        weight: f64,
+        now: i64,
+    ) -> SqliteResult<TestType> {
         self.conn.execute(
-            "INSERT INTO test_coverage (id, test_id, unit_id, topic_id) VALUES (?1, ?2, ?3, ?4)",
-            params![&id, &test_id, &unit_id, &topic_id],
+            "INSERT INTO test_types (id, name, color, priority_window_days, weight, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![&id, &name, &color, priority_window_days, weight, now, now],
         )?;
-        Ok(TestCoverage {
+        Ok(TestType {
             id,
-            test_id,
-            unit_id,
-            topic_id,
+            name,
+            color,
+            priority_window_days,
+            weight,
+            created_at: now,
+            updated_at: now,
         })
     }
 
-    pub fn get_test_coverage(&self, test_id: &str) -> SqliteResult<Vec<TestCoverage>> {
-        let mut stmt = self.conn
-            .prepare("SELECT id, test_id, unit_id, topic_id FROM test_coverage WHERE test_id = ?1")?;
+    pub fn get_all_test_types(&self) -> SqliteResult<Vec<TestType>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT id, name, color, priority_window_days, weight, created_at, updated_at FROM test_types ORDER BY created_at ASC, rowid ASC"
+        )?;
+        let test_types = stmt.query_map([], |row| {
+            Ok(TestType {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                color: row.get(2)?,
+                priority_window_days: row.get(3)?,
+                weight: row.get(4)?,
+                created_at: row.get(5)?,
+                updated_at: row.get(6)?,
+            })
+        })?;
+        test_types.collect()
+    }
+
+    pub fn get_test_type(&self, id: &str) -> SqliteResult<Option<TestType>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT id, name, color, priority_window_days, weight, created_at, updated_at FROM test_types WHERE id = ?1"
+        )?;
+        let test_type = stmt.query_row(params![id], |row| {
+            Ok(TestType {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                color: row.get(2)?,
+                priority_window_days: row.get(3)?,
+                weight: row.get(4)?,
+                created_at: row.get(5)?,
+                updated_at: row.get(6)?,
+            })
+        });
+
+        match test_type {
+            Ok(t) => Ok(Some(t)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn update_test_type(
+        &self,
+        id: &str,
+        name: &str,
+        color: Option<&str>,
+        priority_window_days: i32,
+        weight: f64,
+        now: i64,
+    ) -> SqliteResult<()> {
+        self.conn.execute(
+            "UPDATE test_types SET name = ?1, color = ?2, priority_window_days = ?3, weight = ?4, updated_at = ?5 WHERE id = ?6",
+            params![name, color, priority_window_days, weight, now, id],
+        )?;
+        Ok(())
+    }
+
+    pub fn count_tests_of_type(&self, test_type_id: &str) -> SqliteResult<i64> {
+        self.conn.query_row(
+            "SELECT COUNT(*) FROM tests WHERE test_type = ?1",
+            params![test_type_id],
+            |row| row.get(0),
+        )
+    }
+
+    /// Names of the evaluation schemes with a component for the test type.
+    pub fn get_scheme_names_using_test_type(&self, test_type_id: &str) -> SqliteResult<Vec<String>> {
+        let mut stmt = self.conn.prepare_cached(
+            r#"SELECT s.name FROM evaluation_schemes s
+               JOIN evaluation_components c ON c.scheme_id = s.id
+               WHERE c.test_type_id = ?1
+               ORDER BY s.name"#
+        )?;
+        let names = stmt.query_map(params![test_type_id], |row| row.get(0))?;
+        names.collect()
+    }
+
+    pub fn delete_test_type(&self, id: &str) -> SqliteResult<()> {
+        self.conn
+            .execute("DELETE FROM test_types WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+
+    // ========================================================================
+    // TEST COVERAGE OPERATIONS
+    // ========================================================================
+
+    pub fn create_test_coverage(
+        &self,
+        id: String,
+        test_id: String,
+        unit_id: Option<String>,
+        topic_id: Option<String>,
+    ) -> SqliteResult<TestCoverage> {
+        self.conn.execute(
+            "INSERT INTO test_coverage (id, test_id, unit_id, topic_id) VALUES (?1, ?2, ?3, ?4)",
+            params![&id, &test_id, &unit_id, &topic_id],
+        )?;
+        Ok(TestCoverage {
+            id,
+            test_id,
+            unit_id,
+            topic_id,
+        })
+    }
+
+    pub fn delete_test_coverage(&self, test_id: &str) -> SqliteResult<()> {
+        self.conn.execute("DELETE FROM test_coverage WHERE test_id = ?1", params![test_id])?;
+        Ok(())
+    }
+
+    pub fn get_test_coverage(&self, test_id: &str) -> SqliteResult<Vec<TestCoverage>> {
+        let mut stmt = self.conn
+            .prepare_cached("SELECT id, test_id, unit_id, topic_id FROM test_coverage WHERE test_id = ?1")?;
         let coverage = stmt.query_map(params![test_id], |row| {
             Ok(TestCoverage {
                 id: row.get(0)?,
@@ -541,6 +1167,476 @@ impl Database {
         coverage.collect()
     }
 
+    // ========================================================================
+    // ASSIGNMENT OPERATIONS
+    // ========================================================================
+
+    /// Inserts a new assignment and links its topics.
+    pub fn create_assignment(&self, id: String, tracker_id: String, input: &AssignmentInput, now: i64) -> SqliteResult<Assignment> {
+        let status = AssignmentStatus::Todo;
+        self.conn.execute(
+            "INSERT INTO assignments (id, tracker_id, subject_id, name, description, due_date, status, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![&id, &tracker_id, &input.subject_id, &input.name, &input.description, input.due_date, status.as_str(), now, now],
+        )?;
+        self.set_assignment_topics(&id, &input.topic_ids)?;
+        Ok(Assignment {
+            id,
+            tracker_id,
+            subject_id: input.subject_id.clone(),
+            name: input.name.clone(),
+            description: input.description.clone(),
+            due_date: input.due_date,
+            status,
+            marks_obtained: None,
+            max_marks: None,
+            created_at: now,
+            updated_at: now,
+        })
+    }
+
+    fn row_to_assignment(row: &rusqlite::Row) -> SqliteResult<Assignment> {
+        let status: String = row.get(6)?;
+        Ok(Assignment {
+            id: row.get(0)?,
+            tracker_id: row.get(1)?,
+            subject_id: row.get(2)?,
+            name: row.get(3)?,
+            description: row.get(4)?,
+            due_date: row.get(5)?,
+            status: AssignmentStatus::parse(&status).ok_or_else(|| {
+                rusqlite::Error::FromSqlConversionFailure(
+                    6,
+                    rusqlite::types::Type::Text,
+                    format!("unknown assignment status '{}'", status).into(),
+                )
+            })?,
+            marks_obtained: row.get(7)?,
+            max_marks: row.get(8)?,
+            created_at: row.get(9)?,
+            updated_at: row.get(10)?,
+        })
+    }
+
+    pub fn get_assignments_by_tracker(&self, tracker_id: &str) -> SqliteResult<Vec<Assignment>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT id, tracker_id, subject_id, name, description, due_date, status, marks_obtained, max_marks, created_at, updated_at FROM assignments WHERE tracker_id = ?1 ORDER BY due_date ASC"
+        )?;
+        let assignments = stmt.query_map(params![tracker_id], Self::row_to_assignment)?;
+        assignments.collect()
+    }
+
+    pub fn get_assignment(&self, id: &str) -> SqliteResult<Option<Assignment>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT id, tracker_id, subject_id, name, description, due_date, status, marks_obtained, max_marks, created_at, updated_at FROM assignments WHERE id = ?1"
+        )?;
+        match stmt.query_row(params![id], Self::row_to_assignment) {
+            Ok(a) => Ok(Some(a)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Updates an assignment's details and replaces its linked topics.
+    pub fn update_assignment(&self, id: &str, input: &AssignmentInput, now: i64) -> SqliteResult<()> {
+        self.conn.execute(
+            "UPDATE assignments SET subject_id = ?1, name = ?2, description = ?3, due_date = ?4, updated_at = ?5 WHERE id = ?6",
+            params![&input.subject_id, &input.name, &input.description, input.due_date, now, id],
+        )?;
+        self.set_assignment_topics(id, &input.topic_ids)
+    }
+
+    pub fn set_assignment_status(&self, id: &str, status: AssignmentStatus, now: i64) -> SqliteResult<()> {
+        self.conn.execute(
+            "UPDATE assignments SET status = ?1, updated_at = ?2 WHERE id = ?3",
+            params![status.as_str(), now, id],
+        )?;
+        Ok(())
+    }
+
+    /// Records marks and marks the assignment as graded.
+    pub fn grade_assignment(&self, id: &str, marks_obtained: f64, max_marks: f64, now: i64) -> SqliteResult<()> {
+        self.conn.execute(
+            "UPDATE assignments SET status = ?1, marks_obtained = ?2, max_marks = ?3, updated_at = ?4 WHERE id = ?5",
+            params![AssignmentStatus::Graded.as_str(), marks_obtained, max_marks, now, id],
+        )?;
+        Ok(())
+    }
+
+    pub fn delete_assignment(&self, id: &str) -> SqliteResult<()> {
+        self.conn
+            .execute("DELETE FROM assignments WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+
+    fn set_assignment_topics(&self, assignment_id: &str, topic_ids: &[String]) -> SqliteResult<()> {
+        self.conn.execute(
+            "DELETE FROM assignment_topics WHERE assignment_id = ?1",
+            params![assignment_id],
+        )?;
+        for topic_id in topic_ids {
+            self.conn.execute(
+                "INSERT OR IGNORE INTO assignment_topics (assignment_id, topic_id) VALUES (?1, ?2)",
+                params![assignment_id, topic_id],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Names of the topics linked to an assignment, in outline order.
+    pub fn get_assignment_topic_names(&self, assignment_id: &str) -> SqliteResult<Vec<String>> {
+        let mut stmt = self.conn.prepare_cached(
+            r#"SELECT t.name FROM assignment_topics a
+               JOIN topics t ON t.id = a.topic_id
+               JOIN units u ON u.id = t.unit_id
+               WHERE a.assignment_id = ?1
+               ORDER BY u.subject_id, u."order", t."order""#
+        )?;
+        let names = stmt.query_map(params![assignment_id], |row| row.get(0))?;
+        names.collect()
+    }
+
+    pub fn get_assignment_topic_ids(&self, assignment_id: &str) -> SqliteResult<Vec<String>> {
+        let mut stmt = self.conn
+            .prepare("SELECT topic_id FROM assignment_topics WHERE assignment_id = ?1")?;
+        let ids = stmt.query_map(params![assignment_id], |row| row.get(0))?;
+        ids.collect()
+    }
+
+    // ========================================================================
+    // GRADING SCALE OPERATIONS
+    // ========================================================================
+
+    fn insert_grade_bands(&self, scale_id: &str, bands: &[GradeBand]) -> SqliteResult<()> {
+        for band in bands {
+            self.conn.execute(
+                "INSERT INTO grade_bands (scale_id, letter, min_percentage, grade_points) VALUES (?1, ?2, ?3, ?4)",
+                params![scale_id, &band.letter, band.min_percentage, band.grade_points],
+            )?;
+        }
+        Ok(())
+    }
+
+    pub fn create_grading_scale(&self, id: String, input: &GradingScaleInput, now: i64) -> SqliteResult<GradingScale> {
+        self.conn.execute(
+            "INSERT INTO grading_scales (id, name, created_at, updated_at) VALUES (?1, ?2, ?3, ?4)",
+            params![&id, &input.name, now, now],
+        )?;
+        self.insert_grade_bands(&id, &input.bands)?;
+        Ok(GradingScale {
+            bands: self.get_grade_bands(&id)?,
+            id,
+            name: input.name.clone(),
+            created_at: now,
+            updated_at: now,
+        })
+    }
+
+    fn get_grade_bands(&self, scale_id: &str) -> SqliteResult<Vec<GradeBand>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT letter, min_percentage, grade_points FROM grade_bands WHERE scale_id = ?1 ORDER BY min_percentage DESC"
+        )?;
+        let bands = stmt.query_map(params![scale_id], |row| {
+            Ok(GradeBand {
+                letter: row.get(0)?,
+                min_percentage: row.get(1)?,
+                grade_points: row.get(2)?,
+            })
+        })?;
+        bands.collect()
+    }
+
+    pub fn get_all_grading_scales(&self) -> SqliteResult<Vec<GradingScale>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT id, name, created_at, updated_at FROM grading_scales ORDER BY created_at ASC, rowid ASC"
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get(2)?, row.get(3)?))
+        })?;
+        rows.map(|row| {
+            let (id, name, created_at, updated_at) = row?;
+            Ok(GradingScale {
+                bands: self.get_grade_bands(&id)?,
+                id,
+                name,
+                created_at,
+                updated_at,
+            })
+        })
+        .collect()
+    }
+
+    pub fn get_grading_scale(&self, id: &str) -> SqliteResult<Option<GradingScale>> {
+        Ok(self.get_all_grading_scales()?.into_iter().find(|s| s.id == id))
+    }
+
+    pub fn update_grading_scale(&self, id: &str, input: &GradingScaleInput, now: i64) -> SqliteResult<()> {
+        self.conn.execute(
+            "UPDATE grading_scales SET name = ?1, updated_at = ?2 WHERE id = ?3",
+            params![&input.name, now, id],
+        )?;
+        self.conn.execute("DELETE FROM grade_bands WHERE scale_id = ?1", params![id])?;
+        self.insert_grade_bands(id, &input.bands)
+    }
+
+    pub fn count_schemes_using_scale(&self, scale_id: &str) -> SqliteResult<i64> {
+        self.conn.query_row(
+            "SELECT COUNT(*) FROM evaluation_schemes WHERE grading_scale_id = ?1",
+            params![scale_id],
+            |row| row.get(0),
+        )
+    }
+
+    pub fn delete_grading_scale(&self, id: &str) -> SqliteResult<()> {
+        self.conn
+            .execute("DELETE FROM grading_scales WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+
+    // ========================================================================
+    // EVALUATION SCHEME OPERATIONS
+    // ========================================================================
+
+    fn insert_evaluation_components(&self, scheme_id: &str, components: &[EvaluationComponent]) -> SqliteResult<()> {
+        for component in components {
+            self.conn.execute(
+                "INSERT INTO evaluation_components (scheme_id, test_type_id, weight, best_of) VALUES (?1, ?2, ?3, ?4)",
+                params![scheme_id, &component.test_type_id, component.weight, component.best_of],
+            )?;
+        }
+        Ok(())
+    }
+
+    pub fn create_evaluation_scheme(&self, id: String, input: &EvaluationSchemeInput, now: i64) -> SqliteResult<EvaluationScheme> {
+        self.conn.execute(
+            "INSERT INTO evaluation_schemes (id, name, grading_scale_id, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![&id, &input.name, &input.grading_scale_id, now, now],
+        )?;
+        self.insert_evaluation_components(&id, &input.components)?;
+        Ok(EvaluationScheme {
+            id,
+            name: input.name.clone(),
+            grading_scale_id: input.grading_scale_id.clone(),
+            components: input.components.clone(),
+            created_at: now,
+            updated_at: now,
+        })
+    }
+
+    fn get_evaluation_components(&self, scheme_id: &str) -> SqliteResult<Vec<EvaluationComponent>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT test_type_id, weight, best_of FROM evaluation_components WHERE scheme_id = ?1 ORDER BY rowid ASC"
+        )?;
+        let components = stmt.query_map(params![scheme_id], |row| {
+            Ok(EvaluationComponent {
+                test_type_id: row.get(0)?,
+                weight: row.get(1)?,
+                best_of: row.get(2)?,
+            })
+        })?;
+        components.collect()
+    }
+
+    pub fn get_all_evaluation_schemes(&self) -> SqliteResult<Vec<EvaluationScheme>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT id, name, grading_scale_id, created_at, updated_at FROM evaluation_schemes ORDER BY created_at ASC, rowid ASC"
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?))
+        })?;
+        rows.map(|row| {
+            let (id, name, grading_scale_id, created_at, updated_at) = row?;
+            Ok(EvaluationScheme {
+                components: self.get_evaluation_components(&id)?,
+                id,
+                name,
+                grading_scale_id,
+                created_at,
+                updated_at,
+            })
+        })
+        .collect()
+    }
+
+    pub fn get_evaluation_scheme(&self, id: &str) -> SqliteResult<Option<EvaluationScheme>> {
+        Ok(self.get_all_evaluation_schemes()?.into_iter().find(|s| s.id == id))
+    }
+
+    pub fn update_evaluation_scheme(&self, id: &str, input: &EvaluationSchemeInput, now: i64) -> SqliteResult<()> {
+        self.conn.execute(
+            "UPDATE evaluation_schemes SET name = ?1, grading_scale_id = ?2, updated_at = ?3 WHERE id = ?4",
+            params![&input.name, &input.grading_scale_id, now, id],
+        )?;
+        self.conn.execute("DELETE FROM evaluation_components WHERE scheme_id = ?1", params![id])?;
+        self.insert_evaluation_components(id, &input.components)
+    }
+
+    pub fn count_subjects_using_scheme(&self, scheme_id: &str) -> SqliteResult<i64> {
+        self.conn.query_row(
+            "SELECT COUNT(*) FROM subjects WHERE evaluation_scheme_id = ?1",
+            params![scheme_id],
+            |row| row.get(0),
+        )
+    }
+
+    pub fn delete_evaluation_scheme(&self, id: &str) -> SqliteResult<()> {
+        self.conn
+            .execute("DELETE FROM evaluation_schemes WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+
+    // ========================================================================
+    // STUDY PLAN OPERATIONS
+    // ========================================================================
+
+    pub fn get_plan_settings(&self, tracker_id: &str) -> SqliteResult<Option<PlanSettings>> {
+        let settings = self.conn.query_row(
+            "SELECT capacity_unit, daily_capacity, minutes_per_topic, rest_days FROM plan_settings WHERE tracker_id = ?1",
+            params![tracker_id],
+            |row| {
+                let unit: String = row.get(0)?;
+                Ok(PlanSettings {
+                    capacity_unit: CapacityUnit::parse(&unit).ok_or_else(|| {
+                        rusqlite::Error::FromSqlConversionFailure(
+                            0,
+                            rusqlite::types::Type::Text,
+                            format!("unknown capacity unit '{}'", unit).into(),
+                        )
+                    })?,
+                    daily_capacity: row.get(1)?,
+                    minutes_per_topic: row.get(2)?,
+                    rest_days: row
+                        .get::<_, String>(3)?
+                        .split(',')
+                        .filter_map(|day| day.parse().ok())
+                        .collect(),
+                })
+            },
+        );
+
+        match settings {
+            Ok(s) => Ok(Some(s)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn set_plan_settings(&self, tracker_id: &str, settings: &PlanSettings, now: i64) -> SqliteResult<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO plan_settings (tracker_id, capacity_unit, daily_capacity, minutes_per_topic, rest_days, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                tracker_id,
+                settings.capacity_unit.as_str(),
+                settings.daily_capacity,
+                settings.minutes_per_topic,
+                settings.rest_days.iter().map(|day| day.to_string()).collect::<Vec<_>>().join(","),
+                now
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Removes a tracker's plan and its settings, which stops rebalancing.
+    pub fn delete_study_plan(&self, tracker_id: &str) -> SqliteResult<()> {
+        self.conn.execute("DELETE FROM plan_items WHERE tracker_id = ?1", params![tracker_id])?;
+        self.conn.execute("DELETE FROM plan_settings WHERE tracker_id = ?1", params![tracker_id])?;
+        Ok(())
+    }
+
+    pub fn get_plan_items(&self, tracker_id: &str) -> SqliteResult<Vec<PlanItem>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT id, tracker_id, topic_id, test_id, day, minutes, created_at FROM plan_items WHERE tracker_id = ?1 ORDER BY day IS NULL, day ASC, rowid ASC"
+        )?;
+        let items = stmt.query_map(params![tracker_id], |row| {
+            Ok(PlanItem {
+                id: row.get(0)?,
+                tracker_id: row.get(1)?,
+                topic_id: row.get(2)?,
+                test_id: row.get(3)?,
+                day: row.get(4)?,
+                minutes: row.get(5)?,
+                created_at: row.get(6)?,
+            })
+        })?;
+        items.collect()
+    }
+
+    /// Replaces the plan from `from_day` on, along with anything that didn't
+    /// fit. Earlier days are kept as a record of what was planned.
+    pub fn replace_plan_items(&self, tracker_id: &str, from_day: &str, items: &[PlanItem]) -> SqliteResult<()> {
+        self.conn.execute(
+            "DELETE FROM plan_items WHERE tracker_id = ?1 AND (day IS NULL OR day >= ?2)",
+            params![tracker_id, from_day],
+        )?;
+        for item in items {
+            self.conn.execute(
+                "INSERT INTO plan_items (id, tracker_id, topic_id, test_id, day, minutes, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![&item.id, &item.tracker_id, &item.topic_id, &item.test_id, &item.day, item.minutes, item.created_at],
+            )?;
+        }
+        Ok(())
+    }
+
+    // ========================================================================
+    // SEMESTER OVERVIEW
+    // ========================================================================
+
+    /// Every tracker in a semester with its live topic counts, newest first.
+    pub fn get_tracker_summaries(&self, semester_id: &str) -> SqliteResult<Vec<TrackerSummary>> {
+        let mut stmt = self.conn.prepare_cached(
+            r#"SELECT tr.id, tr.semester_id, tr.name, tr.description, tr.color, tr.total_subjects, tr.total_units, tr.total_topics, tr.created_at, tr.updated_at,
+                      COUNT(t.id), COALESCE(SUM(t.completed), 0)
+               FROM trackers tr
+               LEFT JOIN subjects s ON s.tracker_id = tr.id
+               LEFT JOIN units u ON u.subject_id = s.id
+               LEFT JOIN topics t ON t.unit_id = u.id
+               WHERE tr.semester_id = ?1
+               GROUP BY tr.id
+               ORDER BY tr.created_at DESC"#
+        )?;
+        let summaries = stmt.query_map(params![semester_id], |row| {
+            let total_topics: i32 = row.get(10)?;
+            let completed_topics: i32 = row.get(11)?;
+            Ok(TrackerSummary {
+                tracker: Tracker {
+                    id: row.get(0)?,
+                    semester_id: row.get(1)?,
+                    name: row.get(2)?,
+                    description: row.get(3)?,
+                    color: row.get(4)?,
+                    total_subjects: row.get(5)?,
+                    total_units: row.get(6)?,
+                    total_topics: row.get(7)?,
+                    created_at: row.get(8)?,
+                    updated_at: row.get(9)?,
+                },
+                total_topics,
+                completed_topics,
+                percentage: if total_topics > 0 {
+                    (completed_topics as f64 / total_topics as f64) * 100.0
+                } else {
+                    0.0
+                },
+            })
+        })?;
+        summaries.collect()
+    }
+
+    /// Tests across a semester's trackers scheduled at or after `from`,
+    /// soonest first, each with its tracker's name.
+    pub fn get_upcoming_tests_by_semester(&self, semester_id: &str, from: i64) -> SqliteResult<Vec<(Test, String)>> {
+        let mut stmt = self.conn.prepare_cached(
+            r#"SELECT t.id, t.tracker_id, t.name, t.test_type, t.scheduled_date, t.marks_obtained, t.max_marks, t.created_at, t.updated_at, t.subject_id, t.revision, tr.name
+               FROM tests t
+               JOIN trackers tr ON tr.id = t.tracker_id
+               WHERE tr.semester_id = ?1 AND t.scheduled_date >= ?2
+               ORDER BY t.scheduled_date ASC, t.name ASC"#
+        )?;
+        let tests = stmt.query_map(params![semester_id, from], |row| {
+            Ok((Self::row_to_test(row)?, row.get(11)?))
+        })?;
+        tests.collect()
+    }
+
     // ========================================================================
     // THEME PREFERENCE
     // ========================================================================
@@ -566,37 +1662,300 @@ impl Database {
     }
 
     // ========================================================================
-    // TRACKER STATISTICS UPDATE
+    // TRACKER STATISTICS
     // ========================================================================
 
-    pub fn update_tracker_statistics(&self, tracker_id: &str, now: i64) -> SqliteResult<()> {
-        // Count subjects
-        let subject_count: i32 = self.conn.query_row(
-            "SELECT COUNT(*) FROM subjects WHERE tracker_id = ?1",
-            params![tracker_id],
-            |row| row.get(0),
-        )?;
+    /// Recounts every tracker's subjects, units and topics from scratch.
+    /// Triggers keep the counts current, so this only repairs databases
+    /// edited outside the app. Returns how many trackers were wrong.
+    pub fn recompute_all_statistics(&self) -> SqliteResult<usize> {
+        self.conn.execute(
+            &format!(
+                r#"UPDATE trackers SET total_subjects = c.subjects, total_units = c.units, total_topics = c.topics
+                   FROM ({}) AS c
+                   WHERE c.id = trackers.id
+                     AND (total_subjects IS NOT c.subjects OR total_units IS NOT c.units OR total_topics IS NOT c.topics)"#,
+                TRACKER_COUNTS_SQL
+            ),
+            [],
+        )
+    }
 
-        // Count units
-        let unit_count: i32 = self.conn.query_row(
-            "SELECT COUNT(*) FROM units u JOIN subjects s ON u.subject_id = s.id WHERE s.tracker_id = ?1",
-            params![tracker_id],
-            |row| row.get(0),
+    /// IDs of trackers whose cached counts are wrong.
+    pub fn get_trackers_with_stale_statistics(&self) -> SqliteResult<Vec<String>> {
+        let mut stmt = self.conn.prepare(&format!(
+            r#"SELECT tr.id FROM trackers tr
+               JOIN ({}) AS c ON c.id = tr.id
+               WHERE tr.total_subjects IS NOT c.subjects OR tr.total_units IS NOT c.units OR tr.total_topics IS NOT c.topics"#,
+            TRACKER_COUNTS_SQL
+        ))?;
+        let ids = stmt.query_map([], |row| row.get(0))?;
+        ids.collect()
+    }
+
+    // ========================================================================
+    // DATABASE CHECKS
+    // ========================================================================
+
+    /// Problems reported by `PRAGMA integrity_check`; empty when healthy.
+    pub fn integrity_check(&self) -> SqliteResult<Vec<String>> {
+        let mut stmt = self.conn.prepare("PRAGMA integrity_check")?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+        let mut problems = rows.collect::<SqliteResult<Vec<_>>>()?;
+        problems.retain(|p| p != "ok");
+        Ok(problems)
+    }
+
+    pub fn get_foreign_key_violations(&self) -> SqliteResult<Vec<ForeignKeyViolation>> {
+        let mut stmt = self.conn.prepare("PRAGMA foreign_key_check")?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, Option<i64>>(1)?, row.get::<_, String>(2)?, row.get::<_, i64>(3)?))
+            })?
+            .collect::<SqliteResult<Vec<_>>>()?;
+
+        let mut violations = Vec::new();
+        for (table, rowid, parent, key_id) in rows {
+            // Every table here has a rowid; skip anything that doesn't
+            let Some(rowid) = rowid else { continue };
+            let (column, on_delete) = self.conn.query_row(
+                "SELECT \"from\", on_delete FROM pragma_foreign_key_list(?1) WHERE id = ?2",
+                params![table, key_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )?;
+            violations.push(ForeignKeyViolation { table, rowid, parent, column, on_delete });
+        }
+        Ok(violations)
+    }
+
+    /// Fixes a row with a missing parent the way deleting the parent would
+    /// have: clears the reference for SET NULL keys, deletes the row otherwise.
+    pub fn repair_foreign_key_violation(&self, violation: &ForeignKeyViolation) -> SqliteResult<()> {
+        let table = violation.table.replace('"', "\"\"");
+        if violation.on_delete == "SET NULL" {
+            let column = violation.column.replace('"', "\"\"");
+            self.conn.execute(&format!("UPDATE \"{}\" SET \"{}\" = NULL WHERE rowid = ?1", table, column), params![violation.rowid])?;
+        } else {
+            self.conn.execute(&format!("DELETE FROM \"{}\" WHERE rowid = ?1", table), params![violation.rowid])?;
+        }
+        Ok(())
+    }
+
+    /// Coverage rows that cover neither a unit nor a topic.
+    pub fn get_empty_test_coverage(&self) -> SqliteResult<Vec<TestCoverage>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, test_id, unit_id, topic_id FROM test_coverage WHERE unit_id IS NULL AND topic_id IS NULL"
         )?;
+        let coverage = stmt.query_map([], |row| {
+            Ok(TestCoverage {
+                id: row.get(0)?,
+                test_id: row.get(1)?,
+                unit_id: row.get(2)?,
+                topic_id: row.get(3)?,
+            })
+        })?;
+        coverage.collect()
+    }
 
-        // Count topics
-        let topic_count: i32 = self.conn.query_row(
-            "SELECT COUNT(*) FROM topics t JOIN units u ON t.unit_id = u.id JOIN subjects s ON u.subject_id = s.id WHERE s.tracker_id = ?1",
-            params![tracker_id],
-            |row| row.get(0),
+    pub fn delete_test_coverage_entry(&self, id: &str) -> SqliteResult<()> {
+        self.conn.execute("DELETE FROM test_coverage WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+
+    pub fn get_duplicate_orders(&self) -> SqliteResult<Vec<DuplicateOrder>> {
+        let mut stmt = self.conn.prepare(
+            r#"SELECT 'units', subject_id, "order", COUNT(*) FROM units GROUP BY subject_id, "order" HAVING COUNT(*) > 1
+               UNION ALL
+               SELECT 'topics', unit_id, "order", COUNT(*) FROM topics GROUP BY unit_id, "order" HAVING COUNT(*) > 1
+               ORDER BY 1, 2, 3"#
         )?;
+        let duplicates = stmt.query_map([], |row| {
+            Ok(DuplicateOrder {
+                table: row.get(0)?,
+                parent_id: row.get(1)?,
+                order: row.get(2)?,
+                count: row.get(3)?,
+            })
+        })?;
+        duplicates.collect()
+    }
 
-        // Update tracker
+    /// Renumbers the units of a subject or topics of a unit from 0, keeping
+    /// their current order and breaking ties by creation time.
+    pub fn renumber_children(&self, table: &str, parent_id: &str) -> SqliteResult<()> {
+        let parent_column = match table {
+            "units" => "subject_id",
+            "topics" => "unit_id",
+            other => return Err(rusqlite::Error::InvalidParameterName(other.to_string())),
+        };
         self.conn.execute(
-            "UPDATE trackers SET total_subjects = ?1, total_units = ?2, total_topics = ?3, updated_at = ?4 WHERE id = ?5",
-            params![subject_count, unit_count, topic_count, now, tracker_id],
+            &format!(
+                r#"UPDATE {table} SET "order" = r.position
+                   FROM (
+                       SELECT id, ROW_NUMBER() OVER (ORDER BY "order", created_at, rowid) - 1 AS position
+                       FROM {table} WHERE {parent_column} = ?1
+                   ) AS r
+                   WHERE {table}.id = r.id"#
+            ),
+            params![parent_id],
         )?;
-
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_test_types() {
+        let db = Database::new(PathBuf::from(":memory:")).unwrap();
+        db.seed_test_types().unwrap();
+        let ids: Vec<String> = db.get_all_test_types().unwrap().into_iter().map(|t| t.id).collect();
+        assert_eq!(ids, vec!["lab_practical", "class_test", "isa", "esa"]);
+
+        let viva = db.create_test_type("viva".into(), "Viva".into(), None, 3, 0.5, 10).unwrap();
+        db.update_test_type("viva", "Viva Voce", Some("#000000"), 5, 1.5, 20).unwrap();
+        let updated = db.get_test_type("viva").unwrap().unwrap();
+        assert_eq!((updated.name.as_str(), updated.priority_window_days, updated.weight), ("Viva Voce", 5, 1.5));
+        assert_eq!(updated.created_at, viva.created_at);
+
+        db.create_semester("sem".into(), "Sem".into(), 0).unwrap();
+        db.create_tracker("tr".into(), "sem".into(), "Tracker".into(), None, None, 0).unwrap();
+        db.create_test("t1".into(), "tr".into(), "Viva 1".into(), "viva".into(), 0, 0).unwrap();
+        assert_eq!(db.count_tests_of_type("viva").unwrap(), 1);
+
+        // Types are read back as stored, even ones that no longer exist
+        db.delete_test_type("viva").unwrap();
+        assert!(db.get_test_type("viva").unwrap().is_none());
+        assert_eq!(db.get_test("t1").unwrap().unwrap().test_type, "viva");
+    }
+
+    #[test]
+    fn test_assignments() {
+        let db = Database::new(PathBuf::from(":memory:")).unwrap();
+        db.create_semester("sem".into(), "Sem".into(), 0).unwrap();
+        db.create_tracker("tr".into(), "sem".into(), "Tracker".into(), None, None, 0).unwrap();
+        db.create_subject("os".into(), "tr".into(), "OS".into(), 0).unwrap();
+        db.create_unit("u1".into(), "os".into(), "Processes".into(), 0, 0).unwrap();
+        db.create_topic("t1".into(), "u1".into(), "Threads".into(), 0, 0).unwrap();
+        db.create_topic("t2".into(), "u1".into(), "Scheduling".into(), 1, 0).unwrap();
+
+        let mut input = AssignmentInput {
+            subject_id: Some("os".into()),
+            name: "Shell".into(),
+            description: None,
+            due_date: 100,
+            topic_ids: vec!["t2".into(), "t1".into()],
+        };
+        let created = db.create_assignment("a1".into(), "tr".into(), &input, 0).unwrap();
+        assert_eq!(created.status, AssignmentStatus::Todo);
+        assert_eq!(db.get_assignment_topic_names("a1").unwrap(), vec!["Threads", "Scheduling"]);
+
+        input.topic_ids = vec!["t2".into()];
+        input.due_date = 200;
+        db.update_assignment("a1", &input, 1).unwrap();
+        assert_eq!(db.get_assignment_topic_ids("a1").unwrap(), vec!["t2"]);
+
+        db.grade_assignment("a1", 18.0, 20.0, 2).unwrap();
+        let graded = db.get_assignment("a1").unwrap().unwrap();
+        assert_eq!((graded.status, graded.marks_obtained, graded.due_date), (AssignmentStatus::Graded, Some(18.0), 200));
+
+        // Deleting the subject keeps the assignment on the tracker
+        db.delete_subject("os").unwrap();
+        let orphaned = db.get_assignments_by_tracker("tr").unwrap();
+        assert_eq!(orphaned.len(), 1);
+        assert!(orphaned[0].subject_id.is_none());
+        assert!(db.get_assignment_topic_ids("a1").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_semester_overview_queries() {
+        let db = Database::new(PathBuf::from(":memory:")).unwrap();
+        db.create_semester("sem".into(), "Sem".into(), 0).unwrap();
+        db.create_semester("other".into(), "Other".into(), 0).unwrap();
+        db.create_tracker("a".into(), "sem".into(), "A".into(), None, None, 1).unwrap();
+        db.create_tracker("b".into(), "sem".into(), "B".into(), None, None, 2).unwrap();
+        db.create_tracker("c".into(), "other".into(), "C".into(), None, None, 3).unwrap();
+        db.create_subject("s1".into(), "a".into(), "OS".into(), 0).unwrap();
+        db.create_unit("u1".into(), "s1".into(), "Processes".into(), 0, 0).unwrap();
+        db.create_topic("t1".into(), "u1".into(), "Threads".into(), 0, 0).unwrap();
+        db.create_topic("t2".into(), "u1".into(), "Scheduling".into(), 1, 0).unwrap();
+        db.create_subject("s2".into(), "a".into(), "Empty".into(), 0).unwrap();
+        db.toggle_topic_completion("t1", 0).unwrap();
+
+        let counts: Vec<(String, i32, i32)> = db
+            .get_tracker_summaries("sem")
+            .unwrap()
+            .into_iter()
+            .map(|s| (s.tracker.id, s.total_topics, s.completed_topics))
+            .collect();
+        assert_eq!(counts, vec![("b".to_string(), 0, 0), ("a".to_string(), 2, 1)]);
+
+        db.create_test("late".into(), "b".into(), "Late".into(), "isa".into(), 300, 0).unwrap();
+        db.create_test("past".into(), "a".into(), "Past".into(), "isa".into(), 50, 0).unwrap();
+        db.create_test("soon".into(), "a".into(), "Soon".into(), "isa".into(), 200, 0).unwrap();
+        db.create_test("elsewhere".into(), "c".into(), "Elsewhere".into(), "isa".into(), 250, 0).unwrap();
+        let upcoming: Vec<(String, String)> = db
+            .get_upcoming_tests_by_semester("sem", 100)
+            .unwrap()
+            .into_iter()
+            .map(|(test, tracker)| (test.id, tracker))
+            .collect();
+        assert_eq!(upcoming, vec![("soon".to_string(), "A".to_string()), ("late".to_string(), "B".to_string())]);
+    }
+
+    #[test]
+    fn test_tracker_statistics() {
+        let db = Database::new(PathBuf::from(":memory:")).unwrap();
+        let counts = |id: &str| {
+            let tracker = db.get_tracker(id).unwrap().unwrap();
+            (tracker.total_subjects, tracker.total_units, tracker.total_topics)
+        };
+        let consistent = |db: &Database| assert_eq!(db.recompute_all_statistics().unwrap(), 0);
+
+        db.create_semester("sem".into(), "Sem".into(), 0).unwrap();
+        db.create_tracker("a".into(), "sem".into(), "A".into(), None, None, 0).unwrap();
+        db.create_tracker("b".into(), "sem".into(), "B".into(), None, None, 0).unwrap();
+        db.create_subject("s1".into(), "a".into(), "OS".into(), 0).unwrap();
+        db.create_subject("s2".into(), "a".into(), "DBMS".into(), 0).unwrap();
+        db.create_subject("s3".into(), "b".into(), "Networks".into(), 0).unwrap();
+        db.create_unit("u1".into(), "s1".into(), "Processes".into(), 0, 0).unwrap();
+        db.create_unit("u2".into(), "s1".into(), "Memory".into(), 1, 0).unwrap();
+        db.create_unit("u3".into(), "s2".into(), "SQL".into(), 0, 0).unwrap();
+        db.create_unit("u4".into(), "s3".into(), "Routing".into(), 0, 0).unwrap();
+        for (id, unit) in [("t1", "u1"), ("t2", "u1"), ("t3", "u2"), ("t4", "u3"), ("t5", "u3"), ("t6", "u4")] {
+            db.create_topic(id.into(), unit.into(), id.into(), 0, 0).unwrap();
+        }
+        assert_eq!((counts("a"), counts("b")), ((2, 3, 5), (1, 1, 1)));
+        consistent(&db);
+
+        db.delete_topic("t5").unwrap();
+        assert_eq!(counts("a"), (2, 3, 4));
+
+        db.move_topic("t4", "u4", 1, 0).unwrap();
+        assert_eq!((counts("a"), counts("b")), ((2, 3, 3), (1, 1, 2)));
+
+        db.update_unit("u2", "s3", "Memory", 1, 0).unwrap();
+        assert_eq!((counts("a"), counts("b")), ((2, 2, 2), (1, 2, 3)));
+        db.update_unit("u2", "s3", "Paging", 1, 0).unwrap();
+        assert_eq!(counts("b"), (1, 2, 3));
+        consistent(&db);
+
+        // Cascades count once, from the parent's trigger
+        db.delete_unit("u1").unwrap();
+        assert_eq!(counts("a"), (2, 1, 0));
+        db.delete_subject("s3").unwrap();
+        assert_eq!(counts("b"), (0, 0, 0));
+        consistent(&db);
+
+        db.conn.execute("UPDATE subjects SET tracker_id = 'b' WHERE id = 's2'", []).unwrap();
+        assert_eq!((counts("a"), counts("b")), ((1, 0, 0), (1, 1, 0)));
+        consistent(&db);
+
+        // Counts edited behind the app's back are repaired
+        db.conn.execute("UPDATE trackers SET total_topics = 40 WHERE id = 'a'", []).unwrap();
+        assert_eq!(db.recompute_all_statistics().unwrap(), 1);
+        assert_eq!(counts("a"), (1, 0, 0));
+    }
+}
@@ -0,0 +1,537 @@
+use crate::syllabus::SyllabusExport;
+use crate::utils::normalize_name;
+use serde::Serialize;
+use std::collections::HashSet;
+
+/// Minimum similarity for an unmatched item to count as a rename (or move)
+/// of another instead of an add plus a remove.
+pub const RENAME_THRESHOLD: f64 = 0.6;
+
+// ============================================================================
+// NAME MATCHING
+// ============================================================================
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0; b.len() + 1];
+
+    for i in 1..=a.len() {
+        curr[0] = i;
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            curr[j] = (prev[j] + 1).min(curr[j - 1] + 1).min(prev[j - 1] + cost);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+
+    prev[b.len()]
+}
+
+/// Similarity between two names in `0.0..=1.0`, the better of the edit
+/// distance ratio and the word overlap.
+pub fn name_similarity(a: &str, b: &str) -> f64 {
+    let a = normalize_name(a);
+    let b = normalize_name(b);
+    if a == b {
+        return 1.0;
+    }
+
+    let max_len = a.chars().count().max(b.chars().count());
+    let edit = 1.0 - levenshtein(&a, &b) as f64 / max_len as f64;
+
+    let words_a: HashSet<&str> = a.split(' ').collect();
+    let words_b: HashSet<&str> = b.split(' ').collect();
+    let shared = words_a.intersection(&words_b).count();
+    let total = words_a.union(&words_b).count();
+    let overlap = if total > 0 { shared as f64 / total as f64 } else { 0.0 };
+
+    edit.max(overlap)
+}
+
+/// Share of names two lists have in common, used to recognise a renamed
+/// unit or subject by its contents.
+pub fn children_overlap<'a>(a: impl Iterator<Item = &'a str>, b: impl Iterator<Item = &'a str>) -> f64 {
+    let a: HashSet<String> = a.map(normalize_name).collect();
+    let b: HashSet<String> = b.map(normalize_name).collect();
+    let total = a.union(&b).count();
+    if total == 0 {
+        return 0.0;
+    }
+    a.intersection(&b).count() as f64 / total as f64
+}
+
+/// Returns, for every new name, the index of the old item it continues.
+/// Exact (normalized) name matches are taken first; the rest are paired
+/// greedily by `similarity`, preferring items at nearby positions.
+pub fn match_items(
+    old: &[&str],
+    new: &[&str],
+    similarity: impl Fn(usize, usize) -> f64,
+) -> Vec<Option<usize>> {
+    let mut matched = vec![None; new.len()];
+    let mut taken = vec![false; old.len()];
+
+    let old_keys: Vec<String> = old.iter().map(|n| normalize_name(n)).collect();
+    for (i, name) in new.iter().enumerate() {
+        let key = normalize_name(name);
+        if let Some(j) = (0..old.len()).find(|&j| !taken[j] && old_keys[j] == key) {
+            matched[i] = Some(j);
+            taken[j] = true;
+        }
+    }
+
+    let mut candidates = Vec::new();
+    for (i, m) in matched.iter().enumerate() {
+        if m.is_some() {
+            continue;
+        }
+        for (j, t) in taken.iter().enumerate() {
+            if *t {
+                continue;
+            }
+            let score = similarity(i, j);
+            if score >= RENAME_THRESHOLD {
+                candidates.push((score, i, j));
+            }
+        }
+    }
+    candidates.sort_by(|a, b| {
+        b.0.partial_cmp(&a.0)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then(a.1.abs_diff(a.2).cmp(&b.1.abs_diff(b.2)))
+    });
+
+    for (_, i, j) in candidates {
+        if matched[i].is_none() && !taken[j] {
+            matched[i] = Some(j);
+            taken[j] = true;
+        }
+    }
+
+    matched
+}
+
+// ============================================================================
+// TREE MATCHING
+// ============================================================================
+
+/// `(subject, unit)` indices of a unit.
+pub type UnitKey = (usize, usize);
+/// `(subject, unit, topic)` indices of a topic.
+pub type TopicKey = (usize, usize, usize);
+
+/// Maps every node of a new syllabus to the node of the old one it
+/// continues, by index. Units and topics may map to a different parent
+/// when they were moved.
+#[derive(Debug, Clone, Default)]
+pub struct TreeMatch {
+    /// `subjects[s]` is the old subject index for new subject `s`.
+    pub subjects: Vec<Option<usize>>,
+    /// `units[s][u]` is the old `(subject, unit)` for new unit `u` of subject `s`.
+    pub units: Vec<Vec<Option<UnitKey>>>,
+    /// `topics[s][u][t]` is the old `(subject, unit, topic)` for a new topic.
+    pub topics: Vec<Vec<Vec<Option<TopicKey>>>>,
+}
+
+impl TreeMatch {
+    pub fn matched_old_subjects(&self) -> HashSet<usize> {
+        self.subjects.iter().flatten().copied().collect()
+    }
+
+    pub fn matched_old_units(&self) -> HashSet<UnitKey> {
+        self.units.iter().flatten().flatten().copied().collect()
+    }
+
+    pub fn matched_old_topics(&self) -> HashSet<TopicKey> {
+        self.topics.iter().flatten().flatten().flatten().copied().collect()
+    }
+}
+
+/// Matches `new` against `old` level by level: subjects first, then units
+/// within matched subjects, then topics within matched units. Whatever is
+/// left over is matched across the whole tree to detect moves.
+pub fn match_trees(old: &SyllabusExport, new: &SyllabusExport) -> TreeMatch {
+    let old_subject_names: Vec<&str> = old.subjects.iter().map(|s| s.name.as_str()).collect();
+    let new_subject_names: Vec<&str> = new.subjects.iter().map(|s| s.name.as_str()).collect();
+    let subjects = match_items(&old_subject_names, &new_subject_names, |i, j| {
+        let by_units = children_overlap(
+            new.subjects[i].units.iter().map(|u| u.name.as_str()),
+            old.subjects[j].units.iter().map(|u| u.name.as_str()),
+        );
+        name_similarity(new_subject_names[i], old_subject_names[j]).max(by_units)
+    });
+
+    // Units inside matched subjects
+    let mut units: Vec<Vec<Option<UnitKey>>> =
+        new.subjects.iter().map(|s| vec![None; s.units.len()]).collect();
+    for (ns, m) in subjects.iter().enumerate() {
+        if let Some(os) = *m {
+            let old_units = &old.subjects[os].units;
+            let new_units = &new.subjects[ns].units;
+            let old_names: Vec<&str> = old_units.iter().map(|u| u.name.as_str()).collect();
+            let new_names: Vec<&str> = new_units.iter().map(|u| u.name.as_str()).collect();
+            let matches = match_items(&old_names, &new_names, |i, j| {
+                let by_topics = children_overlap(
+                    new_units[i].topics.iter().map(|t| t.as_str()),
+                    old_units[j].topics.iter().map(|t| t.as_str()),
+                );
+                name_similarity(new_names[i], old_names[j]).max(by_topics)
+            });
+            for (nu, ou) in matches.into_iter().enumerate() {
+                units[ns][nu] = ou.map(|ou| (os, ou));
+            }
+        }
+    }
+
+    // Units moved to another subject
+    let taken: HashSet<UnitKey> = units.iter().flatten().flatten().copied().collect();
+    let old_left: Vec<UnitKey> = old
+        .subjects
+        .iter()
+        .enumerate()
+        .flat_map(|(s, subj)| (0..subj.units.len()).map(move |u| (s, u)))
+        .filter(|k| !taken.contains(k))
+        .collect();
+    let new_left: Vec<UnitKey> = units
+        .iter()
+        .enumerate()
+        .flat_map(|(s, us)| us.iter().enumerate().filter(|(_, m)| m.is_none()).map(move |(u, _)| (s, u)))
+        .collect();
+    let old_names: Vec<&str> = old_left.iter().map(|&(s, u)| old.subjects[s].units[u].name.as_str()).collect();
+    let new_names: Vec<&str> = new_left.iter().map(|&(s, u)| new.subjects[s].units[u].name.as_str()).collect();
+    let moved = match_items(&old_names, &new_names, |i, j| {
+        let (ns, nu) = new_left[i];
+        let (os, ou) = old_left[j];
+        let by_topics = children_overlap(
+            new.subjects[ns].units[nu].topics.iter().map(|t| t.as_str()),
+            old.subjects[os].units[ou].topics.iter().map(|t| t.as_str()),
+        );
+        name_similarity(new_names[i], old_names[j]).max(by_topics)
+    });
+    for (i, m) in moved.into_iter().enumerate() {
+        let (ns, nu) = new_left[i];
+        units[ns][nu] = m.map(|j| old_left[j]);
+    }
+
+    // Topics inside matched units
+    let mut topics: Vec<Vec<Vec<Option<TopicKey>>>> = new
+        .subjects
+        .iter()
+        .map(|s| s.units.iter().map(|u| vec![None; u.topics.len()]).collect())
+        .collect();
+    for (ns, subject_units) in units.iter().enumerate() {
+        for (nu, m) in subject_units.iter().enumerate() {
+            if let Some((os, ou)) = *m {
+                let old_topics: Vec<&str> = old.subjects[os].units[ou].topics.iter().map(|t| t.as_str()).collect();
+                let new_topics: Vec<&str> = new.subjects[ns].units[nu].topics.iter().map(|t| t.as_str()).collect();
+                let matches = match_items(&old_topics, &new_topics, |i, j| name_similarity(new_topics[i], old_topics[j]));
+                for (nt, ot) in matches.into_iter().enumerate() {
+                    topics[ns][nu][nt] = ot.map(|ot| (os, ou, ot));
+                }
+            }
+        }
+    }
+
+    // Topics moved to another unit
+    let taken: HashSet<TopicKey> = topics.iter().flatten().flatten().flatten().copied().collect();
+    let mut old_left = Vec::new();
+    for (s, subject) in old.subjects.iter().enumerate() {
+        for (u, unit) in subject.units.iter().enumerate() {
+            for t in 0..unit.topics.len() {
+                if !taken.contains(&(s, u, t)) {
+                    old_left.push((s, u, t));
+                }
+            }
+        }
+    }
+    let mut new_left = Vec::new();
+    for (s, subject_topics) in topics.iter().enumerate() {
+        for (u, unit_topics) in subject_topics.iter().enumerate() {
+            for (t, m) in unit_topics.iter().enumerate() {
+                if m.is_none() {
+                    new_left.push((s, u, t));
+                }
+            }
+        }
+    }
+    let old_names: Vec<&str> = old_left.iter().map(|&(s, u, t)| old.subjects[s].units[u].topics[t].as_str()).collect();
+    let new_names: Vec<&str> = new_left.iter().map(|&(s, u, t)| new.subjects[s].units[u].topics[t].as_str()).collect();
+    let moved = match_items(&old_names, &new_names, |i, j| name_similarity(new_names[i], old_names[j]));
+    for (i, m) in moved.into_iter().enumerate() {
+        let (ns, nu, nt) = new_left[i];
+        topics[ns][nu][nt] = m.map(|j| old_left[j]);
+    }
+
+    TreeMatch { subjects, units, topics }
+}
+
+// ============================================================================
+// CHANGE LIST
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffLevel {
+    Subject,
+    Unit,
+    Topic,
+}
+
+/// Location of a node by name; `unit` and `topic` are empty above their level.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct NodePath {
+    pub subject: String,
+    pub unit: Option<String>,
+    pub topic: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(tag = "change", rename_all = "snake_case")]
+pub enum SyllabusChange {
+    /// A new node. For subjects and units, `topic_count` counts the new
+    /// topics inside it; topics moved in are reported separately.
+    Added { level: DiffLevel, path: NodePath, topic_count: usize },
+    /// A node that no longer exists, with the topics that went with it.
+    Removed { level: DiffLevel, path: NodePath, topic_count: usize },
+    /// Same parent, different name.
+    Renamed { level: DiffLevel, from: NodePath, to: NodePath },
+    /// Different parent; the name may have changed as well.
+    Moved { level: DiffLevel, from: NodePath, to: NodePath },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SyllabusDiff {
+    pub changes: Vec<SyllabusChange>,
+    pub added_topics: usize,
+    pub removed_topics: usize,
+    pub renamed_topics: usize,
+    pub moved_topics: usize,
+    pub unchanged_topics: usize,
+}
+
+fn subject_path(subject: &str) -> NodePath {
+    NodePath { subject: subject.to_string(), unit: None, topic: None }
+}
+
+fn unit_path(subject: &str, unit: &str) -> NodePath {
+    NodePath { subject: subject.to_string(), unit: Some(unit.to_string()), topic: None }
+}
+
+fn topic_path(subject: &str, unit: &str, topic: &str) -> NodePath {
+    NodePath {
+        subject: subject.to_string(),
+        unit: Some(unit.to_string()),
+        topic: Some(topic.to_string()),
+    }
+}
+
+/// Describes how to get from `old` to `new` given a matching from
+/// [`match_trees`]. Added and removed nodes are reported at the highest
+/// level only, so a new subject is one change rather than one per topic.
+pub fn diff_from_match(old: &SyllabusExport, new: &SyllabusExport, matching: &TreeMatch) -> SyllabusDiff {
+    let mut changes = Vec::new();
+    let (mut added_topics, mut renamed_topics, mut moved_topics, mut unchanged_topics) = (0, 0, 0, 0);
+
+    for (ns, subject) in new.subjects.iter().enumerate() {
+        match matching.subjects[ns] {
+            Some(os) if old.subjects[os].name != subject.name => changes.push(SyllabusChange::Renamed {
+                level: DiffLevel::Subject,
+                from: subject_path(&old.subjects[os].name),
+                to: subject_path(&subject.name),
+            }),
+            Some(_) => {}
+            None => {
+                let new_topics = matching.topics[ns].iter().flatten().filter(|m| m.is_none()).count();
+                changes.push(SyllabusChange::Added {
+                    level: DiffLevel::Subject,
+                    path: subject_path(&subject.name),
+                    topic_count: new_topics,
+                });
+            }
+        }
+
+        for (nu, unit) in subject.units.iter().enumerate() {
+            let unit_match = matching.units[ns][nu];
+            match unit_match {
+                Some((os, ou)) => {
+                    let old_unit = &old.subjects[os].units[ou];
+                    let from = unit_path(&old.subjects[os].name, &old_unit.name);
+                    let to = unit_path(&subject.name, &unit.name);
+                    if matching.subjects[ns] != Some(os) {
+                        changes.push(SyllabusChange::Moved { level: DiffLevel::Unit, from, to });
+                    } else if old_unit.name != unit.name {
+                        changes.push(SyllabusChange::Renamed { level: DiffLevel::Unit, from, to });
+                    }
+                }
+                None if matching.subjects[ns].is_some() => {
+                    let new_topics = matching.topics[ns][nu].iter().filter(|m| m.is_none()).count();
+                    changes.push(SyllabusChange::Added {
+                        level: DiffLevel::Unit,
+                        path: unit_path(&subject.name, &unit.name),
+                        topic_count: new_topics,
+                    });
+                }
+                None => {}
+            }
+
+            for (nt, topic) in unit.topics.iter().enumerate() {
+                match matching.topics[ns][nu][nt] {
+                    Some((os, ou, ot)) => {
+                        let old_topic = &old.subjects[os].units[ou].topics[ot];
+                        let from = topic_path(&old.subjects[os].name, &old.subjects[os].units[ou].name, old_topic);
+                        let to = topic_path(&subject.name, &unit.name, topic);
+                        if unit_match != Some((os, ou)) {
+                            moved_topics += 1;
+                            changes.push(SyllabusChange::Moved { level: DiffLevel::Topic, from, to });
+                        } else if old_topic != topic {
+                            renamed_topics += 1;
+                            changes.push(SyllabusChange::Renamed { level: DiffLevel::Topic, from, to });
+                        } else {
+                            unchanged_topics += 1;
+                        }
+                    }
+                    None => {
+                        added_topics += 1;
+                        if unit_match.is_some() {
+                            changes.push(SyllabusChange::Added {
+                                level: DiffLevel::Topic,
+                                path: topic_path(&subject.name, &unit.name, topic),
+                                topic_count: 1,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // Removals, reported at the highest level that disappeared
+    let old_subjects = matching.matched_old_subjects();
+    let old_units = matching.matched_old_units();
+    let old_topics = matching.matched_old_topics();
+    let mut removed_topics = 0;
+
+    for (os, subject) in old.subjects.iter().enumerate() {
+        let subject_kept = old_subjects.contains(&os);
+        let mut subject_lost = 0;
+
+        for (ou, unit) in subject.units.iter().enumerate() {
+            let unit_kept = old_units.contains(&(os, ou));
+            let mut unit_lost = 0;
+
+            for (ot, topic) in unit.topics.iter().enumerate() {
+                if old_topics.contains(&(os, ou, ot)) {
+                    continue;
+                }
+                unit_lost += 1;
+                if unit_kept {
+                    changes.push(SyllabusChange::Removed {
+                        level: DiffLevel::Topic,
+                        path: topic_path(&subject.name, &unit.name, topic),
+                        topic_count: 1,
+                    });
+                }
+            }
+
+            removed_topics += unit_lost;
+            subject_lost += unit_lost;
+            if !unit_kept && subject_kept {
+                changes.push(SyllabusChange::Removed {
+                    level: DiffLevel::Unit,
+                    path: unit_path(&subject.name, &unit.name),
+                    topic_count: unit_lost,
+                });
+            }
+        }
+
+        if !subject_kept {
+            changes.push(SyllabusChange::Removed {
+                level: DiffLevel::Subject,
+                path: subject_path(&subject.name),
+                topic_count: subject_lost,
+            });
+        }
+    }
+
+    SyllabusDiff {
+        changes,
+        added_topics,
+        removed_topics,
+        renamed_topics,
+        moved_topics,
+        unchanged_topics,
+    }
+}
+
+pub fn diff_syllabi(old: &SyllabusExport, new: &SyllabusExport) -> SyllabusDiff {
+    let matching = match_trees(old, new);
+    diff_from_match(old, new, &matching)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::syllabus::SyllabusSubject;
+    use crate::utils::parse_syllabus;
+
+    fn syllabus(text: &str) -> SyllabusExport {
+        SyllabusExport {
+            name: "Tracker".to_string(),
+            description: None,
+            color: None,
+            version: "1.0".to_string(),
+            subjects: parse_syllabus(text).unwrap().iter().map(SyllabusSubject::from).collect(),
+        }
+    }
+
+    #[test]
+    fn test_match_items_prefers_exact_then_similar() {
+        let old = ["Process Scheduling", "Threads", "Deadlocks"];
+        let new = ["Threads", "CPU Process Scheduling", "Paging"];
+        let matches = match_items(&old, &new, |i, j| name_similarity(new[i], old[j]));
+        assert_eq!(matches, vec![Some(1), Some(0), None]);
+    }
+
+    #[test]
+    fn test_diff_reports_moves_renames_and_removals() {
+        let old = syllabus(
+            "Maths >>> Algebra >>> Groups, Rings
+             Maths >>> Geometry >>> Triangles
+             Maths >>> Calculus >>> Limits
+             Physics >>> Optics >>> Lenses",
+        );
+        let new = syllabus(
+            "Mathematics >>> Algebra >>> Groups, Fields
+             Mathematics >>> Geometry >>> Triangles
+             Analysis >>> Calculus >>> Limits, Rings",
+        );
+        let diff = diff_syllabi(&old, &new);
+
+        assert!(diff.changes.contains(&SyllabusChange::Renamed {
+            level: DiffLevel::Subject,
+            from: subject_path("Maths"),
+            to: subject_path("Mathematics"),
+        }));
+        assert!(diff.changes.contains(&SyllabusChange::Moved {
+            level: DiffLevel::Unit,
+            from: unit_path("Maths", "Calculus"),
+            to: unit_path("Analysis", "Calculus"),
+        }));
+        assert!(diff.changes.contains(&SyllabusChange::Moved {
+            level: DiffLevel::Topic,
+            from: topic_path("Maths", "Algebra", "Rings"),
+            to: topic_path("Analysis", "Calculus", "Rings"),
+        }));
+        assert!(diff.changes.contains(&SyllabusChange::Removed {
+            level: DiffLevel::Subject,
+            path: subject_path("Physics"),
+            topic_count: 1,
+        }));
+        assert_eq!(
+            (diff.added_topics, diff.removed_topics, diff.moved_topics, diff.unchanged_topics),
+            (1, 1, 1, 3)
+        );
+    }
+}
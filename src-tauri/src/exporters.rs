@@ -0,0 +1,438 @@
+use serde::{Deserialize, Serialize};
+use crate::models::{SubjectData, Tracker};
+use crate::syllabus::{is_valid_color, tracker_to_syllabus};
+use crate::utils::{to_syllabus_text, ParsedSyllabusEntry};
+
+// ============================================================================
+// EXPORTER TRAIT
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    /// The shareable `SyllabusExport` document, without progress.
+    Json,
+    /// `Subject >>> Unit >>> topics` lines, as accepted by `create_new_tracker`.
+    Text,
+    /// `# Subject`, `## Unit`, `- [x] topic` checklist.
+    Markdown,
+    /// `subject,unit,topic,completed` rows for spreadsheets.
+    Csv,
+    /// Self-contained study sheet meant for printing.
+    Html,
+}
+
+/// A rendered export, ready to be saved by the frontend.
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportedFile {
+    pub file_name: String,
+    pub mime_type: String,
+    pub content: String,
+}
+
+/// Renders a tracker and its tree in one output format.
+pub trait SyllabusExporter {
+    fn extension(&self) -> &'static str;
+    fn mime_type(&self) -> &'static str;
+    fn render(&self, tracker: &Tracker, subjects: &[SubjectData]) -> String;
+}
+
+impl ExportFormat {
+    pub fn exporter(self) -> &'static dyn SyllabusExporter {
+        match self {
+            ExportFormat::Json => &JsonExporter,
+            ExportFormat::Text => &TextExporter,
+            ExportFormat::Markdown => &MarkdownExporter,
+            ExportFormat::Csv => &CsvExporter,
+            ExportFormat::Html => &HtmlExporter,
+        }
+    }
+}
+
+/// Renders `tracker` in `format` and names the file after the tracker.
+pub fn render_tracker(tracker: &Tracker, subjects: &[SubjectData], format: ExportFormat) -> ExportedFile {
+    let exporter = format.exporter();
+    let base: String = tracker
+        .name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+
+    ExportedFile {
+        file_name: format!("{}_syllabus.{}", base, exporter.extension()),
+        mime_type: exporter.mime_type().to_string(),
+        content: exporter.render(tracker, subjects),
+    }
+}
+
+/// Completed and total topics across `subjects`.
+fn count_topics(subjects: &[SubjectData]) -> (usize, usize) {
+    let topics = subjects.iter().flat_map(|s| &s.units).flat_map(|u| &u.topics);
+    topics.fold((0, 0), |(done, total), t| (done + t.completed as usize, total + 1))
+}
+
+fn progress_line(subjects: &[SubjectData]) -> String {
+    let (done, total) = count_topics(subjects);
+    let percentage = (done * 100).checked_div(total).unwrap_or(0);
+    format!("{}/{} topics completed ({}%)", done, total, percentage)
+}
+
+// ============================================================================
+// JSON
+// ============================================================================
+
+pub struct JsonExporter;
+
+impl SyllabusExporter for JsonExporter {
+    fn extension(&self) -> &'static str {
+        "json"
+    }
+
+    fn mime_type(&self) -> &'static str {
+        "application/json"
+    }
+
+    fn render(&self, tracker: &Tracker, subjects: &[SubjectData]) -> String {
+        serde_json::to_string_pretty(&tracker_to_syllabus(tracker, subjects))
+            .expect("syllabus export is always serializable")
+    }
+}
+
+// ============================================================================
+// TEXT
+// ============================================================================
+
+pub struct TextExporter;
+
+impl SyllabusExporter for TextExporter {
+    fn extension(&self) -> &'static str {
+        "txt"
+    }
+
+    fn mime_type(&self) -> &'static str {
+        "text/plain"
+    }
+
+    fn render(&self, _tracker: &Tracker, subjects: &[SubjectData]) -> String {
+        let entries: Vec<ParsedSyllabusEntry> = subjects.iter().map(ParsedSyllabusEntry::from).collect();
+        to_syllabus_text(&entries)
+    }
+}
+
+// ============================================================================
+// MARKDOWN
+// ============================================================================
+
+pub struct MarkdownExporter;
+
+/// Backslash-escapes characters Markdown would treat as formatting, so the
+/// checklist renders as written and imports back to the same names.
+fn escape_markdown(name: &str) -> String {
+    let mut out = String::with_capacity(name.len());
+    for (idx, c) in name.chars().enumerate() {
+        let leading_marker = idx == 0 && matches!(c, '-' | '+');
+        if leading_marker || matches!(c, '\\' | '`' | '*' | '_' | '[' | ']' | '#' | '<' | '>') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+impl SyllabusExporter for MarkdownExporter {
+    fn extension(&self) -> &'static str {
+        "md"
+    }
+
+    fn mime_type(&self) -> &'static str {
+        "text/markdown"
+    }
+
+    /// The tracker name, description and progress are plain paragraphs,
+    /// which the Markdown importer skips, so the checklist can be imported
+    /// again.
+    fn render(&self, tracker: &Tracker, subjects: &[SubjectData]) -> String {
+        let mut out = format!("**{}**\n\n", escape_markdown(&tracker.name));
+        if let Some(description) = tracker.description.as_deref().filter(|d| !d.trim().is_empty()) {
+            let description = description.split_whitespace().collect::<Vec<_>>().join(" ");
+            out.push_str(&format!("> {}\n\n", escape_markdown(&description)));
+        }
+        out.push_str(&format!("Progress: {}\n", progress_line(subjects)));
+
+        for subject in subjects {
+            out.push_str(&format!("\n# {}\n", escape_markdown(&subject.subject.name)));
+            for unit in &subject.units {
+                out.push_str(&format!("\n## {}\n\n", escape_markdown(&unit.unit.name)));
+                for topic in &unit.topics {
+                    let mark = if topic.completed { "x" } else { " " };
+                    out.push_str(&format!("- [{}] {}\n", mark, escape_markdown(&topic.name)));
+                }
+            }
+        }
+        out
+    }
+}
+
+// ============================================================================
+// CSV
+// ============================================================================
+
+pub struct CsvExporter;
+
+fn csv_field(value: &str) -> String {
+    let needs_quotes = value.contains([',', '"', '\n', '\r']) || value.trim() != value;
+    if needs_quotes {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+impl SyllabusExporter for CsvExporter {
+    fn extension(&self) -> &'static str {
+        "csv"
+    }
+
+    fn mime_type(&self) -> &'static str {
+        "text/csv"
+    }
+
+    /// One row per topic. Units without topics get a row with an empty
+    /// topic, and subjects without units one with an empty unit, so they
+    /// survive a round trip through the CSV importer.
+    fn render(&self, _tracker: &Tracker, subjects: &[SubjectData]) -> String {
+        let mut out = String::from("subject,unit,topic,completed\r\n");
+        for subject in subjects {
+            if subject.units.is_empty() {
+                out.push_str(&format!("{},,,\r\n", csv_field(&subject.subject.name)));
+            }
+            for unit in &subject.units {
+                let row = |topic: &str, completed: &str| {
+                    format!(
+                        "{},{},{},{}\r\n",
+                        csv_field(&subject.subject.name),
+                        csv_field(&unit.unit.name),
+                        csv_field(topic),
+                        completed
+                    )
+                };
+                if unit.topics.is_empty() {
+                    out.push_str(&row("", ""));
+                }
+                for topic in &unit.topics {
+                    out.push_str(&row(&topic.name, if topic.completed { "true" } else { "false" }));
+                }
+            }
+        }
+        out
+    }
+}
+
+// ============================================================================
+// HTML
+// ============================================================================
+
+pub struct HtmlExporter;
+
+fn escape_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+const HTML_STYLE: &str = "
+    body { font-family: -apple-system, 'Segoe UI', Roboto, sans-serif; color: #1f2937; max-width: 800px; margin: 2rem auto; padding: 0 1rem; }
+    h1 { border-bottom: 3px solid var(--accent); padding-bottom: 0.5rem; margin-bottom: 0.25rem; }
+    .description, .progress { color: #4b5563; margin: 0.25rem 0; }
+    section { break-inside: avoid-page; margin-top: 1.5rem; }
+    h2 { color: var(--accent); margin-bottom: 0.5rem; }
+    h3 { font-size: 1rem; margin: 0.75rem 0 0.25rem; }
+    ul { list-style: none; padding-left: 0.5rem; margin: 0; }
+    li { padding: 0.15rem 0; break-inside: avoid; }
+    .box { display: inline-block; width: 0.9em; height: 0.9em; border: 1.5px solid #6b7280; border-radius: 2px; margin-right: 0.5rem; vertical-align: -0.1em; text-align: center; line-height: 0.9em; font-size: 0.85em; }
+    .done { color: #6b7280; }
+    .done .box { background: var(--accent); border-color: var(--accent); color: #fff; }
+    .count { color: #6b7280; font-weight: normal; font-size: 0.85em; }
+    @media print { body { margin: 0; max-width: none; } a { color: inherit; } }
+";
+
+impl SyllabusExporter for HtmlExporter {
+    fn extension(&self) -> &'static str {
+        "html"
+    }
+
+    fn mime_type(&self) -> &'static str {
+        "text/html"
+    }
+
+    /// A single page with inline styles and no scripts or external assets,
+    /// so it can be opened and printed offline.
+    fn render(&self, tracker: &Tracker, subjects: &[SubjectData]) -> String {
+        let accent = tracker
+            .color
+            .as_deref()
+            .filter(|c| is_valid_color(c))
+            .unwrap_or("#3b82f6");
+        let title = escape_html(&tracker.name);
+
+        let mut out = String::new();
+        out.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+        out.push_str(&format!("<title>{}</title>\n", title));
+        out.push_str(&format!("<style>\n    :root {{ --accent: {}; }}{}</style>\n", accent, HTML_STYLE));
+        out.push_str("</head>\n<body>\n");
+        out.push_str(&format!("<h1>{}</h1>\n", title));
+        if let Some(description) = tracker.description.as_deref().filter(|d| !d.trim().is_empty()) {
+            out.push_str(&format!("<p class=\"description\">{}</p>\n", escape_html(description)));
+        }
+        out.push_str(&format!("<p class=\"progress\">{}</p>\n", progress_line(subjects)));
+
+        for subject in subjects {
+            let (done, total) = count_topics(std::slice::from_ref(subject));
+            out.push_str(&format!(
+                "<section>\n<h2>{} <span class=\"count\">{}/{}</span></h2>\n",
+                escape_html(&subject.subject.name),
+                done,
+                total
+            ));
+            for unit in &subject.units {
+                out.push_str(&format!("<h3>{}</h3>\n<ul>\n", escape_html(&unit.unit.name)));
+                for topic in &unit.topics {
+                    let (class, mark) = if topic.completed { (" class=\"done\"", "&#10003;") } else { ("", "") };
+                    out.push_str(&format!(
+                        "<li{}><span class=\"box\">{}</span>{}</li>\n",
+                        class,
+                        mark,
+                        escape_html(&topic.name)
+                    ));
+                }
+                out.push_str("</ul>\n");
+            }
+            out.push_str("</section>\n");
+        }
+
+        out.push_str("</body>\n</html>\n");
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::importers::{detect_format, parse_syllabus_input, SyllabusFormat};
+    use crate::models::{Subject, Topic, Unit, UnitData};
+
+    type Outline = Vec<(String, Vec<(String, Vec<String>)>)>;
+
+    fn sample() -> (Tracker, Vec<SubjectData>) {
+        let tracker = Tracker {
+            id: "tr".to_string(),
+            semester_id: "sem".to_string(),
+            name: "Sem 4 <CSE>".to_string(),
+            description: Some("Core courses\n1. Maths".to_string()),
+            color: Some("red; }".to_string()),
+            total_subjects: 2,
+            total_units: 2,
+            total_topics: 2,
+            created_at: 0,
+            updated_at: 0,
+        };
+        let topic = |name: &str, completed: bool| Topic {
+            id: name.to_string(),
+            unit_id: "u1".to_string(),
+            name: name.to_string(),
+            completed,
+            order: 0,
+            created_at: 0,
+            updated_at: 0,
+        };
+        let unit = |name: &str, topics: Vec<Topic>| UnitData {
+            unit: Unit {
+                id: name.to_string(),
+                subject_id: "s1".to_string(),
+                name: name.to_string(),
+                order: 0,
+                created_at: 0,
+                updated_at: 0,
+            },
+            topics,
+        };
+        let subject = |id: &str, name: &str, units: Vec<UnitData>| SubjectData {
+            subject: Subject {
+                id: id.to_string(),
+                tracker_id: "tr".to_string(),
+                name: name.to_string(),
+                credits: None,
+                evaluation_scheme_id: None,
+                created_at: 0,
+                updated_at: 0,
+            },
+            units,
+        };
+        let subjects = vec![
+            subject("s1", "C#", vec![
+                unit("Basics", vec![topic("Types, values & *refs*", true), topic("- Generics", false)]),
+                unit("Interop with F#", Vec::new()),
+            ]),
+            // A subject without units must survive every text format too
+            subject("s2", "- Electives", Vec::new()),
+        ];
+        (tracker, subjects)
+    }
+
+    fn outline(subjects: &[SubjectData]) -> Outline {
+        subjects
+            .iter()
+            .map(|s| {
+                let units = s
+                    .units
+                    .iter()
+                    .map(|u| (u.unit.name.clone(), u.topics.iter().map(|t| t.name.clone()).collect()))
+                    .collect();
+                (s.subject.name.clone(), units)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_markdown_and_csv_import_back() {
+        let (tracker, subjects) = sample();
+        for (format, import_as) in [
+            (ExportFormat::Text, SyllabusFormat::Text),
+            (ExportFormat::Markdown, SyllabusFormat::Markdown),
+            (ExportFormat::Csv, SyllabusFormat::Csv),
+        ] {
+            let file = render_tracker(&tracker, &subjects, format);
+            assert_eq!(detect_format(&file.content), import_as);
+            let parsed = parse_syllabus_input(&file.content, None).unwrap();
+            let parsed_outline: Outline = parsed
+                .into_iter()
+                .map(|s| (s.subject_name, s.units.into_iter().map(|u| (u.unit_name, u.topics)).collect()))
+                .collect();
+            assert_eq!(parsed_outline, outline(&subjects), "{:?}", format);
+        }
+
+        let markdown = render_tracker(&tracker, &subjects, ExportFormat::Markdown);
+        assert_eq!(markdown.file_name, "Sem_4__CSE__syllabus.md");
+        assert!(markdown.content.contains("- [x] Types, values & \\*refs\\*\n- [ ] \\- Generics\n"));
+        assert!(markdown.content.contains("# C\\#\n") && markdown.content.contains("## Interop with F\\#\n"));
+    }
+
+    #[test]
+    fn test_html_is_escaped() {
+        let (tracker, subjects) = sample();
+        let html = render_tracker(&tracker, &subjects, ExportFormat::Html).content;
+        assert!(html.contains("<h1>Sem 4 &lt;CSE&gt;</h1>"));
+        assert!(html.contains("--accent: #3b82f6;"));
+        assert!(html.contains("<li class=\"done\"><span class=\"box\">&#10003;</span>Types, values &amp; *refs*</li>"));
+        assert!(html.contains("1/2 topics completed (50%)"));
+    }
+}
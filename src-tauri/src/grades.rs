@@ -0,0 +1,691 @@
+use rusqlite::Result as SqliteResult;
+use serde::Serialize;
+use std::collections::HashSet;
+use crate::db::Database;
+use crate::models::*;
+
+// ============================================================================
+// VALIDATION
+// ============================================================================
+
+/// Checks a pair of marks, returning `None` when both are cleared.
+pub fn validate_marks(obtained: Option<f64>, max: Option<f64>) -> Result<Option<(f64, f64)>, String> {
+    match (obtained, max) {
+        (None, None) => Ok(None),
+        (Some(obtained), Some(max)) => {
+            if !max.is_finite() || max <= 0.0 {
+                return Err("Maximum marks must be positive".to_string());
+            }
+            if !obtained.is_finite() || !(0.0..=max).contains(&obtained) {
+                return Err("Marks must be between 0 and the maximum".to_string());
+            }
+            Ok(Some((obtained, max)))
+        }
+        _ => Err("Enter both the marks obtained and the maximum marks".to_string()),
+    }
+}
+
+/// Trims names and sorts bands from the highest cut-off down.
+pub fn validate_grading_scale(mut input: GradingScaleInput) -> Result<GradingScaleInput, String> {
+    input.name = input.name.trim().to_string();
+    if input.name.is_empty() {
+        return Err("Grading scale name cannot be empty".to_string());
+    }
+    if input.bands.is_empty() {
+        return Err("A grading scale needs at least one grade".to_string());
+    }
+
+    let mut letters = HashSet::new();
+    for band in &mut input.bands {
+        band.letter = band.letter.trim().to_string();
+        if band.letter.is_empty() {
+            return Err("Grade letters cannot be empty".to_string());
+        }
+        if !letters.insert(band.letter.to_lowercase()) {
+            return Err(format!("Grade '{}' appears twice", band.letter));
+        }
+        if !band.min_percentage.is_finite() || !(0.0..=100.0).contains(&band.min_percentage) {
+            return Err(format!("The cut-off for '{}' must be between 0 and 100", band.letter));
+        }
+        if !band.grade_points.is_finite() || band.grade_points < 0.0 {
+            return Err(format!("Grade points for '{}' cannot be negative", band.letter));
+        }
+    }
+    input.bands.sort_by(|a, b| b.min_percentage.total_cmp(&a.min_percentage));
+    Ok(input)
+}
+
+/// Checks a scheme against the test types and grading scales that exist.
+pub fn validate_evaluation_scheme(
+    mut input: EvaluationSchemeInput,
+    test_types: &[TestType],
+    scales: &[GradingScale],
+) -> Result<EvaluationSchemeInput, String> {
+    input.name = input.name.trim().to_string();
+    if input.name.is_empty() {
+        return Err("Evaluation scheme name cannot be empty".to_string());
+    }
+    if !scales.iter().any(|s| s.id == input.grading_scale_id) {
+        return Err("Grading scale not found".to_string());
+    }
+    if input.components.is_empty() {
+        return Err("An evaluation scheme needs at least one component".to_string());
+    }
+
+    let mut seen = HashSet::new();
+    for component in &input.components {
+        if !test_types.iter().any(|t| t.id == component.test_type_id) {
+            return Err(format!("Unknown test type '{}'", component.test_type_id));
+        }
+        if !seen.insert(component.test_type_id.as_str()) {
+            return Err(format!("Test type '{}' appears twice", component.test_type_id));
+        }
+        if !component.weight.is_finite() || component.weight <= 0.0 {
+            return Err("Component weights must be positive".to_string());
+        }
+        if component.best_of.is_some_and(|n| n < 1) {
+            return Err("Best-of counts must be at least 1".to_string());
+        }
+    }
+    Ok(input)
+}
+
+// ============================================================================
+// GRADE CALCULATION
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ComponentResult {
+    pub test_type_id: String,
+    pub weight: f64,
+    pub best_of: Option<i32>,
+    /// Average over the counted tests, or `None` before any is marked.
+    pub percentage: Option<f64>,
+    pub marked_tests: usize,
+    pub unmarked_tests: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SubjectGrade {
+    pub subject_id: String,
+    pub subject_name: String,
+    pub tracker_id: String,
+    pub credits: Option<f64>,
+    pub evaluation_scheme_id: Option<String>,
+    pub components: Vec<ComponentResult>,
+    /// Weighted over the components marked so far.
+    pub percentage: Option<f64>,
+    /// Whether every component is marked and no test is waiting for marks.
+    pub complete: bool,
+    pub letter: Option<String>,
+    pub grade_points: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SemesterGrades {
+    pub semester_id: String,
+    pub semester_name: String,
+    pub subjects: Vec<SubjectGrade>,
+    /// Credits of the subjects that have grade points.
+    pub credits: f64,
+    pub sgpa: Option<f64>,
+    /// Whether the SGPA includes grades that may still change.
+    pub provisional: bool,
+    /// Marked tests that belong to no subject and so count towards no grade.
+    pub unassigned_tests: Vec<Test>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GradeReport {
+    pub semesters: Vec<SemesterGrades>,
+    pub credits: f64,
+    pub cgpa: Option<f64>,
+    pub provisional: bool,
+}
+
+pub fn test_percentage(test: &Test) -> Option<f64> {
+    match (test.marks_obtained, test.max_marks) {
+        (Some(obtained), Some(max)) if max > 0.0 => Some(obtained / max * 100.0),
+        _ => None,
+    }
+}
+
+/// Scores a component from the subject's tests of its type.
+pub fn component_result(component: &EvaluationComponent, tests: &[Test]) -> ComponentResult {
+    let of_type: Vec<&Test> = tests
+        .iter()
+        .filter(|t| t.test_type == component.test_type_id)
+        .collect();
+    let mut percentages: Vec<f64> = of_type.iter().filter_map(|t| test_percentage(t)).collect();
+    percentages.sort_by(|a, b| b.total_cmp(a));
+    if let Some(n) = component.best_of {
+        percentages.truncate(n.max(1) as usize);
+    }
+
+    let percentage = if percentages.is_empty() {
+        None
+    } else {
+        Some(percentages.iter().sum::<f64>() / percentages.len() as f64)
+    };
+    let marked_tests = of_type.iter().filter(|t| test_percentage(t).is_some()).count();
+    ComponentResult {
+        test_type_id: component.test_type_id.clone(),
+        weight: component.weight,
+        best_of: component.best_of,
+        percentage,
+        marked_tests,
+        unmarked_tests: of_type.len() - marked_tests,
+    }
+}
+
+/// The band a percentage falls in. Bands are sorted highest first; a
+/// percentage below every cut-off gets no grade.
+pub fn band_for(scale: &GradingScale, percentage: f64) -> Option<&GradeBand> {
+    // Tolerate float error so 80% doesn't become 79.999...% and drop a band
+    scale
+        .bands
+        .iter()
+        .find(|band| percentage + 1e-9 >= band.min_percentage)
+}
+
+pub fn compute_subject_grade(
+    subject: &Subject,
+    scheme: Option<&EvaluationScheme>,
+    scale: Option<&GradingScale>,
+    tests: &[Test],
+) -> SubjectGrade {
+    let components: Vec<ComponentResult> = scheme
+        .map(|s| s.components.iter().map(|c| component_result(c, tests)).collect())
+        .unwrap_or_default();
+
+    let (weighted, weight) = components
+        .iter()
+        .filter_map(|c| c.percentage.map(|p| (p * c.weight, c.weight)))
+        .fold((0.0, 0.0), |(sum, total), (p, w)| (sum + p, total + w));
+    let percentage = (weight > 0.0).then(|| weighted / weight);
+    let band = percentage.zip(scale).and_then(|(p, scale)| band_for(scale, p));
+
+    SubjectGrade {
+        subject_id: subject.id.clone(),
+        subject_name: subject.name.clone(),
+        tracker_id: subject.tracker_id.clone(),
+        credits: subject.credits,
+        evaluation_scheme_id: subject.evaluation_scheme_id.clone(),
+        complete: !components.is_empty()
+            && components.iter().all(|c| c.percentage.is_some() && c.unmarked_tests == 0),
+        components,
+        percentage,
+        letter: band.map(|b| b.letter.clone()),
+        grade_points: band.map(|b| b.grade_points),
+    }
+}
+
+/// Credit-weighted grade point average over the subjects that have both
+/// credits and grade points. Returns the credits counted, the average and
+/// whether any counted grade is incomplete.
+pub fn weighted_gpa<'a>(grades: impl IntoIterator<Item = &'a SubjectGrade>) -> (f64, Option<f64>, bool) {
+    let mut credits = 0.0;
+    let mut points = 0.0;
+    let mut provisional = false;
+    for grade in grades {
+        if let (Some(c), Some(p)) = (grade.credits, grade.grade_points) {
+            if c > 0.0 {
+                credits += c;
+                points += c * p;
+                provisional |= !grade.complete;
+            }
+        }
+    }
+    let gpa = (credits > 0.0).then(|| points / credits);
+    (credits, gpa, provisional)
+}
+
+// ============================================================================
+// LOADING
+// ============================================================================
+
+/// The schemes and scales subjects refer to, loaded once per report.
+pub struct GradingConfig {
+    schemes: Vec<EvaluationScheme>,
+    scales: Vec<GradingScale>,
+}
+
+impl GradingConfig {
+    pub fn load(db: &Database) -> SqliteResult<Self> {
+        Ok(GradingConfig {
+            schemes: db.get_all_evaluation_schemes()?,
+            scales: db.get_all_grading_scales()?,
+        })
+    }
+
+    pub fn scheme(&self, id: Option<&str>) -> Option<&EvaluationScheme> {
+        id.and_then(|id| self.schemes.iter().find(|s| s.id == id))
+    }
+
+    pub fn scale_for(&self, scheme: Option<&EvaluationScheme>) -> Option<&GradingScale> {
+        scheme.and_then(|scheme| self.scales.iter().find(|s| s.id == scheme.grading_scale_id))
+    }
+
+    pub fn subject_grade(&self, db: &Database, subject: &Subject) -> SqliteResult<SubjectGrade> {
+        let scheme = self.scheme(subject.evaluation_scheme_id.as_deref());
+        let tests = db.get_tests_by_subject(&subject.id)?;
+        Ok(compute_subject_grade(subject, scheme, self.scale_for(scheme), &tests))
+    }
+}
+
+pub fn semester_grades(db: &Database, config: &GradingConfig, semester: &Semester) -> SqliteResult<SemesterGrades> {
+    let mut subjects = Vec::new();
+    let mut unassigned_tests = Vec::new();
+    for tracker in db.get_trackers_by_semester(&semester.id)? {
+        for subject in db.get_subjects_by_tracker(&tracker.id)? {
+            subjects.push(config.subject_grade(db, &subject)?);
+        }
+        unassigned_tests.extend(
+            db.get_tests_by_tracker(&tracker.id)?
+                .into_iter()
+                .filter(|t| t.subject_id.is_none() && t.marks_obtained.is_some()),
+        );
+    }
+    let (credits, sgpa, provisional) = weighted_gpa(&subjects);
+    Ok(SemesterGrades {
+        semester_id: semester.id.clone(),
+        semester_name: semester.name.clone(),
+        subjects,
+        credits,
+        sgpa,
+        provisional,
+        unassigned_tests,
+    })
+}
+
+/// Grades for every semester, oldest first, and the CGPA across them. The
+/// CGPA weights each subject by its credits rather than averaging SGPAs.
+pub fn grade_report(db: &Database) -> SqliteResult<GradeReport> {
+    let config = GradingConfig::load(db)?;
+    let mut semesters = Vec::new();
+    for semester in db.get_all_semesters()?.iter().rev() {
+        semesters.push(semester_grades(db, &config, semester)?);
+    }
+    let (credits, cgpa, provisional) = weighted_gpa(semesters.iter().flat_map(|s| &s.subjects));
+    Ok(GradeReport {
+        semesters,
+        credits,
+        cgpa,
+        provisional,
+    })
+}
+
+// ============================================================================
+// GRADE TARGETS
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TargetOutcome {
+    /// The target is met even with zero on everything left.
+    Secured,
+    Reachable,
+    /// Not even full marks on everything left would reach it.
+    Unreachable,
+}
+
+/// A test that still counts towards a subject's grade. Components with no
+/// test scheduled yet count as one unscheduled test.
+#[derive(Debug, Clone, Serialize)]
+pub struct RemainingTest {
+    pub test_type_id: String,
+    pub test_id: Option<String>,
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SubjectTarget {
+    pub subject_id: String,
+    pub subject_name: String,
+    pub target_letter: String,
+    pub target_percentage: f64,
+    pub current_percentage: Option<f64>,
+    pub outcome: TargetOutcome,
+    /// The percentage needed on every remaining test, rounded up to two
+    /// decimals. `None` when unreachable.
+    pub required_percentage: Option<f64>,
+    /// The final percentage with full marks on everything left.
+    pub best_possible_percentage: f64,
+    pub remaining: Vec<RemainingTest>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SemesterTargets {
+    pub semester_id: String,
+    pub target_letter: String,
+    pub subjects: Vec<SubjectTarget>,
+    /// Subjects without an evaluation scheme, or whose scale lacks the
+    /// grade, each with the reason.
+    pub skipped: Vec<(String, String)>,
+}
+
+/// The final percentage if every remaining test scores `score` percent.
+fn projected_percentage(
+    scheme: &EvaluationScheme,
+    marked: &[(String, f64)],
+    remaining: &[RemainingTest],
+    score: f64,
+) -> f64 {
+    let mut weighted = 0.0;
+    let mut weight = 0.0;
+    for component in &scheme.components {
+        let mut percentages: Vec<f64> = marked
+            .iter()
+            .filter(|(test_type, _)| test_type == &component.test_type_id)
+            .map(|(_, p)| *p)
+            .chain(
+                remaining
+                    .iter()
+                    .filter(|r| r.test_type_id == component.test_type_id)
+                    .map(|_| score),
+            )
+            .collect();
+        percentages.sort_by(|a, b| b.total_cmp(a));
+        if let Some(n) = component.best_of {
+            percentages.truncate(n.max(1) as usize);
+        }
+        if !percentages.is_empty() {
+            weighted += component.weight * percentages.iter().sum::<f64>() / percentages.len() as f64;
+            weight += component.weight;
+        }
+    }
+    if weight > 0.0 { weighted / weight } else { 0.0 }
+}
+
+/// The band for `letter`, ignoring case and surrounding spaces.
+fn find_band<'a>(scale: &'a GradingScale, letter: &str) -> Option<&'a GradeBand> {
+    scale.bands.iter().find(|b| b.letter.eq_ignore_ascii_case(letter.trim()))
+}
+
+/// Works out the lowest uniform score on the remaining tests that earns
+/// `letter`. The projection only grows with the score, so a bisection
+/// finds it.
+pub fn compute_target(
+    subject: &Subject,
+    scheme: &EvaluationScheme,
+    scale: &GradingScale,
+    tests: &[Test],
+    letter: &str,
+) -> Result<SubjectTarget, String> {
+    let band = find_band(scale, letter)
+        .ok_or_else(|| format!("Grade '{}' is not in the '{}' scale", letter.trim(), scale.name))?;
+    let target = band.min_percentage;
+
+    let mut marked = Vec::new();
+    let mut remaining = Vec::new();
+    for component in &scheme.components {
+        let of_type: Vec<&Test> = tests
+            .iter()
+            .filter(|t| t.test_type == component.test_type_id)
+            .collect();
+        if of_type.is_empty() {
+            remaining.push(RemainingTest {
+                test_type_id: component.test_type_id.clone(),
+                test_id: None,
+                name: None,
+            });
+        }
+        for test in of_type {
+            match test_percentage(test) {
+                Some(p) => marked.push((test.test_type.clone(), p)),
+                None => remaining.push(RemainingTest {
+                    test_type_id: test.test_type.clone(),
+                    test_id: Some(test.id.clone()),
+                    name: Some(test.name.clone()),
+                }),
+            }
+        }
+    }
+
+    let meets = |score: f64| projected_percentage(scheme, &marked, &remaining, score) + 1e-9 >= target;
+    let best_possible_percentage = projected_percentage(scheme, &marked, &remaining, 100.0);
+    let (outcome, required_percentage) = if meets(0.0) {
+        (TargetOutcome::Secured, Some(0.0))
+    } else if remaining.is_empty() || !meets(100.0) {
+        (TargetOutcome::Unreachable, None)
+    } else {
+        let (mut low, mut high) = (0.0, 100.0);
+        for _ in 0..50 {
+            let mid = (low + high) / 2.0;
+            if meets(mid) { high = mid } else { low = mid }
+        }
+        let rounded = ((high * 100.0).ceil() / 100.0).min(100.0);
+        (TargetOutcome::Reachable, Some(rounded))
+    };
+
+    Ok(SubjectTarget {
+        subject_id: subject.id.clone(),
+        subject_name: subject.name.clone(),
+        target_letter: band.letter.clone(),
+        target_percentage: target,
+        current_percentage: compute_subject_grade(subject, Some(scheme), Some(scale), tests).percentage,
+        outcome,
+        required_percentage,
+        best_possible_percentage,
+        remaining,
+    })
+}
+
+impl GradingConfig {
+    /// The target for one subject, or why it can't be worked out.
+    pub fn subject_target(&self, db: &Database, subject: &Subject, letter: &str) -> Result<SubjectTarget, String> {
+        let scheme = self
+            .scheme(subject.evaluation_scheme_id.as_deref())
+            .ok_or_else(|| format!("'{}' has no evaluation scheme", subject.name))?;
+        let scale = self
+            .scale_for(Some(scheme))
+            .ok_or_else(|| format!("The scheme for '{}' has no grading scale", subject.name))?;
+        let tests = db.get_tests_by_subject(&subject.id).map_err(|e| e.to_string())?;
+        compute_target(subject, scheme, scale, &tests, letter)
+    }
+}
+
+/// The same target grade worked out for every subject in a semester.
+/// Subjects that can't have the grade are skipped with the reason, but a
+/// grade that none of the semester's scales has is an error.
+pub fn semester_targets(db: &Database, semester_id: &str, letter: &str) -> Result<SemesterTargets, String> {
+    let config = GradingConfig::load(db).map_err(|e| e.to_string())?;
+    let mut subjects = Vec::new();
+    let mut skipped = Vec::new();
+    let mut uses_scale = false;
+    for tracker in db.get_trackers_by_semester(semester_id).map_err(|e| e.to_string())? {
+        for subject in db.get_subjects_by_tracker(&tracker.id).map_err(|e| e.to_string())? {
+            let Some(scheme) = config.scheme(subject.evaluation_scheme_id.as_deref()) else {
+                skipped.push((subject.name, "No evaluation scheme".to_string()));
+                continue;
+            };
+            let Some(scale) = config.scale_for(Some(scheme)) else {
+                skipped.push((subject.name, "Its scheme has no grading scale".to_string()));
+                continue;
+            };
+            uses_scale = true;
+            if find_band(scale, letter).is_none() {
+                let reason = format!("Grade '{}' is not in the '{}' scale", letter.trim(), scale.name);
+                skipped.push((subject.name, reason));
+                continue;
+            }
+            let tests = db.get_tests_by_subject(&subject.id).map_err(|e| e.to_string())?;
+            subjects.push(compute_target(&subject, scheme, scale, &tests, letter)?);
+        }
+    }
+    if uses_scale && subjects.is_empty() {
+        return Err(format!("Grade '{}' is not in any grading scale used this semester", letter.trim()));
+    }
+    Ok(SemesterTargets {
+        semester_id: semester_id.to_string(),
+        target_letter: letter.trim().to_string(),
+        subjects,
+        skipped,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn marked(db: &Database, id: &str, test_type: &str, marks: Option<(f64, f64)>, unit: &str) {
+        db.create_test(id.into(), "tr".into(), id.into(), test_type.into(), 0, 0).unwrap();
+        db.create_test_coverage(format!("c-{}", id), id.into(), Some(unit.into()), None).unwrap();
+        db.assign_test_subjects_from_coverage(Some(id)).unwrap();
+        db.set_test_marks(id, marks, 0).unwrap();
+    }
+
+    #[test]
+    fn test_grade_report() {
+        let db = Database::new(PathBuf::from(":memory:")).unwrap();
+        db.create_semester("sem".into(), "Sem 4".into(), 0).unwrap();
+        db.create_tracker("tr".into(), "sem".into(), "Sem 4".into(), None, None, 0).unwrap();
+        db.create_subject("os".into(), "tr".into(), "OS".into(), 0).unwrap();
+        db.create_unit("u1".into(), "os".into(), "Processes".into(), 0, 0).unwrap();
+        db.create_subject("cn".into(), "tr".into(), "Networks".into(), 0).unwrap();
+        db.create_unit("u2".into(), "cn".into(), "Routing".into(), 0, 0).unwrap();
+        db.create_subject("lab".into(), "tr".into(), "Seminar".into(), 0).unwrap();
+
+        let scheme = EvaluationSchemeInput {
+            name: "Best ISA".into(),
+            grading_scale_id: "ten_point".into(),
+            components: vec![
+                EvaluationComponent { test_type_id: "isa".into(), weight: 40.0, best_of: Some(1) },
+                EvaluationComponent { test_type_id: "esa".into(), weight: 60.0, best_of: None },
+            ],
+        };
+        db.create_evaluation_scheme("best".into(), &scheme, 0).unwrap();
+        db.set_subject_grading("os", Some(4.0), Some("best"), 0).unwrap();
+        db.set_subject_grading("cn", Some(2.0), Some("isa_esa"), 0).unwrap();
+        db.set_subject_grading("lab", Some(1.0), None, 0).unwrap();
+
+        // OS: best ISA 90%, ESA 70% -> 78% (B); networks: ISA 80%, ESA not yet written
+        marked(&db, "os-isa1", "isa", Some((18.0, 20.0)), "u1");
+        marked(&db, "os-isa2", "isa", Some((10.0, 20.0)), "u1");
+        marked(&db, "os-esa", "esa", Some((70.0, 100.0)), "u1");
+        marked(&db, "cn-isa", "isa", Some((40.0, 50.0)), "u2");
+        marked(&db, "cn-esa", "esa", None, "u2");
+
+        let report = grade_report(&db).unwrap();
+        let semester = &report.semesters[0];
+        let os = &semester.subjects[0];
+        assert!((os.percentage.unwrap() - 78.0).abs() < 1e-9);
+        assert_eq!((os.letter.as_deref(), os.grade_points, os.complete), (Some("B"), Some(8.0), true));
+        let cn = &semester.subjects[1];
+        assert_eq!((cn.letter.as_deref(), cn.complete), (Some("A"), false));
+        assert_eq!(cn.components[1].unmarked_tests, 1);
+        assert!(semester.subjects[2].grade_points.is_none());
+
+        // (4 * 8 + 2 * 9) / 6, with the ungraded seminar left out
+        assert_eq!(semester.credits, 6.0);
+        assert!((semester.sgpa.unwrap() - 50.0 / 6.0).abs() < 1e-9);
+        assert!(semester.provisional);
+        assert_eq!(report.cgpa, semester.sgpa);
+        assert!(semester.unassigned_tests.is_empty());
+
+        // A test spanning two subjects counts for neither until given one
+        db.create_test("quiz".into(), "tr".into(), "Quiz".into(), "isa".into(), 0, 0).unwrap();
+        db.create_test_coverage("c-quiz1".into(), "quiz".into(), Some("u1".into()), None).unwrap();
+        db.create_test_coverage("c-quiz2".into(), "quiz".into(), Some("u2".into()), None).unwrap();
+        db.assign_test_subjects_from_coverage(Some("quiz")).unwrap();
+        db.set_test_marks("quiz", Some((0.0, 50.0)), 0).unwrap();
+        let semester = &grade_report(&db).unwrap().semesters[0];
+        assert_eq!(semester.unassigned_tests[0].id, "quiz");
+        assert_eq!(semester.subjects[1].components[0].marked_tests, 1);
+
+        db.set_test_subject("quiz", Some("cn"), 0).unwrap();
+        let semester = &grade_report(&db).unwrap().semesters[0];
+        assert!(semester.unassigned_tests.is_empty());
+        assert_eq!(semester.subjects[1].components[0].marked_tests, 2);
+        assert_eq!(semester.subjects[0].components[0].marked_tests, 2);
+    }
+
+    #[test]
+    fn test_validation() {
+        assert_eq!(validate_marks(None, None), Ok(None));
+        assert!(validate_marks(Some(21.0), Some(20.0)).is_err());
+        assert!(validate_marks(Some(5.0), None).is_err());
+
+        let scale = validate_grading_scale(GradingScaleInput {
+            name: " Pass/Fail ".into(),
+            bands: vec![
+                GradeBand { letter: "F".into(), min_percentage: 0.0, grade_points: 0.0 },
+                GradeBand { letter: "P".into(), min_percentage: 40.0, grade_points: 10.0 },
+            ],
+        })
+        .unwrap();
+        assert_eq!(scale.name, "Pass/Fail");
+        assert_eq!(scale.bands[0].letter, "P");
+
+        let duplicate = GradingScaleInput {
+            name: "Dup".into(),
+            bands: vec![
+                GradeBand { letter: "A".into(), min_percentage: 50.0, grade_points: 1.0 },
+                GradeBand { letter: "a".into(), min_percentage: 0.0, grade_points: 0.0 },
+            ],
+        };
+        assert!(validate_grading_scale(duplicate).is_err());
+    }
+
+    #[test]
+    fn test_grade_targets() {
+        let db = Database::new(PathBuf::from(":memory:")).unwrap();
+        db.create_semester("sem".into(), "Sem 4".into(), 0).unwrap();
+        db.create_tracker("tr".into(), "sem".into(), "Sem 4".into(), None, None, 0).unwrap();
+        db.create_subject("os".into(), "tr".into(), "OS".into(), 0).unwrap();
+        db.create_unit("u1".into(), "os".into(), "Processes".into(), 0, 0).unwrap();
+        db.create_subject("misc".into(), "tr".into(), "Misc".into(), 0).unwrap();
+        db.set_subject_grading("os", Some(4.0), Some("isa_esa"), 0).unwrap();
+
+        // ISA 80% and 60% (70% on average), the ESA not scheduled yet
+        marked(&db, "isa1", "isa", Some((16.0, 20.0)), "u1");
+        marked(&db, "isa2", "isa", Some((12.0, 20.0)), "u1");
+
+        let config = GradingConfig::load(&db).unwrap();
+        let os = db.get_subject("os").unwrap().unwrap();
+        let target = config.subject_target(&db, &os, "a").unwrap();
+        assert_eq!((target.target_letter.as_str(), target.outcome), ("A", TargetOutcome::Reachable));
+        assert_eq!(target.required_percentage, Some(90.0));
+        assert_eq!(target.remaining.len(), 1);
+        assert!(target.remaining[0].test_id.is_none());
+
+        let target = config.subject_target(&db, &os, "S").unwrap();
+        assert_eq!((target.outcome, target.best_possible_percentage), (TargetOutcome::Unreachable, 85.0));
+        assert_eq!(config.subject_target(&db, &os, "F").unwrap().outcome, TargetOutcome::Secured);
+        assert!(config.subject_target(&db, &os, "Z").is_err());
+
+        // A scheduled but unmarked ESA is what's left to score on
+        marked(&db, "esa", "esa", None, "u1");
+        let target = config.subject_target(&db, &os, "B").unwrap();
+        assert_eq!(target.required_percentage, Some(70.0));
+        assert_eq!(target.remaining[0].test_id.as_deref(), Some("esa"));
+
+        let pass_fail = GradingScaleInput {
+            name: "Pass/Fail".into(),
+            bands: vec![
+                GradeBand { letter: "P".into(), min_percentage: 40.0, grade_points: 1.0 },
+                GradeBand { letter: "F".into(), min_percentage: 0.0, grade_points: 0.0 },
+            ],
+        };
+        db.create_grading_scale("pf".into(), &pass_fail, 0).unwrap();
+        let scheme = EvaluationSchemeInput {
+            name: "Lab".into(),
+            grading_scale_id: "pf".into(),
+            components: vec![EvaluationComponent { test_type_id: "esa".into(), weight: 100.0, best_of: None }],
+        };
+        db.create_evaluation_scheme("lab".into(), &scheme, 0).unwrap();
+        db.create_subject("lab".into(), "tr".into(), "Lab".into(), 0).unwrap();
+        db.set_subject_grading("lab", Some(1.0), Some("lab"), 0).unwrap();
+
+        let semester = semester_targets(&db, "sem", "B").unwrap();
+        assert_eq!(semester.subjects.len(), 1);
+        let skipped: Vec<&str> = semester.skipped.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(skipped, vec!["Misc", "Lab"]);
+        assert_eq!(semester.skipped[1].1, "Grade 'B' is not in the 'Pass/Fail' scale");
+
+        let passing = semester_targets(&db, "sem", "p").unwrap();
+        assert_eq!(passing.subjects[0].subject_name, "Lab");
+        assert!(semester_targets(&db, "sem", "Z").is_err());
+    }
+}
@@ -0,0 +1,645 @@
+use chrono::{DateTime, Days, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use rusqlite::Result as SqliteResult;
+use serde::{Deserialize, Serialize};
+use crate::db::Database;
+use crate::diff::{name_similarity, RENAME_THRESHOLD};
+use crate::exporters::ExportedFile;
+use crate::models::*;
+use crate::utils::{get_covered_topic_names, local_day, normalize_name};
+
+// ============================================================================
+// CALENDAR EXPORT
+// ============================================================================
+
+/// Which tests go into an exported calendar.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "scope", rename_all = "snake_case")]
+pub enum CalendarScope {
+    Tracker { tracker_id: String },
+    Semester { semester_id: String },
+    All,
+}
+
+/// A test with what its calendar event needs besides the row itself.
+#[derive(Debug, Clone)]
+pub struct CalendarTest {
+    pub test: Test,
+    /// The type's name, or its ID if the type was deleted.
+    pub type_name: String,
+    pub tracker_name: String,
+    pub covered_topics: Vec<String>,
+}
+
+/// Loads the tests in `scope` with their covered topics, along with a name
+/// for the calendar. Returns `None` if the tracker or semester is missing.
+pub fn collect_calendar_tests(
+    db: &Database,
+    scope: &CalendarScope,
+) -> SqliteResult<Option<(String, Vec<CalendarTest>)>> {
+    let (calendar_name, trackers) = match scope {
+        CalendarScope::Tracker { tracker_id } => match db.get_tracker(tracker_id)? {
+            Some(tracker) => (tracker.name.clone(), vec![tracker]),
+            None => return Ok(None),
+        },
+        CalendarScope::Semester { semester_id } => {
+            let semesters = db.get_all_semesters()?;
+            match semesters.into_iter().find(|s| &s.id == semester_id) {
+                Some(semester) => (semester.name, db.get_trackers_by_semester(semester_id)?),
+                None => return Ok(None),
+            }
+        }
+        CalendarScope::All => {
+            let mut trackers = Vec::new();
+            for semester in db.get_all_semesters()? {
+                trackers.extend(db.get_trackers_by_semester(&semester.id)?);
+            }
+            ("StudyApp Tests".to_string(), trackers)
+        }
+    };
+
+    let test_types = db.get_all_test_types()?;
+    let mut tests = Vec::new();
+    for tracker in trackers {
+        for test in db.get_tests_by_tracker(&tracker.id)? {
+            let coverage = db.get_test_coverage(&test.id)?;
+            let type_name = test_types
+                .iter()
+                .find(|t| t.id == test.test_type)
+                .map_or_else(|| test.test_type.clone(), |t| t.name.clone());
+            tests.push(CalendarTest {
+                type_name,
+                covered_topics: get_covered_topic_names(db, &coverage)?,
+                tracker_name: tracker.name.clone(),
+                test,
+            });
+        }
+    }
+    tests.sort_by_key(|t| t.test.scheduled_date);
+
+    Ok(Some((calendar_name, tests)))
+}
+
+/// Escapes a TEXT value (RFC 5545 section 3.3.11).
+fn escape_text(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            ';' => out.push_str("\\;"),
+            ',' => out.push_str("\\,"),
+            '\n' => out.push_str("\\n"),
+            '\r' => {}
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Folds a content line so no physical line exceeds 75 octets, never
+/// splitting a UTF-8 sequence (RFC 5545 section 3.1).
+fn fold_line(line: &str) -> String {
+    let mut out = String::with_capacity(line.len() + line.len() / 74 * 3);
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out.push_str("\r\n");
+    out
+}
+
+fn utc_stamp(ms: i64) -> String {
+    DateTime::<Utc>::from_timestamp_millis(ms)
+        .unwrap_or_default()
+        .format("%Y%m%dT%H%M%SZ")
+        .to_string()
+}
+
+fn describe_test(test: &CalendarTest) -> String {
+    let mut description = format!(
+        "Type: {}\nTracker: {}",
+        test.type_name,
+        test.tracker_name
+    );
+    if !test.covered_topics.is_empty() {
+        description.push_str("\n\nCovered topics:");
+        for topic in &test.covered_topics {
+            description.push_str("\n- ");
+            description.push_str(topic);
+        }
+    }
+    description
+}
+
+/// Renders tests as an RFC 5545 calendar. UIDs come from test IDs, so
+/// importing a later export updates the same events instead of adding new
+/// ones. `SEQUENCE` is the test's revision, so calendars take the newer
+/// export as an update. `now` stamps when the export was made.
+pub fn render_calendar(calendar_name: &str, tests: &[CalendarTest], now: i64) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//StudyApp//Study Tracker//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+        format!("X-WR-CALNAME:{}", escape_text(calendar_name)),
+    ];
+
+    // Tests only carry a date (stored at 8 PM), so events are all-day events
+    for test in tests {
+        let day = local_day(test.test.scheduled_date);
+        let next_day = day.checked_add_days(Days::new(1)).unwrap_or(day);
+        let label = &test.type_name;
+        lines.extend([
+            "BEGIN:VEVENT".to_string(),
+            format!("UID:{}@studyapp", test.test.id),
+            format!("DTSTAMP:{}", utc_stamp(now)),
+            format!("LAST-MODIFIED:{}", utc_stamp(test.test.updated_at)),
+            format!("SEQUENCE:{}", test.test.revision),
+            format!("DTSTART;VALUE=DATE:{}", day.format("%Y%m%d")),
+            format!("DTEND;VALUE=DATE:{}", next_day.format("%Y%m%d")),
+            format!("SUMMARY:{}", escape_text(&format!("{} ({})", test.test.name, label))),
+            format!("CATEGORIES:{}", escape_text(label)),
+            format!("DESCRIPTION:{}", escape_text(&describe_test(test))),
+            "TRANSP:TRANSPARENT".to_string(),
+            "END:VEVENT".to_string(),
+        ]);
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    lines.iter().map(|line| fold_line(line)).collect()
+}
+
+/// Renders `tests` as a downloadable `.ics` file.
+pub fn export_calendar(calendar_name: &str, tests: &[CalendarTest], now: i64) -> ExportedFile {
+    let base: String = calendar_name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    ExportedFile {
+        file_name: format!("{}_tests.ics", base),
+        mime_type: "text/calendar".to_string(),
+        content: render_calendar(calendar_name, tests, now),
+    }
+}
+
+// ============================================================================
+// CALENDAR IMPORT
+// ============================================================================
+
+/// A VEVENT reduced to what a test needs.
+#[derive(Debug, Clone)]
+pub struct CalendarEvent {
+    pub uid: Option<String>,
+    pub summary: String,
+    pub description: Option<String>,
+    pub categories: Vec<String>,
+    pub date: NaiveDate,
+}
+
+/// Joins folded lines back together, keeping the number of the line each
+/// logical line starts on.
+fn unfold(input: &str) -> Vec<(usize, String)> {
+    let mut lines: Vec<(usize, String)> = Vec::new();
+    for (idx, raw) in input.lines().enumerate() {
+        let raw = raw.strip_suffix('\r').unwrap_or(raw);
+        match (raw.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some((_, last))) => last.push_str(rest),
+            _ if raw.is_empty() => {}
+            _ => lines.push((idx + 1, raw.to_string())),
+        }
+    }
+    lines
+}
+
+/// Splits `NAME;PARAM=value:VALUE` into the upper-cased name and the value,
+/// ignoring colons inside quoted parameter values.
+fn split_content_line(line: &str) -> Option<(String, &str)> {
+    let mut in_quotes = false;
+    let colon = line.char_indices().find_map(|(idx, c)| match c {
+        '"' => {
+            in_quotes = !in_quotes;
+            None
+        }
+        ':' if !in_quotes => Some(idx),
+        _ => None,
+    })?;
+
+    let name = line[..colon].split(';').next()?.trim().to_ascii_uppercase();
+    Some((name, &line[colon + 1..]))
+}
+
+fn unescape_text(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') | Some('N') => out.push('\n'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+/// Splits a TEXT list on commas that aren't escaped.
+fn split_text_list(value: &str) -> Vec<String> {
+    let mut items = Vec::new();
+    let mut start = 0;
+    let mut escaped = false;
+    for (idx, c) in value.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            ',' => {
+                items.push(unescape_text(&value[start..idx]));
+                start = idx + 1;
+            }
+            _ => {}
+        }
+    }
+    items.push(unescape_text(&value[start..]));
+    items.into_iter().map(|i| i.trim().to_string()).filter(|i| !i.is_empty()).collect()
+}
+
+/// The calendar day of a DATE or DATE-TIME value. UTC times are converted
+/// to local time; floating and TZID times are taken as written.
+fn parse_event_date(value: &str) -> Option<NaiveDate> {
+    let value = value.trim();
+    if value.len() == 8 {
+        return NaiveDate::parse_from_str(value, "%Y%m%d").ok();
+    }
+    match value.strip_suffix('Z') {
+        Some(utc) => {
+            let naive = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+            Some(naive.and_utc().with_timezone(&Local).date_naive())
+        }
+        None => NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok().map(|dt| dt.date()),
+    }
+}
+
+/// Reads the VEVENTs of an iCalendar file. Events that are cancelled or
+/// have no usable start date are skipped with a warning.
+pub fn parse_calendar(input: &str) -> Result<(Vec<CalendarEvent>, Vec<String>), String> {
+    let lines = unfold(input);
+    if !lines.iter().any(|(_, l)| l.eq_ignore_ascii_case("BEGIN:VCALENDAR")) {
+        return Err("Not an iCalendar file: BEGIN:VCALENDAR is missing".to_string());
+    }
+
+    let mut events = Vec::new();
+    let mut warnings = Vec::new();
+    // Properties of the VEVENT being read, with the line it started on
+    let mut current: Option<(usize, Vec<(String, String)>)> = None;
+    // Depth of components nested inside the event, such as VALARM
+    let mut nested = 0;
+
+    for (line_number, line) in &lines {
+        let Some((name, value)) = split_content_line(line) else {
+            continue;
+        };
+        let component = value.trim().to_ascii_uppercase();
+        match (name.as_str(), current.as_mut()) {
+            ("BEGIN", None) if component == "VEVENT" => current = Some((*line_number, Vec::new())),
+            ("BEGIN", Some(_)) => nested += 1,
+            ("END", Some(_)) if nested > 0 => nested -= 1,
+            ("END", Some(_)) if component == "VEVENT" => {
+                let (start_line, props) = current.take().unwrap_or_default();
+                match build_event(&props) {
+                    Ok(Some(event)) => events.push(event),
+                    Ok(None) => {}
+                    Err(message) => warnings.push(format!("line {}: {}", start_line, message)),
+                }
+            }
+            (_, Some((_, props))) if nested == 0 => props.push((name, value.to_string())),
+            _ => {}
+        }
+    }
+
+    Ok((events, warnings))
+}
+
+/// Turns an event's properties into a `CalendarEvent`; `Ok(None)` means the
+/// event was cancelled.
+fn build_event(props: &[(String, String)]) -> Result<Option<CalendarEvent>, String> {
+    let get = |name: &str| props.iter().find(|(n, _)| n == name).map(|(_, v)| v.as_str());
+
+    let summary = get("SUMMARY").map(unescape_text).unwrap_or_default().trim().to_string();
+    if get("STATUS").is_some_and(|s| s.trim().eq_ignore_ascii_case("CANCELLED")) {
+        return Ok(None);
+    }
+    if summary.is_empty() {
+        return Err("event has no SUMMARY, skipped".to_string());
+    }
+    let date = get("DTSTART")
+        .and_then(parse_event_date)
+        .ok_or_else(|| format!("event '{}' has no valid DTSTART, skipped", summary))?;
+
+    Ok(Some(CalendarEvent {
+        uid: get("UID").map(|u| u.trim().to_string()),
+        description: get("DESCRIPTION").map(unescape_text),
+        categories: props
+            .iter()
+            .filter(|(n, _)| n == "CATEGORIES")
+            .flat_map(|(_, v)| split_text_list(v))
+            .collect(),
+        summary,
+        date,
+    }))
+}
+
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_lowercase())
+        .collect()
+}
+
+/// Guesses the test type ID from the event's summary, categories and
+/// description. A type whose name appears in the text wins; otherwise
+/// keywords pick one of the built-in types, falling back to a class test.
+pub fn guess_test_type(event: &CalendarEvent, test_types: &[TestType]) -> String {
+    let mut text = event.summary.clone();
+    for category in &event.categories {
+        text.push(' ');
+        text.push_str(category);
+    }
+    if let Some(description) = &event.description {
+        text.push(' ');
+        text.push_str(description);
+    }
+    let words = words(&text);
+
+    let named = test_types.iter().find(|t| {
+        let name = self::words(&t.name);
+        !name.is_empty() && words.windows(name.len()).any(|w| w == name.as_slice())
+    });
+    if let Some(test_type) = named {
+        return test_type.id.clone();
+    }
+
+    let has = |keyword: &str| words.iter().any(|w| w == keyword);
+    let has_phrase = |a: &str, b: &str| words.windows(2).any(|w| w[0] == a && w[1] == b);
+    let builtin = if has("lab") || has("practical") || has("practicals") {
+        "lab_practical"
+    } else if has("esa") || has("endsem") || has("final") || has("finals") || has_phrase("end", "sem") || has_phrase("end", "semester") {
+        "esa"
+    } else if has("isa") || has("midsem") || has("midterm") || has("mid") || has("internal") || has("sessional") || has_phrase("in", "semester") {
+        "isa"
+    } else {
+        "class_test"
+    };
+
+    // The built-in types can be deleted, so only suggest ones that exist
+    [builtin, "class_test"]
+        .iter()
+        .find(|id| test_types.iter().any(|t| t.id == **id))
+        .map(|id| id.to_string())
+        .or_else(|| test_types.first().map(|t| t.id.clone()))
+        .unwrap_or_default()
+}
+
+/// Initials of a subject's significant words, e.g. "DAA" for "Design and
+/// Analysis of Algorithms".
+fn acronym(name: &str) -> String {
+    const SKIP: &[&str] = &["and", "of", "the", "in", "to", "for", "a", "an", "with"];
+    words(name)
+        .iter()
+        .filter(|w| !SKIP.contains(&w.as_str()))
+        .filter_map(|w| w.chars().next())
+        .collect()
+}
+
+/// Whether an event's text refers to a subject, by full name, acronym or a
+/// close enough summary.
+fn mentions_subject(event: &CalendarEvent, subject_name: &str) -> bool {
+    let text = format!("{} {}", event.summary, event.description.as_deref().unwrap_or(""));
+    let subject = normalize_name(subject_name);
+    if subject.is_empty() {
+        return false;
+    }
+    let short = acronym(subject_name);
+    normalize_name(&text).contains(&subject)
+        || (short.len() >= 2 && words(&text).contains(&short))
+        || name_similarity(&event.summary, subject_name) >= RENAME_THRESHOLD
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CalendarImportCandidate {
+    pub uid: Option<String>,
+    pub name: String,
+    pub scheduled_date: i64,
+    /// Guessed from keywords; the user can pick another before importing.
+    pub test_type: String,
+    pub matched_subjects: Vec<String>,
+    /// Every unit of the matched subjects.
+    pub coverage: Vec<TestCoverageInput>,
+    /// An existing test on the same day that looks like the same one.
+    pub duplicate_of: Option<Test>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CalendarImportPreview {
+    pub candidates: Vec<CalendarImportCandidate>,
+    pub warnings: Vec<String>,
+}
+
+/// The stored timestamp for a test on `date`: 8 PM local time, as the test
+/// form uses.
+pub fn test_timestamp(date: NaiveDate) -> i64 {
+    let evening = date.and_hms_opt(20, 0, 0).unwrap_or_default();
+    Local
+        .from_local_datetime(&evening)
+        .earliest()
+        .unwrap_or_else(|| evening.and_utc().with_timezone(&Local))
+        .timestamp_millis()
+}
+
+/// Maps events to tests for a tracker, suggesting coverage from subject
+/// names and flagging likely duplicates of `existing` tests.
+pub fn plan_calendar_import(
+    events: &[CalendarEvent],
+    subjects: &[SubjectData],
+    existing: &[Test],
+    test_types: &[TestType],
+) -> Vec<CalendarImportCandidate> {
+    events
+        .iter()
+        .map(|event| {
+            let matched: Vec<&SubjectData> = subjects
+                .iter()
+                .filter(|s| mentions_subject(event, &s.subject.name))
+                .collect();
+            let duplicate_of = existing
+                .iter()
+                .find(|test| {
+                    let same_id = event.uid.as_deref() == Some(format!("{}@studyapp", test.id).as_str());
+                    local_day(test.scheduled_date) == event.date
+                        && (same_id || name_similarity(&test.name, &event.summary) >= RENAME_THRESHOLD)
+                })
+                .cloned();
+
+            CalendarImportCandidate {
+                uid: event.uid.clone(),
+                name: event.summary.clone(),
+                scheduled_date: test_timestamp(event.date),
+                test_type: guess_test_type(event, test_types),
+                matched_subjects: matched.iter().map(|s| s.subject.name.clone()).collect(),
+                coverage: matched
+                    .iter()
+                    .flat_map(|s| &s.units)
+                    .map(|u| TestCoverageInput {
+                        unit_id: Some(u.unit.id.clone()),
+                        topic_id: None,
+                    })
+                    .collect(),
+                duplicate_of,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::get_tracker_tree;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_calendar_export() {
+        let db = Database::new(PathBuf::from(":memory:")).unwrap();
+        db.create_semester("sem".into(), "Sem 4".into(), 0).unwrap();
+        db.create_tracker("tr".into(), "sem".into(), "OS, Networks".into(), None, None, 0).unwrap();
+        db.create_subject("os".into(), "tr".into(), "Operating Systems".into(), 0).unwrap();
+        db.create_unit("u1".into(), "os".into(), "Processes".into(), 0, 0).unwrap();
+        db.create_topic("t1".into(), "u1".into(), "Threads; and fibers".into(), 0, 0).unwrap();
+        db.create_topic("t2".into(), "u1".into(), "Scheduling".repeat(10), 1, 0).unwrap();
+
+        let scheduled = Local.with_ymd_and_hms(2025, 3, 14, 20, 0, 0).unwrap().timestamp_millis();
+        db.create_test("test-1".into(), "tr".into(), "Mid term".into(), "isa".into(), scheduled, 0).unwrap();
+        db.create_test_coverage("c1".into(), "test-1".into(), Some("u1".into()), None).unwrap();
+
+        assert!(collect_calendar_tests(&db, &CalendarScope::Tracker { tracker_id: "nope".into() })
+            .unwrap()
+            .is_none());
+        let (name, tests) = collect_calendar_tests(&db, &CalendarScope::All).unwrap().unwrap();
+        assert_eq!(name, "StudyApp Tests");
+        let (name, tests_in_semester) = collect_calendar_tests(&db, &CalendarScope::Semester { semester_id: "sem".into() })
+            .unwrap()
+            .unwrap();
+        assert_eq!((name.as_str(), tests_in_semester.len()), ("Sem 4", 1));
+
+        let ics = render_calendar(&name, &tests, 1_741_000_000_000);
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        assert!(ics.lines().all(|l| l.len() <= 75));
+        assert!(ics.contains("UID:test-1@studyapp\r\n"));
+        assert!(ics.contains("SEQUENCE:0\r\n"));
+        assert!(ics.contains("DTSTAMP:20250303T110640Z\r\n"));
+        assert!(ics.contains("DTSTART;VALUE=DATE:20250314\r\nDTEND;VALUE=DATE:20250315\r\n"));
+        assert!(ics.contains("SUMMARY:Mid term (ISA)\r\n"));
+
+        let unfolded = ics.replace("\r\n ", "");
+        assert!(unfolded.contains(
+            "DESCRIPTION:Type: ISA\\nTracker: OS\\, Networks\\n\\nCovered topics:\\n- Threads\\; and fibers\\n- Scheduling"
+        ));
+
+        // Moving, renaming or re-covering the test each make the next
+        // export a later revision
+        let sequence = |expected: &str| {
+            let (name, tests) = collect_calendar_tests(&db, &CalendarScope::All).unwrap().unwrap();
+            let ics = render_calendar(&name, &tests, 0);
+            assert!(ics.contains(&format!("SEQUENCE:{}\r\n", expected)), "{}", ics);
+            ics
+        };
+        db.reschedule_test("test-1", scheduled + 86_400_000, 10).unwrap();
+        assert!(sequence("1").contains("DTSTART;VALUE=DATE:20250315\r\n"));
+        db.rename_test("test-1", "Mid semester", 20).unwrap();
+        assert!(sequence("2").contains("SUMMARY:Mid semester (ISA)\r\n"));
+        db.delete_test_coverage("test-1").unwrap();
+        db.bump_test_revision("test-1", 30).unwrap();
+        assert!(!sequence("3").contains("Covered topics"));
+    }
+
+    #[test]
+    fn test_calendar_import() {
+        let db = Database::new(PathBuf::from(":memory:")).unwrap();
+        db.create_semester("sem".into(), "Sem 4".into(), 0).unwrap();
+        db.create_tracker("tr".into(), "sem".into(), "Sem 4".into(), None, None, 0).unwrap();
+        db.create_subject("daa".into(), "tr".into(), "Design and Analysis of Algorithms".into(), 0).unwrap();
+        db.create_unit("u1".into(), "daa".into(), "Brute Force".into(), 0, 0).unwrap();
+        db.create_unit("u2".into(), "daa".into(), "Greedy".into(), 1, 0).unwrap();
+        db.create_subject("os".into(), "tr".into(), "Operating Systems".into(), 0).unwrap();
+        db.create_unit("u3".into(), "os".into(), "Processes".into(), 0, 0).unwrap();
+        let existing_date = test_timestamp(NaiveDate::from_ymd_opt(2025, 5, 2).unwrap());
+        db.create_test("old".into(), "tr".into(), "Operating Systems Lab".into(), "lab_practical".into(), existing_date, 0).unwrap();
+
+        let ics = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+BEGIN:VEVENT\r
+UID:exam-1@uni.example\r
+SUMMARY:DAA End Semester Exam\r
+DTSTART;VALUE=DATE:20250510\r
+BEGIN:VALARM\r
+SUMMARY:Reminder\r
+END:VALARM\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+SUMMARY:Operating Systems Lab\r
+  Exam\r
+DTSTART;TZID=\"Asia/Kolkata\":20250502T093000\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+SUMMARY:Quiz 3\r
+CATEGORIES:ISA\\, internal,Quiz\r
+DTSTART:20250520T040000Z\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+SUMMARY:Moved exam\r
+STATUS:CANCELLED\r
+DTSTART:20250521\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+SUMMARY:No date\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+        let (events, warnings) = parse_calendar(ics).unwrap();
+        assert_eq!(events.len(), 3);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].starts_with("line 26:"));
+        assert_eq!(events[0].summary, "DAA End Semester Exam");
+        assert_eq!(events[1].summary, "Operating Systems Lab Exam");
+        assert_eq!(events[2].categories, vec!["ISA, internal", "Quiz"]);
+
+        let subjects = get_tracker_tree(&db, "tr").unwrap();
+        let existing = db.get_tests_by_tracker("tr").unwrap();
+        let mut test_types = db.get_all_test_types().unwrap();
+        let candidates = plan_calendar_import(&events, &subjects, &existing, &test_types);
+        let types: Vec<&str> = candidates.iter().map(|c| c.test_type.as_str()).collect();
+        assert_eq!(types, vec!["esa", "lab_practical", "isa"]);
+        assert_eq!(candidates[0].matched_subjects, vec!["Design and Analysis of Algorithms"]);
+        assert_eq!(candidates[0].coverage.len(), 2);
+        assert_eq!(candidates[1].matched_subjects, vec!["Operating Systems"]);
+        assert_eq!(candidates[1].duplicate_of.as_ref().map(|t| t.id.as_str()), Some("old"));
+        assert!(candidates[0].duplicate_of.is_none());
+
+        // A user-defined type named in the summary beats the keywords
+        test_types.push(db.create_test_type("viva".into(), "Viva Voce".into(), None, 3, 1.0, 0).unwrap());
+        let viva = CalendarEvent {
+            summary: "OS lab viva voce".into(),
+            ..events[1].clone()
+        };
+        assert_eq!(guess_test_type(&viva, &test_types), "viva");
+        assert_eq!(guess_test_type(&events[0], &test_types[4..]), "viva");
+
+        assert!(parse_calendar("BEGIN:VEVENT\nEND:VEVENT").is_err());
+    }
+}
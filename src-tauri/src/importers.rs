@@ -0,0 +1,479 @@
+use serde::{Deserialize, Serialize};
+use crate::utils::{
+    parse_syllabus, normalize_name, ParsedSyllabusEntry, SyllabusBuilder, SyllabusParseError,
+};
+
+// ============================================================================
+// IMPORTER TRAIT
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyllabusFormat {
+    /// `Subject >>> Unit >>> topics` lines or an indented outline.
+    Text,
+    /// `# Subject`, `## Unit`, `- topic`.
+    Markdown,
+    /// `subject,unit,topic` rows, one topic per row.
+    Csv,
+}
+
+/// Turns one syllabus format into the tree `parse_syllabus` produces.
+pub trait SyllabusImporter {
+    fn parse(&self, input: &str) -> Result<Vec<ParsedSyllabusEntry>, Vec<SyllabusParseError>>;
+}
+
+impl SyllabusFormat {
+    pub fn importer(self) -> &'static dyn SyllabusImporter {
+        match self {
+            SyllabusFormat::Text => &TextImporter,
+            SyllabusFormat::Markdown => &MarkdownImporter,
+            SyllabusFormat::Csv => &CsvImporter,
+        }
+    }
+}
+
+/// Guesses the format of pasted or uploaded syllabus text.
+pub fn detect_format(input: &str) -> SyllabusFormat {
+    let lines: Vec<&str> = input
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with("//"))
+        .collect();
+
+    if lines.iter().any(|l| heading_level(l).is_some()) {
+        return SyllabusFormat::Markdown;
+    }
+    if lines.iter().any(|l| l.contains(">>>")) {
+        return SyllabusFormat::Text;
+    }
+    if let Ok(records) = parse_csv_records(input) {
+        let has_header = records.first().is_some_and(|r| csv_header(r).is_some());
+        if !records.is_empty() && (has_header || records.iter().all(|r| r.fields.len() >= 2)) {
+            return SyllabusFormat::Csv;
+        }
+    }
+    SyllabusFormat::Text
+}
+
+/// Parses `input` with the importer for `format`, detecting it when `None`.
+pub fn parse_syllabus_input(
+    input: &str,
+    format: Option<SyllabusFormat>,
+) -> Result<Vec<ParsedSyllabusEntry>, Vec<SyllabusParseError>> {
+    format.unwrap_or_else(|| detect_format(input)).importer().parse(input)
+}
+
+// ============================================================================
+// TEXT
+// ============================================================================
+
+pub struct TextImporter;
+
+impl SyllabusImporter for TextImporter {
+    fn parse(&self, input: &str) -> Result<Vec<ParsedSyllabusEntry>, Vec<SyllabusParseError>> {
+        parse_syllabus(input)
+    }
+}
+
+// ============================================================================
+// MARKDOWN
+// ============================================================================
+
+pub struct MarkdownImporter;
+
+/// Level of an ATX heading (`#` to `######` followed by a space).
+fn heading_level(line: &str) -> Option<usize> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    let rest = &line[level..];
+    if (1..=6).contains(&level) && (rest.is_empty() || rest.starts_with(char::is_whitespace)) {
+        Some(level)
+    } else {
+        None
+    }
+}
+
+/// Drops a heading's optional closing `#`s. As in CommonMark they only
+/// count when whitespace comes before them, so `C#` and `C\#` keep theirs.
+fn strip_closing_sequence(text: &str) -> &str {
+    let open = text.trim_end_matches('#');
+    if open.len() == text.len() {
+        text
+    } else if open.is_empty() || open.ends_with(char::is_whitespace) {
+        open.trim_end()
+    } else {
+        text
+    }
+}
+
+/// Resolves backslash escapes the CommonMark way: only before ASCII
+/// punctuation, so `C:\Windows` and a trailing `\` keep their backslash.
+fn unescape_markdown(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match chars.peek() {
+            Some(&next) if c == '\\' && next.is_ascii_punctuation() => {
+                out.push(next);
+                chars.next();
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Length of a list marker (`- `, `* `, `+ `, `1. `, `1) `) at the start of
+/// `line`, including the space after it.
+fn bullet_length(line: &str) -> Option<usize> {
+    let digits = line.chars().take_while(|c| c.is_ascii_digit()).count();
+    let marker = if digits > 0 {
+        match line[digits..].chars().next() {
+            Some('.') | Some(')') => digits + 1,
+            _ => return None,
+        }
+    } else {
+        match line.chars().next() {
+            Some('-') | Some('*') | Some('+') => 1,
+            _ => return None,
+        }
+    };
+    let rest = &line[marker..];
+    if rest.starts_with(char::is_whitespace) {
+        Some(line.len() - rest.trim_start().len())
+    } else {
+        None
+    }
+}
+
+impl SyllabusImporter for MarkdownImporter {
+    /// `#` headings are subjects, `##` headings are units, and list items
+    /// (or deeper headings) are topics; a heading with nothing below it is
+    /// kept as an empty subject or unit. Task list boxes are dropped so an
+    /// exported checklist imports cleanly. Other lines are treated as notes
+    /// and ignored.
+    fn parse(&self, input: &str) -> Result<Vec<ParsedSyllabusEntry>, Vec<SyllabusParseError>> {
+        let mut builder = SyllabusBuilder::default();
+        let mut errors = Vec::new();
+        let mut subject = None;
+        let mut unit = None;
+
+        for (idx, raw_line) in input.lines().enumerate() {
+            let line = idx + 1;
+            let leading = raw_line.len() - raw_line.trim_start().len();
+            let trimmed = raw_line.trim();
+            if trimmed.is_empty() || trimmed.starts_with("<!--") {
+                continue;
+            }
+
+            let (level, offset, heading) = if let Some(level) = heading_level(trimmed) {
+                (level.min(3), level, true)
+            } else if let Some(length) = bullet_length(trimmed) {
+                (3, length, false)
+            } else {
+                continue;
+            };
+
+            let mut rest = trimmed[offset..].trim_start();
+            if level == 3 {
+                for checkbox in ["[ ] ", "[x] ", "[X] "] {
+                    if let Some(stripped) = rest.strip_prefix(checkbox) {
+                        rest = stripped.trim_start();
+                        break;
+                    }
+                }
+            }
+            let column = raw_line[..leading + trimmed.len() - rest.len()].chars().count() + 1;
+            let text = if heading {
+                strip_closing_sequence(rest)
+            } else {
+                rest
+            };
+
+            let name = unescape_markdown(text);
+            if name.is_empty() {
+                errors.push(SyllabusParseError {
+                    line,
+                    column,
+                    message: "expected a name after the heading or list marker".to_string(),
+                });
+                continue;
+            }
+
+            match (level, subject, unit) {
+                (1, _, _) => {
+                    subject = Some(builder.subject(name));
+                    unit = None;
+                }
+                (2, Some(s), _) => unit = Some(builder.unit(s, name)),
+                (2, None, _) => errors.push(SyllabusParseError {
+                    line,
+                    column: leading + 1,
+                    message: "unit heading '##' must come after a '# Subject' heading".to_string(),
+                }),
+                (_, Some(s), Some(u)) => builder.topic(s, u, name),
+                _ => errors.push(SyllabusParseError {
+                    line,
+                    column: leading + 1,
+                    message: "topic must come after a '## Unit' heading".to_string(),
+                }),
+            }
+        }
+
+        builder.finish(errors)
+    }
+}
+
+// ============================================================================
+// CSV
+// ============================================================================
+
+pub struct CsvImporter;
+
+struct CsvField {
+    value: String,
+    column: usize,
+}
+
+struct CsvRecord {
+    line: usize,
+    fields: Vec<CsvField>,
+}
+
+/// Splits CSV into records following RFC 4180: fields may be quoted, and
+/// quoted fields may contain commas, doubled quotes and line breaks. Rows
+/// with only empty fields are dropped.
+fn parse_csv_records(input: &str) -> Result<Vec<CsvRecord>, SyllabusParseError> {
+    let mut records = Vec::new();
+    let mut fields = Vec::new();
+    let mut value = String::new();
+    let mut record_line = 1;
+    let mut field_column = 1;
+    let (mut line, mut column) = (1, 1);
+    let mut quoted_at: Option<(usize, usize)> = None;
+    // Excel's "CSV UTF-8" starts the file with a byte order mark
+    let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+    let mut chars = input.chars().peekable();
+
+    fn end_record(records: &mut Vec<CsvRecord>, fields: Vec<CsvField>, line: usize) {
+        if fields.iter().any(|f| !f.value.trim().is_empty()) {
+            records.push(CsvRecord { line, fields });
+        }
+    }
+
+    while let Some(c) = chars.next() {
+        if quoted_at.is_some() {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    value.push('"');
+                    column += 1;
+                }
+                '"' => quoted_at = None,
+                '\n' => {
+                    value.push(c);
+                    line += 1;
+                    column = 0;
+                }
+                _ => value.push(c),
+            }
+        } else {
+            match c {
+                '"' if value.trim().is_empty() => {
+                    value.clear();
+                    quoted_at = Some((line, column));
+                }
+                ',' => {
+                    fields.push(CsvField {
+                        value: std::mem::take(&mut value),
+                        column: field_column,
+                    });
+                    field_column = column + 1;
+                }
+                '\r' if chars.peek() == Some(&'\n') => {}
+                '\n' => {
+                    fields.push(CsvField {
+                        value: std::mem::take(&mut value),
+                        column: field_column,
+                    });
+                    end_record(&mut records, std::mem::take(&mut fields), record_line);
+                    line += 1;
+                    column = 0;
+                    record_line = line;
+                    field_column = 1;
+                }
+                _ => value.push(c),
+            }
+        }
+        column += 1;
+    }
+
+    if let Some((line, column)) = quoted_at {
+        return Err(SyllabusParseError {
+            line,
+            column,
+            message: "quoted field is never closed".to_string(),
+        });
+    }
+    fields.push(CsvField {
+        value,
+        column: field_column,
+    });
+    end_record(&mut records, fields, record_line);
+
+    Ok(records)
+}
+
+/// Positions of the subject, unit and topic columns if `record` is a header.
+fn csv_header(record: &CsvRecord) -> Option<(usize, usize, Option<usize>)> {
+    let names: Vec<String> = record.fields.iter().map(|f| normalize_name(&f.value)).collect();
+    let find = |wanted: &[&str]| names.iter().position(|n| wanted.contains(&n.as_str()));
+    let subject = find(&["subject", "subject name"])?;
+    let unit = find(&["unit", "unit name"])?;
+    Some((subject, unit, find(&["topic", "topic name", "topics"])))
+}
+
+impl SyllabusImporter for CsvImporter {
+    /// One topic per row. Columns are found through a `subject,unit,topic`
+    /// header when there is one (other columns are ignored), otherwise the
+    /// first three columns are used. A row with an empty topic adds a unit
+    /// without topics, and one with an empty unit and topic a subject
+    /// without units.
+    fn parse(&self, input: &str) -> Result<Vec<ParsedSyllabusEntry>, Vec<SyllabusParseError>> {
+        let records = parse_csv_records(input).map_err(|e| vec![e])?;
+        let mut builder = SyllabusBuilder::default();
+        let mut errors = Vec::new();
+
+        let (columns, rows) = match records.first().and_then(csv_header) {
+            Some(columns) => (columns, &records[1..]),
+            None => ((0, 1, Some(2)), &records[..]),
+        };
+        let (subject_col, unit_col, topic_col) = columns;
+
+        for record in rows {
+            let cell = |col: usize| record.fields.get(col).map(|f| (f.value.trim(), f.column));
+            let end_column = record.fields.last().map_or(1, |f| f.column);
+
+            let subject_name = match cell(subject_col) {
+                Some((name, _)) if !name.is_empty() => name,
+                found => {
+                    errors.push(SyllabusParseError {
+                        line: record.line,
+                        column: found.map_or(end_column, |(_, column)| column),
+                        message: "subject column is empty".to_string(),
+                    });
+                    continue;
+                }
+            };
+            let topic_name = topic_col.and_then(cell).map_or("", |(topic, _)| topic);
+            let unit_name = match cell(unit_col) {
+                Some((name, _)) if !name.is_empty() => name,
+                _ if topic_name.is_empty() => {
+                    builder.subject(subject_name.to_string());
+                    continue;
+                }
+                found => {
+                    errors.push(SyllabusParseError {
+                        line: record.line,
+                        column: found.map_or(end_column, |(_, column)| column),
+                        message: "unit column is empty".to_string(),
+                    });
+                    continue;
+                }
+            };
+
+            let subject = builder.subject(subject_name.to_string());
+            let unit = builder.unit(subject, unit_name.to_string());
+            if !topic_name.is_empty() {
+                builder.topic(subject, unit, topic_name.to_string());
+            }
+        }
+
+        builder.finish(errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_markdown_import() {
+        let input = "# Physics
+Notes from the course handbook.
+
+## Mechanics
+- Newton's laws
+- [x] Forces \\- moments
+1. Work and energy
+
+## Optics ##
+### Lenses
+";
+        assert_eq!(detect_format(input), SyllabusFormat::Markdown);
+        let result = parse_syllabus_input(input, None).unwrap();
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].units[0].topics, vec!["Newton's laws", "Forces - moments", "Work and energy"]);
+        assert_eq!(result[0].units[1].unit_name, "Optics");
+        assert_eq!(result[0].units[1].topics, vec!["Lenses"]);
+
+        // Trailing `#`s are part of the name unless a space comes before them,
+        // so `# C\#` is the same subject as `# C#`
+        let result = MarkdownImporter.parse("# C#\n## F\\# ##\n- a\n# C\\#\n## Basics #\n- b").unwrap();
+        assert_eq!(result[0].subject_name, "C#");
+        assert_eq!(result[0].units[0].unit_name, "F#");
+        assert_eq!(result[0].units[1].unit_name, "Basics");
+        assert_eq!(result.len(), 1);
+
+        // Backslashes only escape punctuation
+        let result = MarkdownImporter.parse("# Tools\n## Paths\n- C:\\Windows\n- ends with \\\n- a \\\\\\# b").unwrap();
+        assert_eq!(result[0].units[0].topics, vec!["C:\\Windows", "ends with \\", "a \\# b"]);
+
+        // A heading with nothing below it is still a subject
+        let result = MarkdownImporter.parse("# Physics\n## Optics\n# Electives\n").unwrap();
+        assert_eq!(result[1].subject_name, "Electives");
+        assert!(result[1].units.is_empty());
+
+        let errors = MarkdownImporter.parse("## Mechanics\n- Forces").unwrap_err();
+        let positions: Vec<(usize, usize)> = errors.iter().map(|e| (e.line, e.column)).collect();
+        assert_eq!(positions, vec![(1, 1), (2, 1)]);
+    }
+
+    #[test]
+    fn test_csv_import() {
+        let input = "Topic,Subject,Unit,Completed
+Newton's laws,Physics,Mechanics,true
+\"Forces, moments and \"\"torque\"\"\",Physics,Mechanics,false
+,Physics,Optics,
+Alkanes,Chemistry,Organic,false
+";
+        assert_eq!(detect_format(input), SyllabusFormat::Csv);
+        let result = parse_syllabus_input(input, None).unwrap();
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].units[0].topics, vec!["Newton's laws", "Forces, moments and \"torque\""]);
+        assert!(result[0].units[1].topics.is_empty());
+
+        let with_bom = format!("\u{feff}{}", input);
+        assert_eq!(detect_format(&with_bom), SyllabusFormat::Csv);
+        assert_eq!(parse_syllabus_input(&with_bom, None).unwrap(), result);
+
+        let headerless = CsvImporter.parse("Physics,Mechanics,Forces\n,Optics,Lenses").unwrap_err();
+        assert_eq!((headerless[0].line, headerless[0].column), (2, 1));
+
+        // A subject with no unit or topic has no units; a topic needs a unit
+        let result = CsvImporter.parse("Physics,Mechanics,Forces\nElectives,,").unwrap();
+        assert!(result[1].units.is_empty());
+        let unitless = CsvImporter.parse("Physics,,Forces").unwrap_err();
+        assert_eq!((unitless[0].line, unitless[0].column), (1, 9));
+
+        let unclosed = CsvImporter.parse("Physics,Mechanics,\"Forces").unwrap_err();
+        assert_eq!((unclosed[0].line, unclosed[0].column), (1, 19));
+    }
+
+    #[test]
+    fn test_detects_text_formats() {
+        assert_eq!(detect_format("Maths >>> Algebra >>> rings, fields"), SyllabusFormat::Text);
+        assert_eq!(detect_format("Physics\n    Mechanics\n        Forces, moments"), SyllabusFormat::Text);
+        assert_eq!(detect_format("Physics,Mechanics,Forces"), SyllabusFormat::Csv);
+    }
+}
@@ -0,0 +1,210 @@
+use rusqlite::Result as SqliteResult;
+use serde::Serialize;
+use crate::db::Database;
+use crate::models::*;
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueKind {
+    /// The file itself is damaged. Never repaired in place.
+    Corruption,
+    /// A row refers to a parent that no longer exists.
+    MissingParent,
+    /// A coverage row covers neither a unit nor a topic.
+    EmptyCoverage,
+    /// Siblings share an `order`, so their display order is arbitrary.
+    DuplicateOrder,
+    /// A tracker's cached subject, unit or topic counts are wrong.
+    StaleStatistics,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DatabaseIssue {
+    pub kind: IssueKind,
+    pub table: String,
+    pub detail: String,
+    pub repairable: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DatabaseReport {
+    pub healthy: bool,
+    pub issues: Vec<DatabaseIssue>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RepairReport {
+    pub repaired: usize,
+    /// A fresh check after the repairs.
+    pub report: DatabaseReport,
+}
+
+/// What a check found, with what's needed to repair it. Listed in the
+/// order repairs run: stale counts last, after rows were deleted.
+enum Finding {
+    Corruption(String),
+    MissingParent(ForeignKeyViolation),
+    EmptyCoverage(TestCoverage),
+    DuplicateOrder(DuplicateOrder),
+    StaleStatistics(String),
+}
+
+impl Finding {
+    fn issue(&self) -> DatabaseIssue {
+        let (kind, table, detail) = match self {
+            Finding::Corruption(problem) => (IssueKind::Corruption, String::new(), problem.clone()),
+            Finding::MissingParent(v) => (
+                IssueKind::MissingParent,
+                v.table.clone(),
+                format!(
+                    "Row {} refers to a missing {} row through {}; it will be {}",
+                    v.rowid,
+                    v.parent,
+                    v.column,
+                    if v.on_delete == "SET NULL" { "unlinked" } else { "deleted" }
+                ),
+            ),
+            Finding::EmptyCoverage(c) => (
+                IssueKind::EmptyCoverage,
+                "test_coverage".to_string(),
+                format!("Coverage {} of test {} covers no unit or topic", c.id, c.test_id),
+            ),
+            Finding::DuplicateOrder(d) => (
+                IssueKind::DuplicateOrder,
+                d.table.clone(),
+                format!("{} rows under {} share order {}", d.count, d.parent_id, d.order),
+            ),
+            Finding::StaleStatistics(tracker_id) => (
+                IssueKind::StaleStatistics,
+                "trackers".to_string(),
+                format!("Tracker {} has wrong subject, unit or topic counts", tracker_id),
+            ),
+        };
+        DatabaseIssue {
+            kind,
+            table,
+            detail,
+            repairable: kind != IssueKind::Corruption,
+        }
+    }
+
+    fn repair(&self, db: &Database) -> SqliteResult<()> {
+        match self {
+            Finding::Corruption(_) => Ok(()),
+            Finding::MissingParent(v) => db.repair_foreign_key_violation(v),
+            Finding::EmptyCoverage(c) => db.delete_test_coverage_entry(&c.id),
+            Finding::DuplicateOrder(d) => db.renumber_children(&d.table, &d.parent_id),
+            Finding::StaleStatistics(_) => db.recompute_all_statistics().map(|_| ()),
+        }
+    }
+}
+
+fn find_problems(db: &Database) -> SqliteResult<Vec<Finding>> {
+    let mut findings: Vec<Finding> = db.integrity_check()?.into_iter().map(Finding::Corruption).collect();
+    findings.extend(db.get_foreign_key_violations()?.into_iter().map(Finding::MissingParent));
+    findings.extend(db.get_empty_test_coverage()?.into_iter().map(Finding::EmptyCoverage));
+    findings.extend(db.get_duplicate_orders()?.into_iter().map(Finding::DuplicateOrder));
+    findings.extend(db.get_trackers_with_stale_statistics()?.into_iter().map(Finding::StaleStatistics));
+    Ok(findings)
+}
+
+/// Runs SQLite's own checks plus the app's invariants. Read-only.
+pub fn check_database(db: &Database) -> SqliteResult<DatabaseReport> {
+    let issues: Vec<DatabaseIssue> = find_problems(db)?.iter().map(Finding::issue).collect();
+    Ok(DatabaseReport {
+        healthy: issues.is_empty(),
+        issues,
+    })
+}
+
+/// Fixes every repairable issue in one transaction. A damaged file is left
+/// alone: editing rows can't fix it and may make things worse.
+pub fn repair_database(db: &Database) -> Result<RepairReport, String> {
+    let findings = find_problems(db).map_err(|e| e.to_string())?;
+    if findings.iter().any(|f| matches!(f, Finding::Corruption(_))) {
+        return Err("The database file is damaged and can't be repaired in place. Restore it from a backup.".to_string());
+    }
+
+    db.transaction(|db| {
+        for finding in &findings {
+            finding.repair(db)?;
+        }
+        Ok(())
+    })
+    .map_err(|e: rusqlite::Error| e.to_string())?;
+
+    Ok(RepairReport {
+        repaired: findings.len(),
+        report: check_database(db).map_err(|e| e.to_string())?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusqlite::Connection;
+
+    #[test]
+    fn test_check_and_repair() {
+        let dir = std::env::temp_dir().join(format!("studyapp-integrity-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("studyapp.db");
+
+        let db = Database::new(path.clone()).unwrap();
+        db.create_semester("sem".into(), "Sem".into(), 0).unwrap();
+        db.create_tracker("tr".into(), "sem".into(), "Tracker".into(), None, None, 0).unwrap();
+        db.create_subject("os".into(), "tr".into(), "OS".into(), 0).unwrap();
+        db.create_unit("u1".into(), "os".into(), "Processes".into(), 0, 0).unwrap();
+        db.create_topic("t1".into(), "u1".into(), "Threads".into(), 0, 1).unwrap();
+        db.create_topic("t2".into(), "u1".into(), "Scheduling".into(), 0, 2).unwrap();
+        db.create_topic("t3".into(), "u1".into(), "Deadlocks".into(), 1, 3).unwrap();
+        db.create_test("isa".into(), "tr".into(), "ISA 1".into(), "isa".into(), 0, 0).unwrap();
+        db.create_test_coverage("empty".into(), "isa".into(), None, None).unwrap();
+        let assignment = AssignmentInput {
+            subject_id: Some("os".into()),
+            name: "Report".into(),
+            description: None,
+            due_date: 0,
+            topic_ids: Vec::new(),
+        };
+        db.create_assignment("a1".into(), "tr".into(), &assignment, 0).unwrap();
+        assert!(check_database(&db).unwrap().issues.iter().all(|i| i.kind != IssueKind::MissingParent));
+
+        // Damage the data the way an outside edit with foreign keys off would
+        let raw = Connection::open(&path).unwrap();
+        raw.execute_batch(
+            "PRAGMA foreign_keys = OFF;
+             INSERT INTO topics (id, unit_id, name, completed, \"order\", created_at, updated_at) VALUES ('orphan', 'gone', 'Orphan', 0, 0, 0, 0);
+             UPDATE assignments SET subject_id = 'gone';
+             UPDATE trackers SET total_topics = 99;",
+        )
+        .unwrap();
+        drop(raw);
+
+        let report = check_database(&db).unwrap();
+        let mut kinds: Vec<(IssueKind, &str)> = report.issues.iter().map(|i| (i.kind, i.table.as_str())).collect();
+        kinds.sort_by_key(|(kind, table)| (*kind as u8, table.to_string()));
+        assert!(!report.healthy);
+        assert_eq!(kinds, vec![
+            (IssueKind::MissingParent, "assignments"),
+            (IssueKind::MissingParent, "topics"),
+            (IssueKind::EmptyCoverage, "test_coverage"),
+            (IssueKind::DuplicateOrder, "topics"),
+            (IssueKind::StaleStatistics, "trackers"),
+        ]);
+
+        let repaired = repair_database(&db).unwrap();
+        assert_eq!(repaired.repaired, 5);
+        assert!(repaired.report.healthy, "{:?}", repaired.report.issues);
+
+        assert!(db.get_topic("orphan").unwrap().is_none());
+        assert_eq!(db.get_assignment("a1").unwrap().unwrap().subject_id, None);
+        assert!(db.get_test_coverage("isa").unwrap().is_empty());
+        let topics: Vec<(String, i32)> = db.get_topics_by_unit("u1").unwrap().into_iter().map(|t| (t.id, t.order)).collect();
+        assert_eq!(topics, vec![("t1".to_string(), 0), ("t2".to_string(), 1), ("t3".to_string(), 2)]);
+        assert_eq!(db.get_tracker("tr").unwrap().unwrap().total_topics, 3);
+
+        drop(db);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
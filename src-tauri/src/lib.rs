@@ -1,15 +1,38 @@
-mod models;
-mod db;
-mod utils;
+// The app's logic is a plain library so the command line tool and the
+// integration tests can use it without Tauri; only the `gui` feature
+// pulls Tauri in
+pub mod models;
+pub mod db;
+pub mod utils;
+pub mod syllabus;
+pub mod importers;
+pub mod exporters;
+pub mod ical;
+pub mod diff;
+pub mod merge;
+pub mod grades;
+pub mod planner;
+pub mod agenda;
+pub mod pool;
+pub mod integrity;
+pub mod service;
+#[cfg(feature = "gui")]
 mod commands;
+mod cli;
 
-use commands::AppState;
-use db::Database;
-use std::sync::Mutex;
-use tauri::Manager;
+/// Entry point of the `studyapp-cli` binary. Returns the exit code.
+pub fn run_cli(args: Vec<String>) -> i32 {
+    cli::run(args)
+}
 
+#[cfg(feature = "gui")]
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    use commands::AppState;
+    use pool::{DbPool, READER_COUNT};
+    use std::sync::Arc;
+    use tauri::Manager;
+
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .setup(|app| {
@@ -34,8 +57,8 @@ pub fn run() {
                 }
             }
 
-            let db = match Database::new(db_path.clone()) {
-                Ok(db) => db,
+            let pool = match DbPool::open(db_path.clone(), READER_COUNT) {
+                Ok(pool) => pool,
                 Err(e) => {
                     eprintln!("Failed to initialize database at {:?}: {}", db_path, e);
                     return Err(format!("Failed to initialize database: {}", e).into());
@@ -43,7 +66,7 @@ pub fn run() {
             };
 
             app.manage(AppState {
-                db: Mutex::new(db),
+                pool: Arc::new(pool),
             });
 
             println!("Database initialized successfully");
@@ -53,14 +76,55 @@ pub fn run() {
             commands::create_semester,
             commands::get_all_semesters,
             commands::delete_semester,
+            commands::get_semester_overview,
             commands::create_new_tracker,
             commands::get_trackers_by_semester,
             commands::get_tracker,
             commands::get_tracker_data,
+            commands::recompute_all_statistics,
             commands::toggle_topic,
             commands::schedule_test,
+            commands::reschedule_test,
+            commands::rename_test,
+            commands::set_test_coverage,
             commands::get_tests_by_tracker,
             commands::get_test_details,
+            commands::export_tests_calendar,
+            commands::preview_calendar_import,
+            commands::import_calendar_tests,
+            commands::get_test_types,
+            commands::create_test_type,
+            commands::update_test_type,
+            commands::delete_test_type,
+            commands::create_assignment,
+            commands::get_assignments_by_tracker,
+            commands::get_assignment_details,
+            commands::update_assignment,
+            commands::set_assignment_status,
+            commands::grade_assignment,
+            commands::delete_assignment,
+            commands::record_test_marks,
+            commands::set_test_subject,
+            commands::set_subject_grading,
+            commands::get_grading_scales,
+            commands::create_grading_scale,
+            commands::update_grading_scale,
+            commands::delete_grading_scale,
+            commands::get_evaluation_schemes,
+            commands::create_evaluation_scheme,
+            commands::update_evaluation_scheme,
+            commands::delete_evaluation_scheme,
+            commands::get_semester_grades,
+            commands::get_grade_report,
+            commands::calculate_grade_target,
+            commands::calculate_semester_targets,
+            commands::get_study_plan,
+            commands::generate_study_plan,
+            commands::delete_study_plan,
+            commands::get_today,
+            commands::get_database_incidents,
+            commands::check_database,
+            commands::repair_database,
             commands::get_theme,
             commands::set_theme,
             commands::create_subject,
@@ -70,7 +134,16 @@ pub fn run() {
             commands::update_topic,
             commands::delete_topic,
             commands::export_syllabus,
+            commands::export_tracker,
             commands::import_syllabus,
+            commands::preview_import,
+            commands::preview_merge,
+            commands::merge_syllabus,
+            commands::get_tracker_as_text,
+            commands::preview_tracker_text,
+            commands::apply_tracker_text,
+            commands::diff_trackers,
+            commands::diff_tracker_with_syllabus,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
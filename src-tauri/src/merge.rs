@@ -0,0 +1,494 @@
+use crate::db::Database;
+use crate::diff::{diff_from_match, match_trees, SyllabusDiff, TreeMatch};
+use crate::models::*;
+use crate::syllabus::{current_version, tree_to_subjects, SyllabusExport, SyllabusSubject};
+use crate::utils::{normalize_name, parse_syllabus, to_syllabus_text, ParsedSyllabusEntry, SyllabusParseError};
+use rusqlite::Result as SqliteResult;
+use serde::Serialize;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use uuid::Uuid;
+
+// ============================================================================
+// MERGE PLAN
+// ============================================================================
+
+/// The outcome of matching an incoming syllabus against a tracker. It is
+/// returned to the UI as a preview and applied once the user approves it.
+#[derive(Debug, Clone, Serialize)]
+pub struct MergePlan {
+    pub tracker_id: String,
+    pub diff: SyllabusDiff,
+    /// Existing topics that survive the merge (unchanged, renamed or moved).
+    pub kept_topics: usize,
+    /// Completed topics among `kept_topics`.
+    pub kept_completed: usize,
+    /// Completed topics that are lost if removed items are deleted.
+    pub removed_completed: usize,
+    /// Identifies the tree the plan was made against. Applying checks it
+    /// so a preview the tracker has since moved on from is never written.
+    pub fingerprint: String,
+    #[serde(skip)]
+    existing: Vec<SubjectData>,
+    #[serde(skip)]
+    incoming: SyllabusExport,
+    #[serde(skip)]
+    matching: TreeMatch,
+}
+
+/// Hash of every row's ID, name and place in the tree, and each topic's
+/// completion: everything a plan depends on.
+pub fn tree_fingerprint(existing: &[SubjectData]) -> String {
+    let mut hasher = DefaultHasher::new();
+    for subject in existing {
+        (&subject.subject.id, &subject.subject.name, subject.units.len()).hash(&mut hasher);
+        for unit in &subject.units {
+            (&unit.unit.id, &unit.unit.name, unit.topics.len()).hash(&mut hasher);
+            for topic in &unit.topics {
+                (&topic.id, &topic.name, topic.completed).hash(&mut hasher);
+            }
+        }
+    }
+    format!("{:016x}", hasher.finish())
+}
+
+/// Matches `incoming` against the tracker's current tree by name and
+/// position. Tracker-level fields (name, description, color) are left alone.
+pub fn plan_merge(tracker_id: &str, existing: Vec<SubjectData>, incoming: SyllabusExport) -> MergePlan {
+    let old = SyllabusExport {
+        subjects: tree_to_subjects(&existing),
+        ..incoming.clone()
+    };
+    let matching = match_trees(&old, &incoming);
+    let diff = diff_from_match(&old, &incoming, &matching);
+
+    let kept = matching.matched_old_topics();
+    let (mut kept_topics, mut kept_completed, mut removed_completed) = (0, 0, 0);
+    for (os, subject) in existing.iter().enumerate() {
+        for (ou, unit) in subject.units.iter().enumerate() {
+            for (ot, topic) in unit.topics.iter().enumerate() {
+                if kept.contains(&(os, ou, ot)) {
+                    kept_topics += 1;
+                    kept_completed += topic.completed as usize;
+                } else {
+                    removed_completed += topic.completed as usize;
+                }
+            }
+        }
+    }
+
+    MergePlan {
+        tracker_id: tracker_id.to_string(),
+        diff,
+        kept_topics,
+        kept_completed,
+        removed_completed,
+        fingerprint: tree_fingerprint(&existing),
+        existing,
+        incoming,
+        matching,
+    }
+}
+
+// ============================================================================
+// APPLYING A PLAN
+// ============================================================================
+
+/// Rows written by `apply_merge`. A row that is both renamed and moved
+/// counts towards both.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MergeSummary {
+    pub inserted: usize,
+    pub renamed: usize,
+    /// Units and topics whose parent or position changed.
+    pub moved: usize,
+    pub deleted: usize,
+}
+
+/// Writes a plan to the database in one transaction. Matched rows are
+/// updated in place so their IDs and completion survive, and rows that
+/// already match the incoming tree are not touched. Removed items are only
+/// deleted when `delete_removed` is set; otherwise they stay after the
+/// incoming items of their unit or subject.
+pub fn apply_merge(db: &Database, plan: &MergePlan, delete_removed: bool, now: i64) -> SqliteResult<MergeSummary> {
+    let old = &plan.existing;
+    let m = &plan.matching;
+    let mut summary = MergeSummary::default();
+
+    db.transaction(|db| {
+        // Lay out the incoming tree, reusing matched rows
+        for (ns, subject) in plan.incoming.subjects.iter().enumerate() {
+            let subject_id = match m.subjects[ns] {
+                Some(os) => {
+                    let existing = &old[os].subject;
+                    if existing.name != subject.name {
+                        db.update_subject(&existing.id, &subject.name, now)?;
+                        summary.renamed += 1;
+                    }
+                    existing.id.clone()
+                }
+                None => {
+                    let id = Uuid::new_v4().to_string();
+                    db.create_subject(id.clone(), plan.tracker_id.clone(), subject.name.clone(), now)?;
+                    summary.inserted += 1;
+                    id
+                }
+            };
+
+            for (nu, unit) in subject.units.iter().enumerate() {
+                let unit_id = match m.units[ns][nu] {
+                    Some((os, ou)) => {
+                        let existing = &old[os].units[ou].unit;
+                        let renamed = existing.name != unit.name;
+                        let moved = existing.subject_id != subject_id || existing.order != nu as i32;
+                        if renamed || moved {
+                            db.update_unit(&existing.id, &subject_id, &unit.name, nu as i32, now)?;
+                        }
+                        summary.renamed += renamed as usize;
+                        summary.moved += moved as usize;
+                        existing.id.clone()
+                    }
+                    None => {
+                        let id = Uuid::new_v4().to_string();
+                        db.create_unit(id.clone(), subject_id.clone(), unit.name.clone(), nu as i32, now)?;
+                        summary.inserted += 1;
+                        id
+                    }
+                };
+
+                for (nt, topic) in unit.topics.iter().enumerate() {
+                    match m.topics[ns][nu][nt] {
+                        Some((os, ou, ot)) => {
+                            let existing = &old[os].units[ou].topics[ot];
+                            if existing.name != *topic {
+                                db.update_topic(&existing.id, topic, now)?;
+                                summary.renamed += 1;
+                            }
+                            if existing.unit_id != unit_id || existing.order != nt as i32 {
+                                db.move_topic(&existing.id, &unit_id, nt as i32, now)?;
+                                summary.moved += 1;
+                            }
+                        }
+                        None => {
+                            let id = Uuid::new_v4().to_string();
+                            db.create_topic(id, unit_id.clone(), topic.clone(), nt as i32, now)?;
+                            summary.inserted += 1;
+                        }
+                    }
+                }
+            }
+        }
+
+        // Then deal with whatever the incoming syllabus no longer has
+        let subject_slots: HashMap<usize, usize> = m
+            .subjects
+            .iter()
+            .enumerate()
+            .filter_map(|(ns, os)| os.map(|os| (os, ns)))
+            .collect();
+        let mut unit_slots = HashMap::new();
+        for (ns, units) in m.units.iter().enumerate() {
+            for (nu, old_unit) in units.iter().enumerate() {
+                if let Some(key) = old_unit {
+                    unit_slots.insert(*key, (ns, nu));
+                }
+            }
+        }
+        let kept_topics = m.matched_old_topics();
+
+        for (os, subject) in old.iter().enumerate() {
+            let mut next_unit_order = subject_slots
+                .get(&os)
+                .map(|&ns| plan.incoming.subjects[ns].units.len())
+                .unwrap_or(0);
+
+            for (ou, unit) in subject.units.iter().enumerate() {
+                let mut next_topic_order = unit_slots
+                    .get(&(os, ou))
+                    .map(|&(ns, nu)| plan.incoming.subjects[ns].units[nu].topics.len())
+                    .unwrap_or(0);
+
+                for (ot, topic) in unit.topics.iter().enumerate() {
+                    if kept_topics.contains(&(os, ou, ot)) {
+                        continue;
+                    }
+                    if delete_removed {
+                        db.delete_topic(&topic.id)?;
+                        summary.deleted += 1;
+                    } else if unit_slots.contains_key(&(os, ou)) {
+                        if topic.order != next_topic_order as i32 {
+                            db.move_topic(&topic.id, &unit.unit.id, next_topic_order as i32, now)?;
+                            summary.moved += 1;
+                        }
+                        next_topic_order += 1;
+                    }
+                }
+
+                if unit_slots.contains_key(&(os, ou)) {
+                    continue;
+                }
+                if delete_removed {
+                    db.delete_unit(&unit.unit.id)?;
+                    summary.deleted += 1;
+                } else if subject_slots.contains_key(&os) {
+                    if unit.unit.order != next_unit_order as i32 {
+                        db.update_unit(&unit.unit.id, &subject.subject.id, &unit.unit.name, next_unit_order as i32, now)?;
+                        summary.moved += 1;
+                    }
+                    next_unit_order += 1;
+                }
+            }
+
+            if delete_removed && !subject_slots.contains_key(&os) {
+                db.delete_subject(&subject.subject.id)?;
+                summary.deleted += 1;
+            }
+        }
+
+        Ok::<_, rusqlite::Error>(())
+    })?;
+
+    Ok(summary)
+}
+
+// ============================================================================
+// TEXT EDITING
+// ============================================================================
+
+/// The tracker's outline as `Subject >>> Unit >>> topics` lines.
+pub fn tracker_text(existing: Vec<SubjectData>) -> String {
+    let entries: Vec<ParsedSyllabusEntry> = existing.iter().map(ParsedSyllabusEntry::from).collect();
+    to_syllabus_text(&entries)
+}
+
+/// The first subject, or unit within a subject, that shares its name with
+/// a sibling. Text lines are matched to rows by name and repeated names are
+/// merged, so the two siblings can't be told apart in the text.
+fn duplicate_sibling(subjects: &[SubjectData]) -> Option<(&str, Option<&str>)> {
+    let mut subject_names = HashSet::new();
+    for data in subjects {
+        if !subject_names.insert(normalize_name(&data.subject.name)) {
+            return Some((&data.subject.name, None));
+        }
+        let mut unit_names = HashSet::new();
+        for unit in &data.units {
+            if !unit_names.insert(normalize_name(&unit.unit.name)) {
+                return Some((&data.subject.name, Some(&unit.unit.name)));
+            }
+        }
+    }
+    None
+}
+
+/// Line and column of the first line in `text` that names `subject`, and
+/// `unit` within it if given, or the start of the text if none does.
+fn name_position(text: &str, subject: &str, unit: Option<&str>) -> (usize, usize) {
+    let subject = normalize_name(subject);
+    let unit = unit.map(normalize_name);
+    for (idx, line) in text.lines().enumerate() {
+        let Ok(entries) = parse_syllabus(line) else {
+            continue;
+        };
+        let names_it = entries.iter().any(|e| {
+            normalize_name(&e.subject_name) == subject
+                && unit
+                    .as_ref()
+                    .is_none_or(|unit| e.units.iter().any(|u| normalize_name(&u.unit_name) == *unit))
+        });
+        if names_it {
+            return (idx + 1, line.chars().take_while(|c| c.is_whitespace()).count() + 1);
+        }
+    }
+    (1, 1)
+}
+
+/// Plans turning the tracker into the outline in `text`. Applied with
+/// `delete_removed`, lines missing from the text are deleted, and lines
+/// that didn't change keep their rows untouched. Subjects keep their
+/// creation order since they have no position of their own.
+///
+/// Trackers with two subjects, or two units in a subject, of the same name
+/// are rejected: their lines would be merged and one of the pair deleted.
+pub fn plan_text_edit(
+    tracker_id: &str,
+    existing: Vec<SubjectData>,
+    text: &str,
+) -> Result<MergePlan, Vec<SyllabusParseError>> {
+    let entries = parse_syllabus(text)?;
+    if let Some((subject, unit)) = duplicate_sibling(&existing) {
+        let (line, column) = name_position(text, subject, unit);
+        let message = match unit {
+            Some(unit) => format!("'{}' has more than one unit named '{}'", subject, unit),
+            None => format!("there is more than one subject named '{}'", subject),
+        };
+        let message = format!("{}; rename one before editing the tracker as text", message);
+        return Err(vec![SyllabusParseError { line, column, message }]);
+    }
+    let incoming = SyllabusExport {
+        name: String::new(),
+        description: None,
+        color: None,
+        version: current_version(),
+        subjects: entries.iter().map(SyllabusSubject::from).collect(),
+    };
+
+    Ok(plan_merge(tracker_id, existing, incoming))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::{DiffLevel, SyllabusChange};
+    use crate::syllabus::SyllabusSubject;
+    use crate::utils::{get_tracker_tree, parse_syllabus};
+    use std::path::PathBuf;
+
+    fn syllabus(text: &str) -> SyllabusExport {
+        SyllabusExport {
+            name: "Tracker".to_string(),
+            description: None,
+            color: None,
+            version: "1.0".to_string(),
+            subjects: parse_syllabus(text).unwrap().iter().map(SyllabusSubject::from).collect(),
+        }
+    }
+
+    #[test]
+    fn test_merge_keeps_completion_and_flags_removals() {
+        let db = Database::new(PathBuf::from(":memory:")).unwrap();
+        db.create_semester("sem".into(), "Sem".into(), 0).unwrap();
+        db.create_tracker("tr".into(), "sem".into(), "Tracker".into(), None, None, 0).unwrap();
+        db.create_subject("os".into(), "tr".into(), "Operating Systems".into(), 0).unwrap();
+        db.create_unit("u1".into(), "os".into(), "Processes".into(), 0, 0).unwrap();
+        db.create_topic("t1".into(), "u1".into(), "Threads".into(), 0, 0).unwrap();
+        db.create_topic("t2".into(), "u1".into(), "Process Scheduling".into(), 1, 0).unwrap();
+        db.create_topic("t3".into(), "u1".into(), "Deadlocks".into(), 2, 0).unwrap();
+        db.toggle_topic_completion("t1", 0).unwrap();
+        db.toggle_topic_completion("t2", 0).unwrap();
+
+        let incoming = syllabus(
+            "Operating Systems >>> Processes >>> CPU Process Scheduling, IPC
+             Operating Systems >>> Concurrency >>> Threads",
+        );
+        let plan = plan_merge("tr", get_tracker_tree(&db, "tr").unwrap(), incoming.clone());
+        assert_eq!((plan.kept_topics, plan.kept_completed, plan.removed_completed), (2, 2, 0));
+        assert_eq!(plan.diff.moved_topics, 1);
+        assert!(plan.diff.changes.iter().any(|c| matches!(
+            c,
+            SyllabusChange::Removed { level: DiffLevel::Topic, .. }
+        )));
+
+        apply_merge(&db, &plan, false, 1).unwrap();
+        let processes: Vec<(String, bool)> = db
+            .get_topics_by_unit("u1")
+            .unwrap()
+            .into_iter()
+            .map(|t| (t.name, t.completed))
+            .collect();
+        assert_eq!(
+            processes,
+            vec![
+                ("CPU Process Scheduling".to_string(), true),
+                ("IPC".to_string(), false),
+                ("Deadlocks".to_string(), false),
+            ]
+        );
+        let threads = db.get_topic("t1").unwrap().unwrap();
+        assert!(threads.completed);
+        assert_ne!(threads.unit_id, "u1");
+
+        let plan = plan_merge("tr", get_tracker_tree(&db, "tr").unwrap(), incoming);
+        assert_eq!(plan.diff.changes.len(), 1);
+        apply_merge(&db, &plan, true, 2).unwrap();
+        assert_eq!(db.get_tracker("tr").unwrap().unwrap().total_topics, 3);
+    }
+
+    #[test]
+    fn test_text_edit_keeps_unchanged_rows() {
+        let db = Database::new(PathBuf::from(":memory:")).unwrap();
+        db.create_semester("sem".into(), "Sem".into(), 0).unwrap();
+        db.create_tracker("tr".into(), "sem".into(), "Tracker".into(), None, None, 0).unwrap();
+        db.create_subject("os".into(), "tr".into(), "Operating Systems".into(), 0).unwrap();
+        db.create_subject("empty".into(), "tr".into(), "Electives".into(), 0).unwrap();
+        db.create_unit("u1".into(), "os".into(), "Processes".into(), 0, 0).unwrap();
+        db.create_unit("u2".into(), "os".into(), "Memory".into(), 1, 0).unwrap();
+        db.create_topic("t1".into(), "u1".into(), "Threads".into(), 0, 0).unwrap();
+        db.create_topic("t2".into(), "u1".into(), "Scheduling".into(), 1, 0).unwrap();
+        db.create_topic("t3".into(), "u2".into(), "Paging".into(), 0, 0).unwrap();
+        db.toggle_topic_completion("t2", 0).unwrap();
+
+        let text = tracker_text(get_tracker_tree(&db, "tr").unwrap());
+        assert_eq!(
+            text,
+            "Operating Systems >>> Processes >>> Threads, Scheduling\nOperating Systems >>> Memory >>> Paging\nElectives"
+        );
+        let plan = plan_text_edit("tr", get_tracker_tree(&db, "tr").unwrap(), &text).unwrap();
+        let summary = apply_merge(&db, &plan, true, 1).unwrap();
+        assert_eq!((summary.inserted, summary.renamed, summary.moved, summary.deleted), (0, 0, 0, 0));
+
+        let edited = "Operating Systems >>> Processes >>> CPU Scheduling, Threads, Deadlocks\nElectives";
+        let plan = plan_text_edit("tr", get_tracker_tree(&db, "tr").unwrap(), edited).unwrap();
+        let summary = apply_merge(&db, &plan, true, 2).unwrap();
+        assert_eq!((summary.inserted, summary.renamed, summary.moved, summary.deleted), (1, 1, 2, 2));
+
+        let topics: Vec<(String, String, bool)> = db
+            .get_topics_by_unit("u1")
+            .unwrap()
+            .into_iter()
+            .map(|t| (t.id, t.name, t.completed))
+            .collect();
+        assert_eq!(topics[0], ("t2".to_string(), "CPU Scheduling".to_string(), true));
+        assert_eq!(topics[1].0, "t1");
+        assert_eq!(topics[2].1, "Deadlocks");
+        assert!(db.get_units_by_subject("os").unwrap().iter().all(|u| u.id == "u1"));
+        assert_eq!(db.get_subjects_by_tracker("tr").unwrap().len(), 2);
+        assert_eq!(db.recompute_all_statistics().unwrap(), 0);
+
+        // A unit under the subject without units goes into that subject
+        let with_unit = format!("{}\nElectives >>> Ethics >>> Privacy", edited);
+        let plan = plan_text_edit("tr", get_tracker_tree(&db, "tr").unwrap(), &with_unit).unwrap();
+        assert_eq!(plan.diff.changes.len(), 1);
+        let summary = apply_merge(&db, &plan, true, 3).unwrap();
+        assert_eq!((summary.inserted, summary.deleted), (2, 0));
+        assert_eq!(db.get_subjects_by_tracker("tr").unwrap().len(), 2);
+        assert_eq!(db.get_units_by_subject("empty").unwrap()[0].name, "Ethics");
+
+        // Dropping a subject's lines deletes it, units or not
+        db.delete_unit(&db.get_units_by_subject("empty").unwrap()[0].id).unwrap();
+        let plan = plan_text_edit("tr", get_tracker_tree(&db, "tr").unwrap(), edited.lines().next().unwrap()).unwrap();
+        let summary = apply_merge(&db, &plan, true, 4).unwrap();
+        assert_eq!(summary.deleted, 1);
+        assert_eq!(db.get_subjects_by_tracker("tr").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_text_edit_rejects_same_named_siblings() {
+        let db = Database::new(PathBuf::from(":memory:")).unwrap();
+        db.create_semester("sem".into(), "Sem".into(), 0).unwrap();
+        db.create_tracker("tr".into(), "sem".into(), "Tracker".into(), None, None, 0).unwrap();
+        db.create_subject("os".into(), "tr".into(), "OS".into(), 0).unwrap();
+        db.create_unit("u0".into(), "os".into(), "Processes".into(), 0, 0).unwrap();
+        db.create_unit("u1".into(), "os".into(), "Review".into(), 1, 0).unwrap();
+        db.create_unit("u2".into(), "os".into(), "Review".into(), 2, 0).unwrap();
+        db.create_topic("t1".into(), "u1".into(), "A".into(), 0, 0).unwrap();
+        db.create_topic("t2".into(), "u2".into(), "B".into(), 0, 0).unwrap();
+
+        // The unchanged text would otherwise fold u2 into u1
+        let text = tracker_text(get_tracker_tree(&db, "tr").unwrap());
+        let errors = plan_text_edit("tr", get_tracker_tree(&db, "tr").unwrap(), &text).unwrap_err();
+        assert_eq!((errors[0].line, errors[0].column), (2, 1));
+        assert!(errors[0].message.contains("'Review'"));
+        let units: Vec<String> = db.get_units_by_subject("os").unwrap().into_iter().map(|u| u.id).collect();
+        assert_eq!(units, vec!["u0", "u1", "u2"]);
+        assert_eq!(db.get_topic("t2").unwrap().unwrap().unit_id, "u2");
+
+        db.create_subject("os2".into(), "tr".into(), "os".into(), 0).unwrap();
+        db.create_unit("u3".into(), "os2".into(), "Files".into(), 0, 0).unwrap();
+        db.update_unit("u2", "os", "Revision", 2, 0).unwrap();
+        let text = tracker_text(get_tracker_tree(&db, "tr").unwrap());
+        let errors = plan_text_edit("tr", get_tracker_tree(&db, "tr").unwrap(), &text).unwrap_err();
+        assert_eq!((errors[0].line, errors[0].column), (1, 1));
+        assert!(errors[0].message.contains("subject named 'os'"));
+    }
+}
@@ -1,3 +1,4 @@
+use chrono::Weekday;
 use serde::{Deserialize, Serialize};
 
 // ============================================================================
@@ -39,6 +40,8 @@ pub struct Subject {
     pub id: String,
     pub tracker_id: String,
     pub name: String,
+    pub credits: Option<f64>,
+    pub evaluation_scheme_id: Option<String>,
     pub created_at: i64,
     pub updated_at: i64,
 }
@@ -64,57 +67,254 @@ pub struct Topic {
     pub updated_at: i64,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubjectData {
+    pub subject: Subject,
+    pub units: Vec<UnitData>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnitData {
+    pub unit: Unit,
+    pub topics: Vec<Topic>,
+}
+
 // ============================================================================
 // TESTS
 // ============================================================================
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+/// A kind of test, such as a class test or a viva. Tests store the type's
+/// ID, so renaming a type relabels every test of that type.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TestType {
+    pub id: String,
+    pub name: String,
+    pub color: Option<String>,
+    /// How many days before a test it shows up as a priority.
+    pub priority_window_days: i32,
+    /// Relative importance, e.g. an end semester exam outweighs a quiz.
+    pub weight: f64,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Test {
+    pub id: String,
+    pub tracker_id: String,
+    pub name: String,
+    pub test_type: String, // test type ID
+    pub scheduled_date: i64, // timestamp in ms
+    pub marks_obtained: Option<f64>,
+    pub max_marks: Option<f64>,
+    /// The subject the marks count towards, whatever the coverage spans.
+    pub subject_id: Option<String>,
+    /// Raised by every change that shows in the test's calendar event.
+    pub revision: i64,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestCoverage {
+    pub id: String,
+    pub test_id: String,
+    pub unit_id: Option<String>,
+    pub topic_id: Option<String>,
+}
+
+/// Coverage of a test being scheduled, before it has an ID.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestCoverageInput {
+    pub unit_id: Option<String>,
+    pub topic_id: Option<String>,
+}
+
+/// A test to schedule, as sent in bulk by the calendar import.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewTest {
+    pub name: String,
+    pub test_type: String,
+    pub scheduled_date: i64,
+    pub coverage: Vec<TestCoverageInput>,
+}
+
+// ============================================================================
+// ASSIGNMENTS
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
-pub enum TestType {
-    LabPractical,
-    ClassTest,
-    ISA,
-    ESA,
+pub enum AssignmentStatus {
+    Todo,
+    Doing,
+    Submitted,
+    Graded,
 }
 
-impl TestType {
+impl AssignmentStatus {
     pub fn as_str(&self) -> &str {
         match self {
-            TestType::LabPractical => "lab_practical",
-            TestType::ClassTest => "class_test",
-            TestType::ISA => "isa",
-            TestType::ESA => "esa",
+            AssignmentStatus::Todo => "todo",
+            AssignmentStatus::Doing => "doing",
+            AssignmentStatus::Submitted => "submitted",
+            AssignmentStatus::Graded => "graded",
         }
     }
 
-    pub fn from_str(s: &str) -> Option<Self> {
+    pub fn parse(s: &str) -> Option<Self> {
         match s {
-            "lab_practical" => Some(TestType::LabPractical),
-            "class_test" => Some(TestType::ClassTest),
-            "isa" => Some(TestType::ISA),
-            "esa" => Some(TestType::ESA),
+            "todo" => Some(AssignmentStatus::Todo),
+            "doing" => Some(AssignmentStatus::Doing),
+            "submitted" => Some(AssignmentStatus::Submitted),
+            "graded" => Some(AssignmentStatus::Graded),
             _ => None,
         }
     }
+
+    /// Whether the assignment still needs work before its deadline.
+    pub fn is_open(&self) -> bool {
+        matches!(self, AssignmentStatus::Todo | AssignmentStatus::Doing)
+    }
 }
 
+/// Coursework with a deadline. Belongs to a tracker and optionally to one
+/// of its subjects.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Test {
+pub struct Assignment {
     pub id: String,
     pub tracker_id: String,
+    pub subject_id: Option<String>,
     pub name: String,
-    pub test_type: TestType,
-    pub scheduled_date: i64, // timestamp in ms
+    pub description: Option<String>,
+    pub due_date: i64, // timestamp in ms
+    pub status: AssignmentStatus,
+    pub marks_obtained: Option<f64>,
+    pub max_marks: Option<f64>,
     pub created_at: i64,
     pub updated_at: i64,
 }
 
+/// The editable fields of an assignment, as sent by the assignment form.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct TestCoverage {
+pub struct AssignmentInput {
+    pub subject_id: Option<String>,
+    pub name: String,
+    pub description: Option<String>,
+    pub due_date: i64,
+    pub topic_ids: Vec<String>,
+}
+
+// ============================================================================
+// GRADES
+// ============================================================================
+
+/// The lowest percentage that earns a letter grade, and its grade points.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GradeBand {
+    pub letter: String,
+    pub min_percentage: f64,
+    pub grade_points: f64,
+}
+
+/// Maps a final percentage to a letter and grade points. Bands are sorted
+/// from the highest `min_percentage` down.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GradingScale {
     pub id: String,
-    pub test_id: String,
-    pub unit_id: Option<String>,
-    pub topic_id: Option<String>,
+    pub name: String,
+    pub bands: Vec<GradeBand>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GradingScaleInput {
+    pub name: String,
+    pub bands: Vec<GradeBand>,
+}
+
+/// How much the tests of one type count towards a subject's final mark.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EvaluationComponent {
+    pub test_type_id: String,
+    /// Share of the final mark, relative to the other components.
+    pub weight: f64,
+    /// Only the best N tests of the type count, e.g. best 2 of 3 ISAs.
+    pub best_of: Option<i32>,
+}
+
+/// How a subject's test percentages combine into a final grade.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvaluationScheme {
+    pub id: String,
+    pub name: String,
+    pub grading_scale_id: String,
+    pub components: Vec<EvaluationComponent>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvaluationSchemeInput {
+    pub name: String,
+    pub grading_scale_id: String,
+    pub components: Vec<EvaluationComponent>,
+}
+
+// ============================================================================
+// STUDY PLAN
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CapacityUnit {
+    Topics,
+    Minutes,
+}
+
+impl CapacityUnit {
+    pub fn as_str(&self) -> &str {
+        match self {
+            CapacityUnit::Topics => "topics",
+            CapacityUnit::Minutes => "minutes",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "topics" => Some(CapacityUnit::Topics),
+            "minutes" => Some(CapacityUnit::Minutes),
+            _ => None,
+        }
+    }
+}
+
+/// How much a tracker's study plan puts on each day. Trackers with settings
+/// have their plan rebuilt whenever topics or tests change.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PlanSettings {
+    pub capacity_unit: CapacityUnit,
+    pub daily_capacity: i32,
+    /// Estimated study time per topic, which is what a topic costs when the
+    /// capacity is in minutes.
+    pub minutes_per_topic: i32,
+    /// Weekdays with no study time; nothing is planned on them.
+    #[serde(default)]
+    pub rest_days: Vec<Weekday>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanItem {
+    pub id: String,
+    pub tracker_id: String,
+    pub topic_id: String,
+    /// The test the topic is studied for.
+    pub test_id: Option<String>,
+    /// Local date as YYYY-MM-DD, or `None` if it doesn't fit before its test.
+    pub day: Option<String>,
+    pub minutes: i32,
+    pub created_at: i64,
 }
 
 // ============================================================================
@@ -149,6 +349,32 @@ pub struct TrackerProgress {
     pub subjects: Vec<SubjectProgress>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrackerSummary {
+    pub tracker: Tracker,
+    pub total_topics: i32,
+    pub completed_topics: i32,
+    pub percentage: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpcomingTest {
+    pub test: Test,
+    pub tracker_name: String,
+    pub days_remaining: i32,
+    pub time_remaining: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SemesterOverview {
+    pub semester_id: String,
+    pub total_topics: i32,
+    pub completed_topics: i32,
+    pub percentage: f64,
+    pub trackers: Vec<TrackerSummary>,
+    pub upcoming_tests: Vec<UpcomingTest>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PriorityTest {
     pub test: Test,
@@ -157,3 +383,35 @@ pub struct PriorityTest {
     pub time_remaining: String,
     pub covered_topics: Vec<String>,
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PriorityAssignment {
+    pub assignment: Assignment,
+    pub days_remaining: i32,
+    pub time_remaining: String,
+    pub linked_topics: Vec<String>,
+}
+
+// ============================================================================
+// DATABASE CHECKS
+// ============================================================================
+
+/// A row whose parent row is missing, as found by `PRAGMA foreign_key_check`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForeignKeyViolation {
+    pub table: String,
+    pub rowid: i64,
+    pub parent: String,
+    pub column: String,
+    /// The key's ON DELETE action, which says how to repair the row.
+    pub on_delete: String,
+}
+
+/// Units of one subject, or topics of one unit, sharing an `order`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateOrder {
+    pub table: String,
+    pub parent_id: String,
+    pub order: i32,
+    pub count: i32,
+}
@@ -0,0 +1,335 @@
+use chrono::{Datelike, Days, NaiveDate};
+use rusqlite::Result as SqliteResult;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet, VecDeque};
+use uuid::Uuid;
+use crate::db::Database;
+use crate::models::*;
+use crate::utils::{get_tracker_tree, local_day};
+
+const DAY_FORMAT: &str = "%Y-%m-%d";
+
+// ============================================================================
+// SCHEDULING
+// ============================================================================
+
+/// The incomplete topics a test covers, to be studied before its day.
+#[derive(Debug, Clone)]
+pub struct PlanDeadline {
+    pub test_id: String,
+    pub day: NaiveDate,
+    pub topic_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScheduledTopic {
+    pub topic_id: String,
+    pub test_id: String,
+    /// `None` if the topic doesn't fit before its test.
+    pub day: Option<NaiveDate>,
+}
+
+/// Spreads topics over the days from `today`, earliest test first, never
+/// later than the day before the topic's test. Each day takes as many topics
+/// as its capacity allows, but always at least one, so a topic longer than
+/// a day's budget still gets a day of its own. Rest days are skipped.
+pub fn schedule_topics(today: NaiveDate, settings: &PlanSettings, deadlines: &[PlanDeadline]) -> Vec<ScheduledTopic> {
+    let cost = match settings.capacity_unit {
+        CapacityUnit::Topics => 1,
+        CapacityUnit::Minutes => settings.minutes_per_topic.max(1),
+    };
+
+    let mut queues: Vec<(&PlanDeadline, VecDeque<&String>)> = deadlines
+        .iter()
+        .map(|d| (d, d.topic_ids.iter().collect()))
+        .collect();
+    queues.sort_by_key(|(d, _)| d.day);
+
+    let mut scheduled = Vec::new();
+    let mut day = today;
+    loop {
+        // Whatever is left for a test on or before this day no longer fits
+        for (deadline, queue) in queues.iter_mut().filter(|(d, _)| d.day <= day) {
+            scheduled.extend(queue.drain(..).map(|topic_id| ScheduledTopic {
+                topic_id: topic_id.clone(),
+                test_id: deadline.test_id.clone(),
+                day: None,
+            }));
+        }
+        if queues.iter().all(|(_, queue)| queue.is_empty()) {
+            break;
+        }
+
+        let mut used = 0;
+        let resting = settings.rest_days.contains(&day.weekday());
+        while let Some((deadline, queue)) = queues.iter_mut().find(|(_, q)| !q.is_empty()) {
+            if resting || (used > 0 && used + cost > settings.daily_capacity) {
+                break;
+            }
+            if let Some(topic_id) = queue.pop_front() {
+                scheduled.push(ScheduledTopic {
+                    topic_id: topic_id.clone(),
+                    test_id: deadline.test_id.clone(),
+                    day: Some(day),
+                });
+                used += cost;
+            }
+        }
+
+        match day.checked_add_days(Days::new(1)) {
+            Some(next) => day = next,
+            None => break,
+        }
+    }
+    scheduled
+}
+
+/// The incomplete topics each upcoming test covers, in outline order. A
+/// topic covered by several tests is studied for the earliest one.
+fn upcoming_deadlines(db: &Database, tree: &[SubjectData], tracker_id: &str, today: NaiveDate) -> SqliteResult<Vec<PlanDeadline>> {
+    let mut tests = db.get_tests_by_tracker(tracker_id)?;
+    tests.retain(|t| local_day(t.scheduled_date) > today);
+    tests.sort_by_key(|t| t.scheduled_date);
+
+    let mut claimed = HashSet::new();
+    let mut deadlines = Vec::new();
+    for test in tests {
+        let coverage = db.get_test_coverage(&test.id)?;
+        let units: HashSet<&str> = coverage.iter().filter_map(|c| c.unit_id.as_deref()).collect();
+        let topics: HashSet<&str> = coverage.iter().filter_map(|c| c.topic_id.as_deref()).collect();
+
+        let topic_ids = tree
+            .iter()
+            .flat_map(|s| &s.units)
+            .flat_map(|u| u.topics.iter().map(move |t| (u, t)))
+            .filter(|(u, t)| !t.completed && (units.contains(u.unit.id.as_str()) || topics.contains(t.id.as_str())))
+            .filter(|(_, t)| claimed.insert(t.id.clone()))
+            .map(|(_, t)| t.id.clone())
+            .collect();
+        deadlines.push(PlanDeadline {
+            test_id: test.id,
+            day: local_day(test.scheduled_date),
+            topic_ids,
+        });
+    }
+    Ok(deadlines)
+}
+
+/// Rebuilds a tracker's plan from `today` on.
+pub fn rebuild_plan(db: &Database, tracker_id: &str, settings: &PlanSettings, today: NaiveDate, now: i64) -> SqliteResult<()> {
+    let tree = get_tracker_tree(db, tracker_id)?;
+    let deadlines = upcoming_deadlines(db, &tree, tracker_id, today)?;
+    let items: Vec<PlanItem> = schedule_topics(today, settings, &deadlines)
+        .into_iter()
+        .map(|s| PlanItem {
+            id: Uuid::new_v4().to_string(),
+            tracker_id: tracker_id.to_string(),
+            topic_id: s.topic_id,
+            test_id: Some(s.test_id),
+            day: s.day.map(|d| d.format(DAY_FORMAT).to_string()),
+            minutes: settings.minutes_per_topic,
+            created_at: now,
+        })
+        .collect();
+    db.replace_plan_items(tracker_id, &today.format(DAY_FORMAT).to_string(), &items)
+}
+
+/// Rebalances a tracker's plan after its topics or tests change. Trackers
+/// without plan settings have no plan, so nothing happens.
+pub fn refresh_plan(db: &Database, tracker_id: &str, now: i64) -> SqliteResult<()> {
+    match db.get_plan_settings(tracker_id)? {
+        Some(settings) => rebuild_plan(db, tracker_id, &settings, local_day(now), now),
+        None => Ok(()),
+    }
+}
+
+// ============================================================================
+// PLAN VIEW
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PlannedTopic {
+    pub item_id: String,
+    pub topic_id: String,
+    pub topic_name: String,
+    pub unit_name: String,
+    pub subject_name: String,
+    pub test_id: Option<String>,
+    pub test_name: Option<String>,
+    pub minutes: i32,
+    pub completed: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PlanDay {
+    pub day: String,
+    pub minutes: i32,
+    pub topics: Vec<PlannedTopic>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StudyPlan {
+    pub tracker_id: String,
+    pub settings: PlanSettings,
+    pub days: Vec<PlanDay>,
+    /// Topics that don't fit before their test at the current capacity.
+    pub unscheduled: Vec<PlannedTopic>,
+}
+
+/// A tracker's stored plan with names filled in, or `None` if it has none.
+pub fn load_plan(db: &Database, tracker_id: &str) -> SqliteResult<Option<StudyPlan>> {
+    let settings = match db.get_plan_settings(tracker_id)? {
+        Some(settings) => settings,
+        None => return Ok(None),
+    };
+
+    let tree = get_tracker_tree(db, tracker_id)?;
+    let topics: HashMap<&str, (&Topic, &str, &str)> = tree
+        .iter()
+        .flat_map(|s| s.units.iter().map(move |u| (s, u)))
+        .flat_map(|(s, u)| u.topics.iter().map(move |t| (t.id.as_str(), (t, u.unit.name.as_str(), s.subject.name.as_str()))))
+        .collect();
+    let test_names: HashMap<String, String> = db
+        .get_tests_by_tracker(tracker_id)?
+        .into_iter()
+        .map(|t| (t.id, t.name))
+        .collect();
+
+    let mut days: Vec<PlanDay> = Vec::new();
+    let mut unscheduled = Vec::new();
+    for item in db.get_plan_items(tracker_id)? {
+        let Some((topic, unit_name, subject_name)) = topics.get(item.topic_id.as_str()) else {
+            continue;
+        };
+        let planned = PlannedTopic {
+            topic_id: item.topic_id,
+            topic_name: topic.name.clone(),
+            unit_name: unit_name.to_string(),
+            subject_name: subject_name.to_string(),
+            test_name: item.test_id.as_ref().and_then(|id| test_names.get(id)).cloned(),
+            test_id: item.test_id,
+            minutes: item.minutes,
+            completed: topic.completed,
+            item_id: item.id,
+        };
+        match item.day {
+            None => unscheduled.push(planned),
+            Some(day) => match days.last_mut() {
+                Some(last) if last.day == day => {
+                    last.minutes += planned.minutes;
+                    last.topics.push(planned);
+                }
+                _ => days.push(PlanDay {
+                    day,
+                    minutes: planned.minutes,
+                    topics: vec![planned],
+                }),
+            },
+        }
+    }
+
+    Ok(Some(StudyPlan {
+        tracker_id: tracker_id.to_string(),
+        settings,
+        days,
+        unscheduled,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Local, TimeZone, Weekday};
+    use std::path::PathBuf;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 3, day).unwrap()
+    }
+
+    fn ids(names: &[&str]) -> Vec<String> {
+        names.iter().map(|n| n.to_string()).collect()
+    }
+
+    #[test]
+    fn test_schedule_topics() {
+        let settings = PlanSettings {
+            capacity_unit: CapacityUnit::Minutes,
+            daily_capacity: 90,
+            minutes_per_topic: 45,
+            rest_days: Vec::new(),
+        };
+        let deadlines = vec![
+            PlanDeadline { test_id: "late".into(), day: date(6), topic_ids: ids(&["l1", "l2"]) },
+            PlanDeadline { test_id: "soon".into(), day: date(3), topic_ids: ids(&["s1", "s2", "s3", "s4", "s5"]) },
+        ];
+        let plan = schedule_topics(date(1), &settings, &deadlines);
+        let days: Vec<(&str, Option<u32>)> = plan
+            .iter()
+            .map(|s| (s.topic_id.as_str(), s.day.map(|d| d.format("%d").to_string().parse().unwrap())))
+            .collect();
+        // Two topics a day; the earlier test goes first and its fifth topic
+        // doesn't fit before March 3rd
+        assert_eq!(days, vec![
+            ("s1", Some(1)), ("s2", Some(1)),
+            ("s3", Some(2)), ("s4", Some(2)),
+            ("s5", None),
+            ("l1", Some(3)), ("l2", Some(3)),
+        ]);
+
+        // A topic longer than the daily budget still gets a day of its own
+        let tight = PlanSettings { daily_capacity: 30, ..settings.clone() };
+        let plan = schedule_topics(date(1), &tight, &deadlines[..1]);
+        assert_eq!(plan.iter().map(|s| s.day).collect::<Vec<_>>(), vec![Some(date(1)), Some(date(2))]);
+
+        // Nothing lands on a rest day; March 2nd 2025 is a Sunday
+        let weekdays = PlanSettings { rest_days: vec![Weekday::Sun], ..tight };
+        let plan = schedule_topics(date(1), &weekdays, &deadlines[..1]);
+        assert_eq!(plan.iter().map(|s| s.day).collect::<Vec<_>>(), vec![Some(date(1)), Some(date(3))]);
+    }
+
+    #[test]
+    fn test_plan_rebalances() {
+        let db = Database::new(PathBuf::from(":memory:")).unwrap();
+        db.create_semester("sem".into(), "Sem".into(), 0).unwrap();
+        db.create_tracker("tr".into(), "sem".into(), "Tracker".into(), None, None, 0).unwrap();
+        db.create_subject("os".into(), "tr".into(), "OS".into(), 0).unwrap();
+        db.create_unit("u1".into(), "os".into(), "Processes".into(), 0, 0).unwrap();
+        for (i, name) in ["Threads", "Scheduling", "Deadlocks"].iter().enumerate() {
+            db.create_topic(format!("t{}", i), "u1".into(), name.to_string(), i as i32, 0).unwrap();
+        }
+        let now = Local.with_ymd_and_hms(2025, 3, 1, 9, 0, 0).unwrap().timestamp_millis();
+        let exam = Local.with_ymd_and_hms(2025, 3, 4, 20, 0, 0).unwrap().timestamp_millis();
+        db.create_test("isa".into(), "tr".into(), "ISA 1".into(), "isa".into(), exam, 0).unwrap();
+        db.create_test_coverage("c1".into(), "isa".into(), Some("u1".into()), None).unwrap();
+
+        // No settings means no plan to rebalance
+        refresh_plan(&db, "tr", now).unwrap();
+        assert!(load_plan(&db, "tr").unwrap().is_none());
+
+        let settings = PlanSettings {
+            capacity_unit: CapacityUnit::Topics,
+            daily_capacity: 1,
+            minutes_per_topic: 30,
+            rest_days: Vec::new(),
+        };
+        db.set_plan_settings("tr", &settings, now).unwrap();
+        refresh_plan(&db, "tr", now).unwrap();
+        let plan = load_plan(&db, "tr").unwrap().unwrap();
+        let days: Vec<&str> = plan.days.iter().map(|d| d.day.as_str()).collect();
+        assert_eq!(days, vec!["2025-03-01", "2025-03-02", "2025-03-03"]);
+        assert_eq!(plan.days[0].topics[0].test_name.as_deref(), Some("ISA 1"));
+
+        // Completing a topic and moving the test up shifts everything left
+        db.toggle_topic_completion("t0", now).unwrap();
+        db.reschedule_test("isa", exam - 86_400_000, now).unwrap();
+        refresh_plan(&db, "tr", now).unwrap();
+        let plan = load_plan(&db, "tr").unwrap().unwrap();
+        let topics: Vec<(&str, &str)> = plan
+            .days
+            .iter()
+            .flat_map(|d| d.topics.iter().map(move |t| (d.day.as_str(), t.topic_name.as_str())))
+            .collect();
+        assert_eq!(topics, vec![("2025-03-01", "Scheduling"), ("2025-03-02", "Deadlocks")]);
+        assert!(plan.unscheduled.is_empty());
+    }
+}
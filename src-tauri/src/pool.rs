@@ -0,0 +1,207 @@
+use rusqlite::Result as SqliteResult;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, MutexGuard, PoisonError, TryLockError};
+use std::time::SystemTime;
+use crate::db::Database;
+
+/// Read connections kept open next to the writer.
+pub const READER_COUNT: usize = 4;
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RecoveryAction {
+    /// The connection passed its checks and was put back in service.
+    Recovered,
+    /// The connection failed its checks and was replaced with a new one.
+    Reopened,
+}
+
+/// A command panicked while holding a connection.
+#[derive(Debug, Clone, Serialize)]
+pub struct DbIncident {
+    pub occurred_at: i64,
+    /// "writer" or "reader N".
+    pub connection: String,
+    pub action: RecoveryAction,
+    pub detail: String,
+}
+
+/// One writer plus a few read-only connections to the same WAL database.
+/// SQLite allows a single writer at a time anyway, so writes queue on one
+/// mutex while reads spread over the others and never wait on a write.
+pub struct DbPool {
+    db_path: PathBuf,
+    writer: Mutex<Database>,
+    readers: Vec<Mutex<Database>>,
+    next_reader: AtomicUsize,
+    incidents: Mutex<Vec<DbIncident>>,
+}
+
+impl DbPool {
+    pub fn open(db_path: PathBuf, reader_count: usize) -> SqliteResult<Self> {
+        // The writer creates and migrates the schema before any reader opens
+        let writer = Database::new(db_path.clone())?;
+        // Every ":memory:" connection is its own empty database
+        let reader_count = if db_path == Path::new(":memory:") { 0 } else { reader_count };
+        let readers = (0..reader_count)
+            .map(|_| Database::open_reader(&db_path).map(Mutex::new))
+            .collect::<SqliteResult<Vec<_>>>()?;
+        Ok(DbPool {
+            db_path,
+            writer: Mutex::new(writer),
+            readers,
+            next_reader: AtomicUsize::new(0),
+            incidents: Mutex::new(Vec::new()),
+        })
+    }
+
+    pub fn write(&self) -> Result<MutexGuard<'_, Database>, String> {
+        match self.writer.lock() {
+            Ok(writer) => Ok(writer),
+            Err(poisoned) => self.recover(&self.writer, poisoned, None),
+        }
+    }
+
+    /// An idle reader if there is one, otherwise waits for the next in turn.
+    /// Without readers, reads go through the writer.
+    pub fn read(&self) -> Result<MutexGuard<'_, Database>, String> {
+        if self.readers.is_empty() {
+            return self.write();
+        }
+        let start = self.next_reader.fetch_add(1, Ordering::Relaxed);
+        for offset in 0..self.readers.len() {
+            let index = (start + offset) % self.readers.len();
+            match self.readers[index].try_lock() {
+                Ok(reader) => return Ok(reader),
+                Err(TryLockError::Poisoned(poisoned)) => {
+                    return self.recover(&self.readers[index], poisoned, Some(index));
+                }
+                Err(TryLockError::WouldBlock) => {}
+            }
+        }
+        let index = start % self.readers.len();
+        match self.readers[index].lock() {
+            Ok(reader) => Ok(reader),
+            Err(poisoned) => self.recover(&self.readers[index], poisoned, Some(index)),
+        }
+    }
+
+    /// Incidents since the app started, oldest first.
+    pub fn incidents(&self) -> Vec<DbIncident> {
+        self.incidents
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// A command panicked while holding this connection. Checks it, reopens
+    /// it if the check fails, and only then clears the poison, so a failed
+    /// reopen is retried by the next command instead of handing out a
+    /// broken connection.
+    fn recover<'a>(
+        &self,
+        slot: &'a Mutex<Database>,
+        poisoned: PoisonError<MutexGuard<'a, Database>>,
+        reader: Option<usize>,
+    ) -> Result<MutexGuard<'a, Database>, String> {
+        let mut db = poisoned.into_inner();
+        let (action, detail) = match db.check_connection() {
+            Ok(()) => (RecoveryAction::Recovered, "Connection passed an integrity check".to_string()),
+            Err(problem) => {
+                *db = match reader {
+                    Some(_) => Database::open_reader(&self.db_path),
+                    None => Database::new(self.db_path.clone()),
+                }
+                .map_err(|e| format!("Failed to reopen the database after a crash: {}", e))?;
+                (RecoveryAction::Reopened, problem)
+            }
+        };
+        slot.clear_poison();
+
+        let incident = DbIncident {
+            occurred_at: SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .unwrap()
+                .as_millis() as i64,
+            connection: reader.map_or_else(|| "writer".to_string(), |index| format!("reader {}", index)),
+            action,
+            detail,
+        };
+        eprintln!("Recovered database connection after a panic: {:?}", incident);
+        self.incidents
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(incident);
+        Ok(db)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    fn temp_pool(readers: usize) -> (DbPool, PathBuf) {
+        let dir = std::env::temp_dir().join(format!("studyapp-pool-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        (DbPool::open(dir.join("studyapp.db"), readers).unwrap(), dir)
+    }
+
+    #[test]
+    fn test_readers_see_writes_while_another_read_runs() {
+        let (pool, dir) = temp_pool(2);
+
+        // A long read holds one reader; the writer and other reader still work
+        let busy = pool.read().unwrap();
+        pool.write().unwrap().create_semester("sem".into(), "Sem".into(), 0).unwrap();
+        let semesters = pool.read().unwrap().get_all_semesters().unwrap();
+        assert_eq!(semesters.len(), 1);
+        assert!(busy.get_all_semesters().is_ok());
+        assert!(pool.read().unwrap().create_semester("x".into(), "X".into(), 0).is_err());
+        drop(busy);
+
+        std::fs::remove_dir_all(dir).unwrap();
+
+        let memory = DbPool::open(PathBuf::from(":memory:"), 2).unwrap();
+        memory.write().unwrap().create_semester("sem".into(), "Sem".into(), 0).unwrap();
+        assert_eq!(memory.read().unwrap().get_all_semesters().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_recovers_from_a_panic_mid_transaction() {
+        let (pool, dir) = temp_pool(1);
+        pool.write().unwrap().create_semester("kept".into(), "Kept".into(), 0).unwrap();
+
+        let crashed = catch_unwind(AssertUnwindSafe(|| {
+            let db = pool.write().unwrap();
+            db.transaction(|db| {
+                db.create_semester("lost".into(), "Lost".into(), 0)?;
+                panic!("command bug");
+                #[allow(unreachable_code)]
+                Ok::<_, rusqlite::Error>(())
+            })
+        }));
+        assert!(crashed.is_err());
+        let crashed = catch_unwind(AssertUnwindSafe(|| {
+            let _db = pool.read().unwrap();
+            panic!("another command bug");
+        }));
+        assert!(crashed.is_err());
+
+        let names: Vec<String> = pool.write().unwrap().get_all_semesters().unwrap().into_iter().map(|s| s.id).collect();
+        assert_eq!(names, vec!["kept"]);
+        assert_eq!(pool.read().unwrap().get_all_semesters().unwrap().len(), 1);
+
+        let incidents = pool.incidents();
+        let connections: Vec<(&str, RecoveryAction)> = incidents.iter().map(|i| (i.connection.as_str(), i.action)).collect();
+        assert_eq!(connections, vec![("writer", RecoveryAction::Recovered), ("reader 0", RecoveryAction::Recovered)]);
+
+        // Recovery happens once; later commands lock normally
+        assert!(pool.write().is_ok());
+        assert_eq!(pool.incidents().len(), 2);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
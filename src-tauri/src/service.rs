@@ -0,0 +1,1052 @@
+use crate::models::*;
+use crate::db::{Database, DEFAULT_PRIORITY_WINDOW_DAYS};
+use crate::utils::{format_parse_errors, calculate_tracker_progress, semester_overview, get_covered_topic_names, get_tracker_tree, get_days_remaining, format_time_remaining, is_within_priority_window, local_day};
+use crate::importers::{SyllabusFormat, parse_syllabus_input};
+use crate::exporters::{ExportFormat, ExportedFile, render_tracker};
+use crate::ical::{CalendarImportPreview, CalendarScope, collect_calendar_tests, export_calendar, parse_calendar, plan_calendar_import};
+use crate::diff::{SyllabusDiff, diff_syllabi};
+use crate::agenda::{Agenda, build_agenda};
+use crate::integrity::{self, DatabaseReport, RepairReport};
+use crate::planner::{StudyPlan, load_plan, rebuild_plan, refresh_plan};
+use crate::grades::{GradeReport, GradingConfig, SemesterGrades, SemesterTargets, SubjectTarget, grade_report, semester_grades, semester_targets, validate_evaluation_scheme, validate_grading_scale, validate_marks};
+use crate::merge::{MergePlan, MergeSummary, apply_merge, plan_merge, plan_text_edit, tracker_text};
+use crate::syllabus::{SyllabusExport, format_issues, load_syllabus, parse_syllabus_value, tracker_to_syllabus};
+use serde::Serialize;
+use uuid::Uuid;
+use std::collections::HashSet;
+use std::time::SystemTime;
+
+fn get_current_timestamp() -> i64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as i64
+}
+
+/// Everything the app does with its data, over one connection. The Tauri
+/// commands and `studyapp-cli` are thin adapters around it, so it can be
+/// used and tested without a window or an async runtime.
+pub struct StudyService<'a> {
+    db: &'a Database,
+    /// The time writes are stamped with and deadlines are counted from.
+    now: i64,
+}
+
+impl<'a> StudyService<'a> {
+    pub fn new(db: &'a Database) -> Self {
+        Self::at(db, get_current_timestamp())
+    }
+
+    /// A service whose clock is fixed at `now`, in milliseconds.
+    pub fn at(db: &'a Database, now: i64) -> Self {
+        StudyService { db, now }
+    }
+
+    fn require_semester(&self, semester_id: &str) -> Result<Semester, String> {
+        self.db.get_all_semesters()
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .find(|s| s.id == semester_id)
+            .ok_or_else(|| "Semester not found".to_string())
+    }
+
+    fn require_tracker(&self, tracker_id: &str) -> Result<Tracker, String> {
+        self.db.get_tracker(tracker_id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "Tracker not found".to_string())
+    }
+
+    // ========================================================================
+    // SEMESTERS
+    // ========================================================================
+
+    pub fn create_semester(&self, name: String) -> Result<Semester, String> {
+        let id = Uuid::new_v4().to_string();
+        self.db.create_semester(id, name, self.now)
+            .map_err(|e| e.to_string())
+    }
+
+    pub fn get_all_semesters(&self) -> Result<Vec<Semester>, String> {
+        self.db.get_all_semesters().map_err(|e| e.to_string())
+    }
+
+    pub fn delete_semester(&self, id: &str) -> Result<(), String> {
+        self.db.delete_semester(id).map_err(|e| e.to_string())
+    }
+
+    /// Topic progress of every tracker in a semester plus its upcoming tests.
+    pub fn semester_overview(&self, semester_id: &str) -> Result<SemesterOverview, String> {
+        self.require_semester(semester_id)?;
+        semester_overview(self.db, semester_id, self.now).map_err(|e| e.to_string())
+    }
+
+    // ========================================================================
+    // TRACKERS
+    // ========================================================================
+
+    /// Creates a tracker from a pasted syllabus, detecting its format unless
+    /// one is given.
+    pub fn create_tracker(
+        &self,
+        semester_id: String,
+        name: String,
+        syllabus_text: &str,
+        format: Option<SyllabusFormat>,
+    ) -> Result<Tracker, String> {
+        let db = self.db;
+        let now = self.now;
+        let parsed_entries = parse_syllabus_input(syllabus_text, format).map_err(|errors| format_parse_errors(&errors))?;
+        self.require_semester(&semester_id)?;
+
+        // Create tracker
+        let tracker_id = Uuid::new_v4().to_string();
+        db.create_tracker(tracker_id.clone(), semester_id, name, None, None, now)
+            .map_err(|e| e.to_string())?;
+
+        // Create subjects, units, and topics
+        for entry in &parsed_entries {
+            let subject_id = Uuid::new_v4().to_string();
+            db.create_subject(subject_id.clone(), tracker_id.clone(), entry.subject_name.clone(), now)
+                .map_err(|e| e.to_string())?;
+
+            for (unit_idx, unit) in entry.units.iter().enumerate() {
+                let unit_id = Uuid::new_v4().to_string();
+                db.create_unit(
+                    unit_id.clone(),
+                    subject_id.clone(),
+                    unit.unit_name.clone(),
+                    unit_idx as i32,
+                    now,
+                )
+                .map_err(|e| e.to_string())?;
+
+                for (topic_idx, topic) in unit.topics.iter().enumerate() {
+                    let topic_id = Uuid::new_v4().to_string();
+                    db.create_topic(topic_id, unit_id.clone(), topic.clone(), topic_idx as i32, now)
+                        .map_err(|e| e.to_string())?;
+                }
+            }
+        }
+
+        // Return updated tracker
+        db.get_tracker(&tracker_id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "Failed to retrieve created tracker".to_string())
+    }
+
+    pub fn get_trackers_by_semester(&self, semester_id: &str) -> Result<Vec<Tracker>, String> {
+        self.db.get_trackers_by_semester(semester_id)
+            .map_err(|e| e.to_string())
+    }
+
+    pub fn get_tracker(&self, id: &str) -> Result<Option<Tracker>, String> {
+        self.db.get_tracker(id).map_err(|e| e.to_string())
+    }
+
+    /// The tracker's subjects, units and topics, in order.
+    pub fn get_tracker_tree(&self, tracker_id: &str) -> Result<Vec<SubjectData>, String> {
+        self.require_tracker(tracker_id)?;
+        get_tracker_tree(self.db, tracker_id).map_err(|e| e.to_string())
+    }
+
+    pub fn tracker_progress(&self, tracker_id: &str) -> Result<TrackerProgress, String> {
+        let subjects = self.get_tracker_tree(tracker_id)?;
+        Ok(calculate_tracker_progress(tracker_id, &subjects))
+    }
+
+    /// The tree, progress, tests and assignments of a tracker, with the tests
+    /// and assignments inside their priority window picked out.
+    pub fn get_tracker_data(&self, tracker_id: &str) -> Result<TrackerData, String> {
+        let db = self.db;
+        let subjects_data = get_tracker_tree(db, tracker_id)
+            .map_err(|e| e.to_string())?;
+
+        let progress = calculate_tracker_progress(tracker_id, &subjects_data);
+
+        let tests = db.get_tests_by_tracker(tracker_id)
+            .map_err(|e| e.to_string())?;
+
+        let test_types = db.get_all_test_types()
+            .map_err(|e| e.to_string())?;
+
+        let mut priority_tests = Vec::new();
+        for test in tests.iter() {
+            // Tests of a type that no longer exists fall back to the default window
+            let window = test_types
+                .iter()
+                .find(|t| t.id == test.test_type)
+                .map_or(DEFAULT_PRIORITY_WINDOW_DAYS, |t| t.priority_window_days);
+            if is_within_priority_window(test.scheduled_date, window, self.now) {
+                let coverage = db.get_test_coverage(&test.id)
+                    .map_err(|e| e.to_string())?;
+
+                // Collect all topics covered by this test
+                let covered_topics = get_covered_topic_names(db, &coverage)
+                    .map_err(|e| e.to_string())?;
+
+                priority_tests.push(PriorityTest {
+                    test: test.clone(),
+                    coverage: coverage.clone(),
+                    days_remaining: get_days_remaining(test.scheduled_date, self.now),
+                    time_remaining: format_time_remaining(test.scheduled_date, self.now),
+                    covered_topics,
+                });
+            }
+        }
+
+        // Sort by days remaining (closer tests first)
+        priority_tests.sort_by_key(|t| t.days_remaining);
+
+        let assignments = db.get_assignments_by_tracker(tracker_id)
+            .map_err(|e| e.to_string())?;
+
+        let mut priority_assignments = Vec::new();
+        for assignment in assignments.iter() {
+            if assignment.status.is_open()
+                && is_within_priority_window(assignment.due_date, DEFAULT_PRIORITY_WINDOW_DAYS, self.now)
+            {
+                priority_assignments.push(PriorityAssignment {
+                    assignment: assignment.clone(),
+                    days_remaining: get_days_remaining(assignment.due_date, self.now),
+                    time_remaining: format_time_remaining(assignment.due_date, self.now),
+                    linked_topics: db.get_assignment_topic_names(&assignment.id)
+                        .map_err(|e| e.to_string())?,
+                });
+            }
+        }
+
+        Ok(TrackerData {
+            subjects: subjects_data,
+            progress,
+            all_tests: tests,
+            priority_tests,
+            test_types,
+            all_assignments: assignments,
+            priority_assignments,
+        })
+    }
+
+    /// Recounts every tracker's cached totals. Returns how many were wrong.
+    pub fn recompute_all_statistics(&self) -> Result<usize, String> {
+        self.db.recompute_all_statistics().map_err(|e| e.to_string())
+    }
+
+    // ========================================================================
+    // SUBJECTS & TOPICS
+    // ========================================================================
+
+    pub fn create_subject(&self, tracker_id: String, name: String) -> Result<Subject, String> {
+        let id = Uuid::new_v4().to_string();
+        self.db.create_subject(id, tracker_id, name, self.now)
+            .map_err(|e| e.to_string())
+    }
+
+    pub fn update_subject(&self, subject_id: &str, name: &str) -> Result<(), String> {
+        self.db.update_subject(subject_id, name, self.now)
+            .map_err(|e| e.to_string())
+    }
+
+    /// Deletes a subject with its units and topics, and takes them off the
+    /// study plan.
+    pub fn delete_subject(&self, subject_id: &str) -> Result<(), String> {
+        let subject = self.db.get_subject(subject_id).map_err(|e| e.to_string())?;
+        self.db.delete_subject(subject_id)
+            .map_err(|e| e.to_string())?;
+        if let Some(subject) = subject {
+            refresh_plan(self.db, &subject.tracker_id, self.now).map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    }
+
+    /// Adds a topic at the end of its unit and plans it.
+    pub fn create_topic(&self, unit_id: String, name: String) -> Result<Topic, String> {
+        let id = Uuid::new_v4().to_string();
+        let topics = self.db.get_topics_by_unit(&unit_id).map_err(|e| e.to_string())?;
+        let order = topics.iter().map(|t| t.order).max().unwrap_or(-1) + 1;
+
+        let topic = self.db.create_topic(id, unit_id, name, order, self.now)
+            .map_err(|e| e.to_string())?;
+        if let Some(tracker_id) = self.db.get_tracker_id_for_topic(&topic.id).map_err(|e| e.to_string())? {
+            refresh_plan(self.db, &tracker_id, self.now).map_err(|e| e.to_string())?;
+        }
+        Ok(topic)
+    }
+
+    pub fn update_topic(&self, topic_id: &str, name: &str) -> Result<(), String> {
+        self.db.update_topic(topic_id, name, self.now)
+            .map_err(|e| e.to_string())
+    }
+
+    /// Deletes a topic and rebalances the plan it was on.
+    pub fn delete_topic(&self, topic_id: &str) -> Result<(), String> {
+        let tracker_id = self.db.get_tracker_id_for_topic(topic_id).map_err(|e| e.to_string())?;
+        self.db.delete_topic(topic_id)
+            .map_err(|e| e.to_string())?;
+        if let Some(tracker_id) = tracker_id {
+            refresh_plan(self.db, &tracker_id, self.now).map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    }
+
+    /// Flips a topic's completion and rebalances its tracker's study plan.
+    pub fn toggle_topic(&self, topic_id: &str) -> Result<Option<Topic>, String> {
+        let topic = self.db.toggle_topic_completion(topic_id, self.now)
+            .map_err(|e| e.to_string())?;
+
+        // Completed topics leave the study plan, reopened ones rejoin it
+        if let Some(tracker_id) = self.db.get_tracker_id_for_topic(topic_id).map_err(|e| e.to_string())? {
+            refresh_plan(self.db, &tracker_id, self.now).map_err(|e| e.to_string())?;
+        }
+        Ok(topic)
+    }
+
+    // ========================================================================
+    // TESTS
+    // ========================================================================
+
+    /// Checks the test type, creates the test with its coverage and
+    /// rebalances the tracker's study plan.
+    pub fn schedule_test(
+        &self,
+        tracker_id: String,
+        name: String,
+        test_type: String,
+        scheduled_date: i64,
+        coverage: Vec<TestCoverageInput>,
+    ) -> Result<Test, String> {
+        self.db.get_test_type(&test_type)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Unknown test type '{}'", test_type))?;
+
+        let test = create_test_with_coverage(self.db, tracker_id.clone(), name, test_type, scheduled_date, coverage, self.now)
+            .map_err(|e| e.to_string())?;
+        refresh_plan(self.db, &tracker_id, self.now).map_err(|e| e.to_string())?;
+        Ok(test)
+    }
+
+    pub fn reschedule_test(&self, test_id: &str, scheduled_date: i64) -> Result<Test, String> {
+        let test = self.db.get_test(test_id)
+            .map_err(|e| e.to_string())?
+            .ok_or("Test not found")?;
+        self.db.reschedule_test(test_id, scheduled_date, self.now)
+            .map_err(|e| e.to_string())?;
+        refresh_plan(self.db, &test.tracker_id, self.now).map_err(|e| e.to_string())?;
+        self.db.get_test(test_id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "Test not found".to_string())
+    }
+
+    pub fn rename_test(&self, test_id: &str, name: &str) -> Result<Test, String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("Test name cannot be empty".to_string());
+        }
+        self.db.get_test(test_id)
+            .map_err(|e| e.to_string())?
+            .ok_or("Test not found")?;
+        self.db.rename_test(test_id, name, self.now)
+            .map_err(|e| e.to_string())?;
+        self.db.get_test(test_id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "Test not found".to_string())
+    }
+
+    /// Replaces what a test covers and rebalances the tracker's study plan.
+    pub fn set_test_coverage(&self, test_id: &str, coverage: Vec<TestCoverageInput>) -> Result<Test, String> {
+        let test = self.db.get_test(test_id)
+            .map_err(|e| e.to_string())?
+            .ok_or("Test not found")?;
+        self.db.transaction(|db| {
+            db.delete_test_coverage(test_id)?;
+            for cov_input in coverage {
+                let cov_id = Uuid::new_v4().to_string();
+                db.create_test_coverage(cov_id, test_id.to_string(), cov_input.unit_id, cov_input.topic_id)?;
+            }
+            db.assign_test_subjects_from_coverage(Some(test_id))?;
+            db.bump_test_revision(test_id, self.now)?;
+            refresh_plan(db, &test.tracker_id, self.now)
+        })
+        .map_err(|e: rusqlite::Error| e.to_string())?;
+        self.db.get_test(test_id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "Test not found".to_string())
+    }
+
+    pub fn get_tests_by_tracker(&self, tracker_id: &str) -> Result<Vec<Test>, String> {
+        self.db.get_tests_by_tracker(tracker_id)
+            .map_err(|e| e.to_string())
+    }
+
+    pub fn get_test_details(&self, test_id: &str) -> Result<Option<TestDetails>, String> {
+        let Some(test) = self.db.get_test(test_id).map_err(|e| e.to_string())? else {
+            return Ok(None);
+        };
+        let coverage = self.db.get_test_coverage(test_id)
+            .map_err(|e| e.to_string())?;
+
+        let covered_topics = get_covered_topic_names(self.db, &coverage)
+            .map_err(|e| e.to_string())?;
+
+        Ok(Some(TestDetails {
+            days_remaining: get_days_remaining(test.scheduled_date, self.now),
+            time_remaining: format_time_remaining(test.scheduled_date, self.now),
+            test,
+            coverage,
+            covered_topics,
+        }))
+    }
+
+    /// The tests of a tracker, a semester or every tracker as an iCalendar
+    /// file.
+    pub fn export_tests_calendar(&self, scope: &CalendarScope) -> Result<ExportedFile, String> {
+        let (calendar_name, tests) = collect_calendar_tests(self.db, scope)
+            .map_err(|e| e.to_string())?
+            .ok_or("Tracker or semester not found")?;
+        Ok(export_calendar(&calendar_name, &tests, self.now))
+    }
+
+    /// Reads an exam timetable and proposes a test per event, without
+    /// saving anything.
+    pub fn preview_calendar_import(&self, tracker_id: &str, ics: &str) -> Result<CalendarImportPreview, String> {
+        let (events, warnings) = parse_calendar(ics)?;
+        self.require_tracker(tracker_id)?;
+
+        let subjects = get_tracker_tree(self.db, tracker_id)
+            .map_err(|e| e.to_string())?;
+        let existing = self.db.get_tests_by_tracker(tracker_id)
+            .map_err(|e| e.to_string())?;
+        let test_types = self.db.get_all_test_types()
+            .map_err(|e| e.to_string())?;
+
+        Ok(CalendarImportPreview {
+            candidates: plan_calendar_import(&events, &subjects, &existing, &test_types),
+            warnings,
+        })
+    }
+
+    pub fn import_calendar_tests(&self, tracker_id: &str, tests: Vec<NewTest>) -> Result<Vec<Test>, String> {
+        self.require_tracker(tracker_id)?;
+
+        // Check every type up front so a bad entry doesn't leave half an import
+        let test_types = self.db.get_all_test_types()
+            .map_err(|e| e.to_string())?;
+        for test in &tests {
+            if !test_types.iter().any(|t| t.id == test.test_type) {
+                return Err(format!("Unknown test type '{}' for '{}'", test.test_type, test.name));
+            }
+        }
+
+        self.db.transaction(|db| {
+            let created = tests
+                .into_iter()
+                .map(|test| {
+                    create_test_with_coverage(db, tracker_id.to_string(), test.name, test.test_type, test.scheduled_date, test.coverage, self.now)
+                })
+                .collect::<rusqlite::Result<Vec<Test>>>()?;
+            refresh_plan(db, tracker_id, self.now)?;
+            Ok(created)
+        })
+        .map_err(|e: rusqlite::Error| e.to_string())
+    }
+
+    // ========================================================================
+    // TEST TYPES
+    // ========================================================================
+
+    pub fn get_test_types(&self) -> Result<Vec<TestType>, String> {
+        self.db.get_all_test_types().map_err(|e| e.to_string())
+    }
+
+    pub fn create_test_type(
+        &self,
+        name: &str,
+        color: Option<String>,
+        priority_window_days: i32,
+        weight: f64,
+    ) -> Result<TestType, String> {
+        let name = validate_test_type(name, priority_window_days, weight)?;
+        let id = Uuid::new_v4().to_string();
+        self.db.create_test_type(id, name, color, priority_window_days, weight, self.now)
+            .map_err(|e| e.to_string())
+    }
+
+    pub fn update_test_type(
+        &self,
+        id: &str,
+        name: &str,
+        color: Option<&str>,
+        priority_window_days: i32,
+        weight: f64,
+    ) -> Result<TestType, String> {
+        let name = validate_test_type(name, priority_window_days, weight)?;
+        self.db.get_test_type(id)
+            .map_err(|e| e.to_string())?
+            .ok_or("Test type not found")?;
+        self.db.update_test_type(id, &name, color, priority_window_days, weight, self.now)
+            .map_err(|e| e.to_string())?;
+        self.db.get_test_type(id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "Test type not found".to_string())
+    }
+
+    /// Deletes a test type no test uses. Tests keep their type's ID, so
+    /// deleting a type in use would leave them unlabelled.
+    pub fn delete_test_type(&self, id: &str) -> Result<(), String> {
+        let in_use = self.db.count_tests_of_type(id).map_err(|e| e.to_string())?;
+        if in_use > 0 {
+            return Err(format!("This test type is used by {} test(s)", in_use));
+        }
+        // Dropping it from a scheme would quietly re-weight every grade
+        let schemes = self.db.get_scheme_names_using_test_type(id).map_err(|e| e.to_string())?;
+        if !schemes.is_empty() {
+            return Err(format!("This test type is part of the evaluation scheme(s): {}", schemes.join(", ")));
+        }
+        self.db.delete_test_type(id).map_err(|e| e.to_string())
+    }
+
+    // ========================================================================
+    // ASSIGNMENTS
+    // ========================================================================
+
+    /// Trims the name and checks the subject and topics belong to the tracker.
+    fn validate_assignment(&self, tracker_id: &str, mut input: AssignmentInput) -> Result<AssignmentInput, String> {
+        input.name = input.name.trim().to_string();
+        if input.name.is_empty() {
+            return Err("Assignment name cannot be empty".to_string());
+        }
+
+        let subjects = get_tracker_tree(self.db, tracker_id).map_err(|e| e.to_string())?;
+        if let Some(subject_id) = &input.subject_id {
+            if !subjects.iter().any(|s| &s.subject.id == subject_id) {
+                return Err("Subject not found in this tracker".to_string());
+            }
+        }
+        let in_tracker = |topic_id: &String| {
+            subjects
+                .iter()
+                .flat_map(|s| &s.units)
+                .flat_map(|u| &u.topics)
+                .any(|t| &t.id == topic_id)
+        };
+        if !input.topic_ids.iter().all(in_tracker) {
+            return Err("Topic not found in this tracker".to_string());
+        }
+
+        Ok(input)
+    }
+
+    fn find_assignment(&self, assignment_id: &str) -> Result<Assignment, String> {
+        self.db.get_assignment(assignment_id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "Assignment not found".to_string())
+    }
+
+    pub fn create_assignment(&self, tracker_id: String, assignment: AssignmentInput) -> Result<Assignment, String> {
+        self.require_tracker(&tracker_id)?;
+
+        let input = self.validate_assignment(&tracker_id, assignment)?;
+        let id = Uuid::new_v4().to_string();
+        self.db.transaction(|db| db.create_assignment(id, tracker_id, &input, self.now))
+            .map_err(|e| e.to_string())
+    }
+
+    pub fn get_assignments_by_tracker(&self, tracker_id: &str) -> Result<Vec<Assignment>, String> {
+        self.db.get_assignments_by_tracker(tracker_id)
+            .map_err(|e| e.to_string())
+    }
+
+    pub fn get_assignment_details(&self, assignment_id: &str) -> Result<Option<AssignmentDetails>, String> {
+        let Some(assignment) = self.db.get_assignment(assignment_id).map_err(|e| e.to_string())? else {
+            return Ok(None);
+        };
+        Ok(Some(AssignmentDetails {
+            topic_ids: self.db.get_assignment_topic_ids(assignment_id)
+                .map_err(|e| e.to_string())?,
+            linked_topics: self.db.get_assignment_topic_names(assignment_id)
+                .map_err(|e| e.to_string())?,
+            days_remaining: get_days_remaining(assignment.due_date, self.now),
+            time_remaining: format_time_remaining(assignment.due_date, self.now),
+            assignment,
+        }))
+    }
+
+    pub fn update_assignment(&self, assignment_id: &str, assignment: AssignmentInput) -> Result<Assignment, String> {
+        let existing = self.find_assignment(assignment_id)?;
+
+        let input = self.validate_assignment(&existing.tracker_id, assignment)?;
+        self.db.transaction(|db| db.update_assignment(assignment_id, &input, self.now))
+            .map_err(|e| e.to_string())?;
+        self.find_assignment(assignment_id)
+    }
+
+    pub fn set_assignment_status(&self, assignment_id: &str, status: AssignmentStatus) -> Result<Assignment, String> {
+        self.find_assignment(assignment_id)?;
+        self.db.set_assignment_status(assignment_id, status, self.now)
+            .map_err(|e| e.to_string())?;
+        self.find_assignment(assignment_id)
+    }
+
+    /// Records the marks for an assignment, which also marks it as graded.
+    pub fn grade_assignment(&self, assignment_id: &str, marks_obtained: f64, max_marks: f64) -> Result<Assignment, String> {
+        validate_marks(Some(marks_obtained), Some(max_marks))?;
+        self.find_assignment(assignment_id)?;
+        self.db.grade_assignment(assignment_id, marks_obtained, max_marks, self.now)
+            .map_err(|e| e.to_string())?;
+        self.find_assignment(assignment_id)
+    }
+
+    pub fn delete_assignment(&self, assignment_id: &str) -> Result<(), String> {
+        self.db.delete_assignment(assignment_id)
+            .map_err(|e| e.to_string())
+    }
+
+    // ========================================================================
+    // GRADES
+    // ========================================================================
+
+    /// Records the marks scored on a test. Passing `None` for both clears them.
+    pub fn record_test_marks(&self, test_id: &str, marks_obtained: Option<f64>, max_marks: Option<f64>) -> Result<Test, String> {
+        let marks = validate_marks(marks_obtained, max_marks)?;
+        self.db.get_test(test_id)
+            .map_err(|e| e.to_string())?
+            .ok_or("Test not found")?;
+        self.db.set_test_marks(test_id, marks, self.now)
+            .map_err(|e| e.to_string())?;
+        self.db.get_test(test_id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "Test not found".to_string())
+    }
+
+    /// Sets the subject a test's marks count towards, which must be in the
+    /// test's tracker. `None` leaves the marks out of every grade.
+    pub fn set_test_subject(&self, test_id: &str, subject_id: Option<&str>) -> Result<Test, String> {
+        let test = self.db.get_test(test_id)
+            .map_err(|e| e.to_string())?
+            .ok_or("Test not found")?;
+        if let Some(subject_id) = subject_id {
+            let subject = self.db.get_subject(subject_id)
+                .map_err(|e| e.to_string())?
+                .ok_or("Subject not found")?;
+            if subject.tracker_id != test.tracker_id {
+                return Err("Subject is not in the test's tracker".to_string());
+            }
+        }
+        self.db.set_test_subject(test_id, subject_id, self.now)
+            .map_err(|e| e.to_string())?;
+        self.db.get_test(test_id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "Test not found".to_string())
+    }
+
+    pub fn set_subject_grading(
+        &self,
+        subject_id: &str,
+        credits: Option<f64>,
+        evaluation_scheme_id: Option<&str>,
+    ) -> Result<Subject, String> {
+        if credits.is_some_and(|c| !c.is_finite() || c < 0.0) {
+            return Err("Credits cannot be negative".to_string());
+        }
+        self.db.get_subject(subject_id)
+            .map_err(|e| e.to_string())?
+            .ok_or("Subject not found")?;
+        if let Some(scheme_id) = evaluation_scheme_id {
+            self.db.get_evaluation_scheme(scheme_id)
+                .map_err(|e| e.to_string())?
+                .ok_or("Evaluation scheme not found")?;
+        }
+        self.db.set_subject_grading(subject_id, credits, evaluation_scheme_id, self.now)
+            .map_err(|e| e.to_string())?;
+        self.db.get_subject(subject_id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "Subject not found".to_string())
+    }
+
+    pub fn get_grading_scales(&self) -> Result<Vec<GradingScale>, String> {
+        self.db.get_all_grading_scales().map_err(|e| e.to_string())
+    }
+
+    pub fn create_grading_scale(&self, scale: GradingScaleInput) -> Result<GradingScale, String> {
+        let scale = validate_grading_scale(scale)?;
+        let id = Uuid::new_v4().to_string();
+        self.db.transaction(|db| db.create_grading_scale(id, &scale, self.now))
+            .map_err(|e| e.to_string())
+    }
+
+    pub fn update_grading_scale(&self, id: &str, scale: GradingScaleInput) -> Result<GradingScale, String> {
+        let scale = validate_grading_scale(scale)?;
+        self.db.get_grading_scale(id)
+            .map_err(|e| e.to_string())?
+            .ok_or("Grading scale not found")?;
+        self.db.transaction(|db| db.update_grading_scale(id, &scale, self.now))
+            .map_err(|e| e.to_string())?;
+        self.db.get_grading_scale(id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "Grading scale not found".to_string())
+    }
+
+    pub fn delete_grading_scale(&self, id: &str) -> Result<(), String> {
+        let in_use = self.db.count_schemes_using_scale(id).map_err(|e| e.to_string())?;
+        if in_use > 0 {
+            return Err(format!("This grading scale is used by {} evaluation scheme(s)", in_use));
+        }
+        self.db.delete_grading_scale(id).map_err(|e| e.to_string())
+    }
+
+    pub fn get_evaluation_schemes(&self) -> Result<Vec<EvaluationScheme>, String> {
+        self.db.get_all_evaluation_schemes().map_err(|e| e.to_string())
+    }
+
+    pub fn create_evaluation_scheme(&self, scheme: EvaluationSchemeInput) -> Result<EvaluationScheme, String> {
+        let test_types = self.db.get_all_test_types().map_err(|e| e.to_string())?;
+        let scales = self.db.get_all_grading_scales().map_err(|e| e.to_string())?;
+        let scheme = validate_evaluation_scheme(scheme, &test_types, &scales)?;
+        let id = Uuid::new_v4().to_string();
+        self.db.transaction(|db| db.create_evaluation_scheme(id, &scheme, self.now))
+            .map_err(|e| e.to_string())
+    }
+
+    pub fn update_evaluation_scheme(&self, id: &str, scheme: EvaluationSchemeInput) -> Result<EvaluationScheme, String> {
+        self.db.get_evaluation_scheme(id)
+            .map_err(|e| e.to_string())?
+            .ok_or("Evaluation scheme not found")?;
+        let test_types = self.db.get_all_test_types().map_err(|e| e.to_string())?;
+        let scales = self.db.get_all_grading_scales().map_err(|e| e.to_string())?;
+        let scheme = validate_evaluation_scheme(scheme, &test_types, &scales)?;
+        self.db.transaction(|db| db.update_evaluation_scheme(id, &scheme, self.now))
+            .map_err(|e| e.to_string())?;
+        self.db.get_evaluation_scheme(id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "Evaluation scheme not found".to_string())
+    }
+
+    pub fn delete_evaluation_scheme(&self, id: &str) -> Result<(), String> {
+        let in_use = self.db.count_subjects_using_scheme(id).map_err(|e| e.to_string())?;
+        if in_use > 0 {
+            return Err(format!("This evaluation scheme is used by {} subject(s)", in_use));
+        }
+        self.db.delete_evaluation_scheme(id).map_err(|e| e.to_string())
+    }
+
+    /// Subject grades and the SGPA for one semester.
+    pub fn get_semester_grades(&self, semester_id: &str) -> Result<SemesterGrades, String> {
+        let semester = self.require_semester(semester_id)?;
+        let config = GradingConfig::load(self.db).map_err(|e| e.to_string())?;
+        semester_grades(self.db, &config, &semester).map_err(|e| e.to_string())
+    }
+
+    /// Grades for every semester with the cumulative CGPA.
+    pub fn get_grade_report(&self) -> Result<GradeReport, String> {
+        grade_report(self.db).map_err(|e| e.to_string())
+    }
+
+    /// What a subject needs on its remaining tests to earn `target_grade`.
+    pub fn calculate_grade_target(&self, subject_id: &str, target_grade: &str) -> Result<SubjectTarget, String> {
+        let subject = self.db.get_subject(subject_id)
+            .map_err(|e| e.to_string())?
+            .ok_or("Subject not found")?;
+        let config = GradingConfig::load(self.db).map_err(|e| e.to_string())?;
+        config.subject_target(self.db, &subject, target_grade)
+    }
+
+    /// The same target grade worked out for every subject in a semester.
+    pub fn calculate_semester_targets(&self, semester_id: &str, target_grade: &str) -> Result<SemesterTargets, String> {
+        self.require_semester(semester_id)?;
+        semester_targets(self.db, semester_id, target_grade)
+    }
+
+    // ========================================================================
+    // STUDY PLANS & AGENDA
+    // ========================================================================
+
+    pub fn get_study_plan(&self, tracker_id: &str) -> Result<Option<StudyPlan>, String> {
+        load_plan(self.db, tracker_id).map_err(|e| e.to_string())
+    }
+
+    /// Saves the daily capacity and plans every incomplete topic before its
+    /// test. From then on the plan rebalances as topics and tests change.
+    pub fn generate_study_plan(&self, tracker_id: &str, settings: PlanSettings) -> Result<StudyPlan, String> {
+        if settings.daily_capacity < 1 || settings.minutes_per_topic < 1 {
+            return Err("Daily capacity and minutes per topic must be at least 1".to_string());
+        }
+        if settings.rest_days.iter().collect::<HashSet<_>>().len() >= 7 {
+            return Err("At least one day of the week must be free to study".to_string());
+        }
+        self.require_tracker(tracker_id)?;
+
+        self.db.transaction(|db| {
+            db.set_plan_settings(tracker_id, &settings, self.now)?;
+            rebuild_plan(db, tracker_id, &settings, local_day(self.now), self.now)
+        })
+        .map_err(|e| e.to_string())?;
+
+        load_plan(self.db, tracker_id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "Study plan not found".to_string())
+    }
+
+    pub fn delete_study_plan(&self, tracker_id: &str) -> Result<(), String> {
+        self.db.delete_study_plan(tracker_id).map_err(|e| e.to_string())
+    }
+
+    /// Today's ranked agenda across every tracker in a semester, or across
+    /// all semesters when none is given.
+    pub fn get_today(&self, semester_id: Option<&str>) -> Result<Agenda, String> {
+        let semesters = self.db.get_all_semesters().map_err(|e| e.to_string())?;
+        if let Some(id) = semester_id {
+            if !semesters.iter().any(|s| s.id == id) {
+                return Err("Semester not found".to_string());
+            }
+        }
+
+        let mut trackers = Vec::new();
+        for semester in semesters.iter().filter(|s| semester_id.is_none_or(|id| s.id == id)) {
+            trackers.extend(self.db.get_trackers_by_semester(&semester.id).map_err(|e| e.to_string())?);
+        }
+        build_agenda(self.db, &trackers, local_day(self.now)).map_err(|e| e.to_string())
+    }
+
+    // ========================================================================
+    // DATABASE & SETTINGS
+    // ========================================================================
+
+    /// SQLite's integrity and foreign key checks plus the app's own invariants.
+    pub fn check_database(&self) -> Result<DatabaseReport, String> {
+        integrity::check_database(self.db).map_err(|e| e.to_string())
+    }
+
+    /// Fixes what `check_database` found, where that can be done safely.
+    pub fn repair_database(&self) -> Result<RepairReport, String> {
+        integrity::repair_database(self.db)
+    }
+
+    pub fn get_theme(&self) -> Result<String, String> {
+        self.db.get_theme_preference().map_err(|e| e.to_string())
+    }
+
+    pub fn set_theme(&self, theme: String) -> Result<String, String> {
+        self.db.set_theme_preference(&theme)
+            .map_err(|e| e.to_string())?;
+        Ok(theme)
+    }
+
+    // ========================================================================
+    // SYLLABUS IMPORT, EXPORT & EDITING
+    // ========================================================================
+
+    pub fn export_syllabus(&self, tracker_id: &str) -> Result<SyllabusExport, String> {
+        let tracker = self.require_tracker(tracker_id)?;
+        let subjects = get_tracker_tree(self.db, tracker_id)
+            .map_err(|e| e.to_string())?;
+        Ok(tracker_to_syllabus(&tracker, &subjects))
+    }
+
+    /// Renders a tracker as JSON, a Markdown checklist, CSV or a printable
+    /// HTML sheet. Unlike `export_syllabus`, the Markdown, CSV and HTML
+    /// formats include completion.
+    pub fn export_tracker(&self, tracker_id: &str, format: ExportFormat) -> Result<ExportedFile, String> {
+        let tracker = self.require_tracker(tracker_id)?;
+        let subjects = get_tracker_tree(self.db, tracker_id)
+            .map_err(|e| e.to_string())?;
+        Ok(render_tracker(&tracker, &subjects, format))
+    }
+
+    /// Creates a tracker with the whole tree of a syllabus document, after
+    /// validating all of it.
+    pub fn import_syllabus(&self, semester_id: String, syllabus: serde_json::Value) -> Result<Tracker, String> {
+        let syllabus = load_syllabus(syllabus).map_err(|issues| format_issues(&issues))?;
+        self.require_semester(&semester_id)?;
+        let db = self.db;
+        let now = self.now;
+
+        // Create tracker
+        let tracker_id = Uuid::new_v4().to_string();
+        db.create_tracker(
+            tracker_id.clone(),
+            semester_id,
+            syllabus.name,
+            syllabus.description,
+            syllabus.color,
+            now,
+        ).map_err(|e| e.to_string())?;
+
+        // Create subjects, units, and topics
+        for subject_data in syllabus.subjects {
+            let subject_id = Uuid::new_v4().to_string();
+            db.create_subject(subject_id.clone(), tracker_id.clone(), subject_data.name, now)
+                .map_err(|e| e.to_string())?;
+
+            for (unit_order, unit_data) in subject_data.units.iter().enumerate() {
+                let unit_id = Uuid::new_v4().to_string();
+                db.create_unit(
+                    unit_id.clone(),
+                    subject_id.clone(),
+                    unit_data.name.clone(),
+                    unit_order as i32,
+                    now,
+                ).map_err(|e| e.to_string())?;
+
+                for (topic_order, topic_name) in unit_data.topics.iter().enumerate() {
+                    let topic_id = Uuid::new_v4().to_string();
+                    db.create_topic(
+                        topic_id,
+                        unit_id.clone(),
+                        topic_name.clone(),
+                        topic_order as i32,
+                        now,
+                    ).map_err(|e| e.to_string())?;
+                }
+            }
+        }
+
+        // Re-read so the counts kept by the triggers are included
+        db.get_tracker(&tracker_id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "Failed to retrieve created tracker".to_string())
+    }
+
+    pub fn preview_merge(&self, tracker_id: &str, syllabus: serde_json::Value) -> Result<MergePlan, String> {
+        let syllabus = load_syllabus(syllabus).map_err(|issues| format_issues(&issues))?;
+        self.require_tracker(tracker_id)?;
+
+        let existing = get_tracker_tree(self.db, tracker_id)
+            .map_err(|e| e.to_string())?;
+        Ok(plan_merge(tracker_id, existing, syllabus))
+    }
+
+    /// Applies the merge previewed with `fingerprint`. If the tracker has
+    /// changed since, the plan would differ from the one the user approved,
+    /// so nothing is written.
+    pub fn merge_syllabus(
+        &self,
+        tracker_id: &str,
+        syllabus: serde_json::Value,
+        fingerprint: &str,
+        delete_removed: bool,
+    ) -> Result<Tracker, String> {
+        let plan = self.preview_merge(tracker_id, syllabus)?;
+        require_fingerprint(&plan, fingerprint)?;
+        apply_merge(self.db, &plan, delete_removed, self.now)
+            .map_err(|e| e.to_string())?;
+        refresh_plan(self.db, tracker_id, self.now).map_err(|e| e.to_string())?;
+
+        self.db.get_tracker(tracker_id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "Failed to retrieve merged tracker".to_string())
+    }
+
+    pub fn get_tracker_as_text(&self, tracker_id: &str) -> Result<String, String> {
+        Ok(tracker_text(self.get_tracker_tree(tracker_id)?))
+    }
+
+    pub fn preview_tracker_text(&self, tracker_id: &str, text: &str) -> Result<MergePlan, String> {
+        let existing = self.get_tracker_tree(tracker_id)?;
+        plan_text_edit(tracker_id, existing, text).map_err(|errors| format_parse_errors(&errors))
+    }
+
+    /// Replaces the tracker's outline with `text`, keeping the IDs and
+    /// completion of matched lines and deleting lines that were removed.
+    /// Like `merge_syllabus`, it only applies the plan previewed with
+    /// `fingerprint`.
+    pub fn apply_tracker_text(&self, tracker_id: &str, text: &str, fingerprint: &str) -> Result<MergeSummary, String> {
+        let plan = self.preview_tracker_text(tracker_id, text)?;
+        require_fingerprint(&plan, fingerprint)?;
+        let summary = apply_merge(self.db, &plan, true, self.now)
+            .map_err(|e| e.to_string())?;
+        refresh_plan(self.db, tracker_id, self.now).map_err(|e| e.to_string())?;
+        Ok(summary)
+    }
+
+    pub fn diff_trackers(&self, old_tracker_id: &str, new_tracker_id: &str) -> Result<SyllabusDiff, String> {
+        let old = self.export_syllabus(old_tracker_id)?;
+        let new = self.export_syllabus(new_tracker_id)?;
+        Ok(diff_syllabi(&old, &new))
+    }
+
+    pub fn diff_tracker_with_syllabus(&self, tracker_id: &str, syllabus: serde_json::Value) -> Result<SyllabusDiff, String> {
+        let new = parse_syllabus_value(syllabus).map_err(|issues| format_issues(&issues))?;
+        let old = self.export_syllabus(tracker_id)?;
+        Ok(diff_syllabi(&old, &new))
+    }
+}
+
+/// Trims the name and rejects values the priority window and study plans
+/// can't work with.
+fn validate_test_type(name: &str, priority_window_days: i32, weight: f64) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Test type name cannot be empty".to_string());
+    }
+    if priority_window_days < 0 {
+        return Err("Priority window cannot be negative".to_string());
+    }
+    if !weight.is_finite() || weight < 0.0 {
+        return Err("Weight must be a non-negative number".to_string());
+    }
+    Ok(name.to_string())
+}
+
+/// Rejects a plan made against a different tree than the previewed one.
+fn require_fingerprint(plan: &MergePlan, fingerprint: &str) -> Result<(), String> {
+    if plan.fingerprint != fingerprint {
+        return Err("The tracker has changed since this preview; preview the changes again".to_string());
+    }
+    Ok(())
+}
+
+fn create_test_with_coverage(
+    db: &Database,
+    tracker_id: String,
+    name: String,
+    test_type: String,
+    scheduled_date: i64,
+    coverage: Vec<TestCoverageInput>,
+    now: i64,
+) -> rusqlite::Result<Test> {
+    let test_id = Uuid::new_v4().to_string();
+    let mut test = db.create_test(test_id.clone(), tracker_id, name, test_type, scheduled_date, now)?;
+
+    // Create coverage entries
+    for cov_input in coverage {
+        let cov_id = Uuid::new_v4().to_string();
+        db.create_test_coverage(cov_id, test_id.clone(), cov_input.unit_id, cov_input.topic_id)?;
+    }
+
+    // Coverage within one subject says whose marks these are
+    db.assign_test_subjects_from_coverage(Some(&test_id))?;
+    test.subject_id = db.get_test(&test_id)?.and_then(|t| t.subject_id);
+    Ok(test)
+}
+
+// ============================================================================
+// HELPER STRUCTS (for API responses)
+// ============================================================================
+
+#[derive(Serialize)]
+pub struct TrackerData {
+    pub subjects: Vec<SubjectData>,
+    pub progress: TrackerProgress,
+    pub all_tests: Vec<Test>,
+    pub priority_tests: Vec<PriorityTest>,
+    pub test_types: Vec<TestType>,
+    pub all_assignments: Vec<Assignment>,
+    pub priority_assignments: Vec<PriorityAssignment>,
+}
+
+#[derive(Serialize)]
+pub struct TestDetails {
+    pub test: Test,
+    pub coverage: Vec<TestCoverage>,
+    pub covered_topics: Vec<String>,
+    pub days_remaining: i32,
+    pub time_remaining: String,
+}
+
+#[derive(Serialize)]
+pub struct AssignmentDetails {
+    pub assignment: Assignment,
+    pub topic_ids: Vec<String>,
+    pub linked_topics: Vec<String>,
+    pub days_remaining: i32,
+    pub time_remaining: String,
+}
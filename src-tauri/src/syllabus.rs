@@ -0,0 +1,540 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::fmt;
+use crate::models::{SubjectData, Tracker};
+use crate::importers::{parse_syllabus_input, SyllabusFormat};
+use crate::utils::ParsedSyllabusEntry;
+
+// ============================================================================
+// SYLLABUS SCHEMA
+// ============================================================================
+
+pub const SCHEMA_MAJOR: u32 = 1;
+pub const SCHEMA_MINOR: u32 = V1_UPGRADES.len() as u32;
+
+/// Upgrade steps for 1.x documents; entry `n` turns a 1.n file into 1.(n+1).
+/// 1.0 is the first published format, so the list is still empty.
+const V1_UPGRADES: &[fn(&mut Value)] = &[];
+
+pub const MAX_NAME_LENGTH: usize = 200;
+pub const MAX_DESCRIPTION_LENGTH: usize = 2000;
+pub const MAX_SUBJECTS: usize = 100;
+pub const MAX_UNITS_PER_SUBJECT: usize = 100;
+pub const MAX_TOPICS_PER_UNIT: usize = 500;
+pub const MAX_TOTAL_TOPICS: usize = 10_000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyllabusExport {
+    pub name: String,
+    pub description: Option<String>,
+    pub color: Option<String>,
+    pub version: String,
+    pub subjects: Vec<SyllabusSubject>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyllabusSubject {
+    pub name: String,
+    pub units: Vec<SyllabusUnit>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyllabusUnit {
+    pub name: String,
+    pub topics: Vec<String>,
+}
+
+pub fn current_version() -> String {
+    format!("{}.{}", SCHEMA_MAJOR, SCHEMA_MINOR)
+}
+
+/// Strips a loaded tracker tree down to names.
+pub fn tree_to_subjects(subjects: &[SubjectData]) -> Vec<SyllabusSubject> {
+    subjects
+        .iter()
+        .map(|s| SyllabusSubject {
+            name: s.subject.name.clone(),
+            units: s
+                .units
+                .iter()
+                .map(|u| SyllabusUnit {
+                    name: u.unit.name.clone(),
+                    topics: u.topics.iter().map(|t| t.name.clone()).collect(),
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+/// Builds the shareable syllabus of a tracker from its loaded tree.
+pub fn tracker_to_syllabus(tracker: &Tracker, subjects: &[SubjectData]) -> SyllabusExport {
+    SyllabusExport {
+        name: tracker.name.clone(),
+        description: tracker.description.clone(),
+        color: tracker.color.clone(),
+        version: current_version(),
+        subjects: tree_to_subjects(subjects),
+    }
+}
+
+// ============================================================================
+// VALIDATION ISSUES
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ValidationIssue {
+    pub path: String,
+    pub message: String,
+}
+
+impl ValidationIssue {
+    fn new(path: impl Into<String>, message: impl Into<String>) -> Self {
+        ValidationIssue {
+            path: path.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+pub fn format_issues(issues: &[ValidationIssue]) -> String {
+    let lines: Vec<String> = issues.iter().map(|i| i.to_string()).collect();
+    format!("Invalid syllabus:\n{}", lines.join("\n"))
+}
+
+// ============================================================================
+// VERSION DISPATCH
+// ============================================================================
+
+fn parse_version(version: &str) -> Option<(u32, u32)> {
+    let mut parts = version.trim().split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = match parts.next() {
+        Some(m) => m.parse().ok()?,
+        None => 0,
+    };
+    if parts.next().is_some() {
+        return None;
+    }
+    Some((major, minor))
+}
+
+/// Brings a 1.x document up to the current minor version in place.
+fn upgrade_v1(value: &mut Value, from_minor: u32) {
+    for step in V1_UPGRADES.iter().skip(from_minor as usize) {
+        step(value);
+    }
+
+    if let Some(obj) = value.as_object_mut() {
+        obj.insert("version".to_string(), Value::String(current_version()));
+    }
+}
+
+/// Parses a raw syllabus document, dispatching on its `version` field.
+/// Unknown major versions are rejected, older minors are upgraded and
+/// newer minors are read as-is (minor bumps only add optional fields).
+/// The content itself is not validated; see [`load_syllabus`].
+pub fn parse_syllabus_value(mut value: Value) -> Result<SyllabusExport, Vec<ValidationIssue>> {
+    if !value.is_object() {
+        return Err(vec![ValidationIssue::new("$", "expected a JSON object")]);
+    }
+
+    let version = match value.get("version") {
+        Some(Value::String(v)) => v.clone(),
+        Some(_) => {
+            return Err(vec![ValidationIssue::new("$.version", "must be a string such as \"1.0\"")]);
+        }
+        None => {
+            return Err(vec![ValidationIssue::new("$.version", "is missing")]);
+        }
+    };
+
+    let (major, minor) = parse_version(&version).ok_or_else(|| {
+        vec![ValidationIssue::new(
+            "$.version",
+            format!("'{}' is not a valid version, expected MAJOR.MINOR", version),
+        )]
+    })?;
+
+    match major {
+        SCHEMA_MAJOR => upgrade_v1(&mut value, minor),
+        _ => {
+            return Err(vec![ValidationIssue::new(
+                "$.version",
+                format!(
+                    "unsupported major version {} (this app reads {}.x files)",
+                    major, SCHEMA_MAJOR
+                ),
+            )]);
+        }
+    }
+
+    serde_json::from_value(value).map_err(|e| vec![ValidationIssue::new("$", e.to_string())])
+}
+
+/// Parses and validates a raw syllabus document.
+pub fn load_syllabus(value: Value) -> Result<SyllabusExport, Vec<ValidationIssue>> {
+    let syllabus = parse_syllabus_value(value)?;
+    validate_syllabus(&syllabus)?;
+    Ok(syllabus)
+}
+
+// ============================================================================
+// CONTENT VALIDATION
+// ============================================================================
+
+fn check_name(issues: &mut Vec<ValidationIssue>, path: &str, name: &str) {
+    if name.trim().is_empty() {
+        issues.push(ValidationIssue::new(path, "must not be empty"));
+    } else if name.chars().count() > MAX_NAME_LENGTH {
+        issues.push(ValidationIssue::new(
+            path,
+            format!("must be at most {} characters", MAX_NAME_LENGTH),
+        ));
+    }
+}
+
+/// Records a duplicate when `name` (trimmed, case-insensitive) was already seen.
+fn check_duplicate(issues: &mut Vec<ValidationIssue>, seen: &mut HashSet<String>, path: &str, name: &str) {
+    let key = name.trim().to_lowercase();
+    if key.is_empty() {
+        return;
+    }
+    if !seen.insert(key) {
+        issues.push(ValidationIssue::new(path, format!("duplicate name '{}'", name.trim())));
+    }
+}
+
+pub fn is_valid_color(color: &str) -> bool {
+    let hex = match color.strip_prefix('#') {
+        Some(h) => h,
+        None => return false,
+    };
+    (hex.len() == 3 || hex.len() == 6) && hex.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Checks a syllabus against the content rules and returns every problem found.
+pub fn validate_syllabus(syllabus: &SyllabusExport) -> Result<(), Vec<ValidationIssue>> {
+    let mut issues = Vec::new();
+
+    check_name(&mut issues, "$.name", &syllabus.name);
+
+    if let Some(description) = &syllabus.description {
+        if description.chars().count() > MAX_DESCRIPTION_LENGTH {
+            issues.push(ValidationIssue::new(
+                "$.description",
+                format!("must be at most {} characters", MAX_DESCRIPTION_LENGTH),
+            ));
+        }
+    }
+
+    if let Some(color) = &syllabus.color {
+        if !is_valid_color(color) {
+            issues.push(ValidationIssue::new(
+                "$.color",
+                format!("'{}' is not a hex color like #3b82f6", color),
+            ));
+        }
+    }
+
+    if syllabus.subjects.is_empty() {
+        issues.push(ValidationIssue::new("$.subjects", "must contain at least one subject"));
+    } else if syllabus.subjects.len() > MAX_SUBJECTS {
+        issues.push(ValidationIssue::new(
+            "$.subjects",
+            format!("must contain at most {} subjects", MAX_SUBJECTS),
+        ));
+    }
+
+    let mut total_topics = 0;
+    let mut subject_names = HashSet::new();
+
+    for (s_idx, subject) in syllabus.subjects.iter().enumerate() {
+        let subject_path = format!("$.subjects[{}]", s_idx);
+        let name_path = format!("{}.name", subject_path);
+        check_name(&mut issues, &name_path, &subject.name);
+        check_duplicate(&mut issues, &mut subject_names, &name_path, &subject.name);
+
+        if subject.units.len() > MAX_UNITS_PER_SUBJECT {
+            issues.push(ValidationIssue::new(
+                format!("{}.units", subject_path),
+                format!("must contain at most {} units", MAX_UNITS_PER_SUBJECT),
+            ));
+        }
+
+        let mut unit_names = HashSet::new();
+        for (u_idx, unit) in subject.units.iter().enumerate() {
+            let unit_path = format!("{}.units[{}]", subject_path, u_idx);
+            let name_path = format!("{}.name", unit_path);
+            check_name(&mut issues, &name_path, &unit.name);
+            check_duplicate(&mut issues, &mut unit_names, &name_path, &unit.name);
+
+            if unit.topics.len() > MAX_TOPICS_PER_UNIT {
+                issues.push(ValidationIssue::new(
+                    format!("{}.topics", unit_path),
+                    format!("must contain at most {} topics", MAX_TOPICS_PER_UNIT),
+                ));
+            }
+            total_topics += unit.topics.len();
+
+            let mut topic_names = HashSet::new();
+            for (t_idx, topic) in unit.topics.iter().enumerate() {
+                let topic_path = format!("{}.topics[{}]", unit_path, t_idx);
+                check_name(&mut issues, &topic_path, topic);
+                check_duplicate(&mut issues, &mut topic_names, &topic_path, topic);
+            }
+        }
+    }
+
+    if total_topics > MAX_TOTAL_TOPICS {
+        issues.push(ValidationIssue::new(
+            "$.subjects",
+            format!("must contain at most {} topics in total (found {})", MAX_TOTAL_TOPICS, total_topics),
+        ));
+    }
+
+    if issues.is_empty() {
+        Ok(())
+    } else {
+        Err(issues)
+    }
+}
+
+// ============================================================================
+// IMPORT PREVIEW
+// ============================================================================
+
+/// Input accepted by the import preview: syllabus text (as used by
+/// `create_new_tracker`) or a JSON syllabus file (as used by `import_syllabus`).
+/// `Auto` detects the text format; the other text variants force one, as
+/// the same `format` does for `create_new_tracker`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "format", rename_all = "snake_case")]
+pub enum ImportSource {
+    Auto { text: String },
+    Text { text: String },
+    Markdown { text: String },
+    Csv { text: String },
+    Json { syllabus: Value },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportPreview {
+    pub name: Option<String>,
+    pub subject_count: usize,
+    pub unit_count: usize,
+    pub topic_count: usize,
+    /// Problems that will make the import fail.
+    pub errors: Vec<ValidationIssue>,
+    /// Suspicious content that will still be imported.
+    pub warnings: Vec<ValidationIssue>,
+    pub subjects: Vec<SyllabusSubject>,
+}
+
+impl From<&ParsedSyllabusEntry> for SyllabusSubject {
+    fn from(entry: &ParsedSyllabusEntry) -> Self {
+        SyllabusSubject {
+            name: entry.subject_name.clone(),
+            units: entry
+                .units
+                .iter()
+                .map(|u| SyllabusUnit {
+                    name: u.unit_name.clone(),
+                    topics: u.topics.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Flags content that is valid but probably not what the author meant.
+pub fn collect_warnings(subjects: &[SyllabusSubject]) -> Vec<ValidationIssue> {
+    let mut warnings = Vec::new();
+    let mut subject_names = HashSet::new();
+
+    for (s_idx, subject) in subjects.iter().enumerate() {
+        let subject_path = format!("$.subjects[{}]", s_idx);
+        if !subject_names.insert(subject.name.trim().to_lowercase()) {
+            warnings.push(ValidationIssue::new(
+                format!("{}.name", subject_path),
+                format!("entries for '{}' will be merged", subject.name.trim()),
+            ));
+        }
+        if subject.units.is_empty() {
+            warnings.push(ValidationIssue::new(
+                format!("{}.units", subject_path),
+                "subject has no units",
+            ));
+        }
+
+        let mut unit_names = HashSet::new();
+        let mut topic_names = HashSet::new();
+        for (u_idx, unit) in subject.units.iter().enumerate() {
+            let unit_path = format!("{}.units[{}]", subject_path, u_idx);
+            if !unit_names.insert(unit.name.trim().to_lowercase()) {
+                warnings.push(ValidationIssue::new(
+                    format!("{}.name", unit_path),
+                    format!("unit '{}' appears more than once in '{}'", unit.name.trim(), subject.name.trim()),
+                ));
+            }
+            if unit.topics.is_empty() {
+                warnings.push(ValidationIssue::new(
+                    format!("{}.topics", unit_path),
+                    "unit has no topics",
+                ));
+            }
+            for (t_idx, topic) in unit.topics.iter().enumerate() {
+                if !topic_names.insert(topic.trim().to_lowercase()) {
+                    warnings.push(ValidationIssue::new(
+                        format!("{}.topics[{}]", unit_path, t_idx),
+                        format!("topic '{}' appears more than once in '{}'", topic.trim(), subject.name.trim()),
+                    ));
+                }
+            }
+        }
+    }
+
+    warnings
+}
+
+fn build_preview(name: Option<String>, subjects: Vec<SyllabusSubject>, errors: Vec<ValidationIssue>) -> ImportPreview {
+    // Anything already reported as an error doesn't need a warning too
+    let warnings = collect_warnings(&subjects)
+        .into_iter()
+        .filter(|w| !errors.iter().any(|e| e.path == w.path))
+        .collect();
+
+    ImportPreview {
+        name,
+        subject_count: subjects.len(),
+        unit_count: subjects.iter().map(|s| s.units.len()).sum(),
+        topic_count: subjects
+            .iter()
+            .flat_map(|s| s.units.iter())
+            .map(|u| u.topics.len())
+            .sum(),
+        errors,
+        warnings,
+        subjects,
+    }
+}
+
+/// Parses an import source without writing anything and summarizes what
+/// would be created.
+pub fn preview_import(source: ImportSource) -> ImportPreview {
+    match source {
+        ImportSource::Auto { text } => preview_text(&text, None),
+        ImportSource::Text { text } => preview_text(&text, Some(SyllabusFormat::Text)),
+        ImportSource::Markdown { text } => preview_text(&text, Some(SyllabusFormat::Markdown)),
+        ImportSource::Csv { text } => preview_text(&text, Some(SyllabusFormat::Csv)),
+        ImportSource::Json { syllabus } => match parse_syllabus_value(syllabus) {
+            Ok(syllabus) => {
+                let errors = validate_syllabus(&syllabus).err().unwrap_or_default();
+                build_preview(Some(syllabus.name), syllabus.subjects, errors)
+            }
+            Err(errors) => build_preview(None, Vec::new(), errors),
+        },
+    }
+}
+
+fn preview_text(text: &str, format: Option<SyllabusFormat>) -> ImportPreview {
+    match parse_syllabus_input(text, format) {
+        Ok(entries) => build_preview(None, entries.iter().map(SyllabusSubject::from).collect(), Vec::new()),
+        Err(errors) => {
+            let issues = errors
+                .into_iter()
+                .map(|e| ValidationIssue::new(format!("line {}, column {}", e.line, e.column), e.message))
+                .collect();
+            build_preview(None, Vec::new(), issues)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn sample() -> Value {
+        json!({
+            "name": "Semester 4",
+            "description": null,
+            "color": "#3b82f6",
+            "version": "1.0",
+            "subjects": [
+                {
+                    "name": "Operating Systems",
+                    "units": [
+                        { "name": "Processes", "topics": ["Threads", "Scheduling"] }
+                    ]
+                }
+            ]
+        })
+    }
+
+    #[test]
+    fn test_load_valid_syllabus() {
+        let syllabus = load_syllabus(sample()).unwrap();
+        assert_eq!(syllabus.version, "1.0");
+        assert_eq!(syllabus.subjects[0].units[0].topics.len(), 2);
+    }
+
+    #[test]
+    fn test_rejects_unknown_major_version() {
+        let mut value = sample();
+        value["version"] = json!("2.0");
+        let issues = load_syllabus(value).unwrap_err();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].path, "$.version");
+    }
+
+    #[test]
+    fn test_reports_every_issue_with_path() {
+        let mut value = sample();
+        value["color"] = json!("blue");
+        value["subjects"] = json!([
+            { "name": "OS", "units": [{ "name": "", "topics": ["a", " ", "A"] }] },
+            { "name": "os ", "units": [] }
+        ]);
+        let issues = load_syllabus(value).unwrap_err();
+        let paths: Vec<&str> = issues.iter().map(|i| i.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "$.color",
+                "$.subjects[0].units[0].name",
+                "$.subjects[0].units[0].topics[1]",
+                "$.subjects[0].units[0].topics[2]",
+                "$.subjects[1].name",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_preview_text_counts_and_warnings() {
+        let preview = preview_import(ImportSource::Text {
+            text: "Maths >>> Algebra >>> groups, rings\nMaths >>> Geometry >>> Rings\nMaths >>> Calculus".to_string(),
+        });
+        assert!(preview.errors.is_empty());
+        assert_eq!((preview.subject_count, preview.unit_count, preview.topic_count), (1, 3, 3));
+        let paths: Vec<&str> = preview.warnings.iter().map(|w| w.path.as_str()).collect();
+        assert_eq!(paths, vec!["$.subjects[0].units[1].topics[0]", "$.subjects[0].units[2].topics"]);
+
+        let repeated = collect_warnings(&[preview.subjects[0].clone(), preview.subjects[0].clone()]);
+        assert_eq!(repeated[2].message, "entries for 'Maths' will be merged");
+
+        // Only `Auto` detects the format; `Text` reads the plain text format
+        let markdown = "# Maths\n## Algebra\n- Groups";
+        let auto = preview_import(ImportSource::Auto { text: markdown.to_string() });
+        assert!(auto.errors.is_empty());
+        assert_eq!(auto.subjects[0].name, "Maths");
+        let text = preview_import(ImportSource::Text { text: markdown.to_string() });
+        assert!(!text.subjects.iter().any(|s| s.name == "Maths"));
+    }
+}
@@ -1,99 +1,169 @@
 use crate::models::*;
 use crate::db::Database;
+use chrono::{DateTime, Local, NaiveDate, Utc};
 use rusqlite::Result as SqliteResult;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt;
 
 // ============================================================================
-// PROGRESS CALCULATIONS
+// TRACKER TREE
 // ============================================================================
 
-pub fn calculate_tracker_progress(
-    db: &Database,
-    tracker_id: &str,
-) -> SqliteResult<TrackerProgress> {
+/// Loads a tracker's subjects, units and topics with one query each and
+/// nests them in memory, so the cost doesn't grow with the number of units.
+pub fn get_tracker_tree(db: &Database, tracker_id: &str) -> SqliteResult<Vec<SubjectData>> {
     let subjects = db.get_subjects_by_tracker(tracker_id)?;
-    
+
+    let mut topics_by_unit: HashMap<String, Vec<Topic>> = HashMap::new();
+    for topic in db.get_topics_by_tracker(tracker_id)? {
+        topics_by_unit.entry(topic.unit_id.clone()).or_default().push(topic);
+    }
+
+    let mut units_by_subject: HashMap<String, Vec<UnitData>> = HashMap::new();
+    for unit in db.get_units_by_tracker(tracker_id)? {
+        let mut topics = topics_by_unit.remove(&unit.id).unwrap_or_default();
+        topics.sort_by_key(|t| t.order);
+        units_by_subject
+            .entry(unit.subject_id.clone())
+            .or_default()
+            .push(UnitData { unit, topics });
+    }
+
+    // Sorting each small group here is cheaper than an ORDER BY over the
+    // whole tracker
+    Ok(subjects
+        .into_iter()
+        .map(|subject| {
+            let mut units = units_by_subject.remove(&subject.id).unwrap_or_default();
+            units.sort_by_key(|u| u.unit.order);
+            SubjectData { subject, units }
+        })
+        .collect())
+}
+
+// ============================================================================
+// PROGRESS CALCULATIONS
+// ============================================================================
+
+fn percentage(completed: i32, total: i32) -> f64 {
+    if total > 0 {
+        (completed as f64 / total as f64) * 100.0
+    } else {
+        0.0
+    }
+}
+
+/// Progress of a tracker tree loaded with `get_tracker_tree`.
+pub fn calculate_tracker_progress(tracker_id: &str, tree: &[SubjectData]) -> TrackerProgress {
     let mut total_topics = 0;
     let mut completed_topics = 0;
     let mut subjects_progress = Vec::new();
 
-    for subject in subjects {
-        let units = db.get_units_by_subject(&subject.id)?;
-        
+    for subject_data in tree {
         let mut subject_total = 0;
         let mut subject_completed = 0;
         let mut units_progress = Vec::new();
 
-        for unit in units {
-            let topics = db.get_topics_by_unit(&unit.id)?;
-            
-            let unit_total = topics.len() as i32;
-            let unit_completed = topics.iter().filter(|t| t.completed).count() as i32;
+        for unit_data in &subject_data.units {
+            let unit_total = unit_data.topics.len() as i32;
+            let unit_completed = unit_data.topics.iter().filter(|t| t.completed).count() as i32;
 
             subject_total += unit_total;
             subject_completed += unit_completed;
-            total_topics += unit_total;
-            completed_topics += unit_completed;
 
             units_progress.push(UnitProgress {
-                unit_id: unit.id.clone(),
-                unit_name: unit.name.clone(),
+                unit_id: unit_data.unit.id.clone(),
+                unit_name: unit_data.unit.name.clone(),
                 total_topics: unit_total,
                 completed_topics: unit_completed,
-                percentage: if unit_total > 0 {
-                    (unit_completed as f64 / unit_total as f64) * 100.0
-                } else {
-                    0.0
-                },
+                percentage: percentage(unit_completed, unit_total),
             });
         }
 
+        total_topics += subject_total;
+        completed_topics += subject_completed;
+
         subjects_progress.push(SubjectProgress {
-            subject_id: subject.id.clone(),
-            subject_name: subject.name.clone(),
+            subject_id: subject_data.subject.id.clone(),
+            subject_name: subject_data.subject.name.clone(),
             total_topics: subject_total,
             completed_topics: subject_completed,
-            percentage: if subject_total > 0 {
-                (subject_completed as f64 / subject_total as f64) * 100.0
-            } else {
-                0.0
-            },
+            percentage: percentage(subject_completed, subject_total),
             units: units_progress,
         });
     }
 
-    Ok(TrackerProgress {
+    TrackerProgress {
         tracker_id: tracker_id.to_string(),
         total_topics,
         completed_topics,
-        percentage: if total_topics > 0 {
-            (completed_topics as f64 / total_topics as f64) * 100.0
-        } else {
-            0.0
-        },
+        percentage: percentage(completed_topics, total_topics),
         subjects: subjects_progress,
+    }
+}
+
+/// Progress of every tracker in a semester and the tests still ahead, from
+/// two aggregate queries rather than a tree walk per tracker.
+pub fn semester_overview(db: &Database, semester_id: &str, now: i64) -> SqliteResult<SemesterOverview> {
+    let trackers = db.get_tracker_summaries(semester_id)?;
+    let total_topics: i32 = trackers.iter().map(|t| t.total_topics).sum();
+    let completed_topics: i32 = trackers.iter().map(|t| t.completed_topics).sum();
+
+    let upcoming_tests = db
+        .get_upcoming_tests_by_semester(semester_id, now)?
+        .into_iter()
+        .map(|(test, tracker_name)| UpcomingTest {
+            days_remaining: get_days_remaining(test.scheduled_date, now),
+            time_remaining: format_time_remaining(test.scheduled_date, now),
+            test,
+            tracker_name,
+        })
+        .collect();
+
+    Ok(SemesterOverview {
+        semester_id: semester_id.to_string(),
+        total_topics,
+        completed_topics,
+        percentage: percentage(completed_topics, total_topics),
+        trackers,
+        upcoming_tests,
     })
 }
 
+// ============================================================================
+// TEST COVERAGE
+// ============================================================================
+
+/// Names of the topics a test covers: every topic of a covered unit, plus
+/// individually covered topics.
+pub fn get_covered_topic_names(db: &Database, coverage: &[TestCoverage]) -> SqliteResult<Vec<String>> {
+    let mut covered_topics = Vec::new();
+    for cov in coverage.iter() {
+        if let Some(unit_id) = &cov.unit_id {
+            // Entire unit covered
+            let unit_topics = db.get_topics_by_unit(unit_id)?;
+            covered_topics.extend(unit_topics.iter().map(|t| t.name.clone()));
+        } else if let Some(topic_id) = &cov.topic_id {
+            // Specific topic covered
+            if let Some(topic) = db.get_topic(topic_id)? {
+                covered_topics.push(topic.name);
+            }
+        }
+    }
+    Ok(covered_topics)
+}
+
 // ============================================================================
 // TIME CALCULATIONS
 // ============================================================================
 
-pub fn get_days_remaining(scheduled_date: i64) -> i32 {
-    let now = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap()
-        .as_millis() as i64;
-    
+pub fn get_days_remaining(scheduled_date: i64, now: i64) -> i32 {
     let diff_ms = scheduled_date - now;
     (diff_ms as f64 / (86400000.0)).ceil() as i32
 }
 
-pub fn format_time_remaining(scheduled_date: i64) -> String {
-    let now = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap()
-        .as_millis() as i64;
-    
+pub fn format_time_remaining(scheduled_date: i64, now: i64) -> String {
     let diff_ms = scheduled_date - now;
     
     if diff_ms <= 0 {
@@ -107,92 +177,419 @@ pub fn format_time_remaining(scheduled_date: i64) -> String {
     format!("{}d {}h {}m", days, hours, minutes)
 }
 
-pub fn is_within_priority_window(scheduled_date: i64) -> bool {
-    let days = get_days_remaining(scheduled_date);
-    days > 0 && days <= 7
+/// The local calendar day a timestamp falls on.
+pub fn local_day(timestamp: i64) -> NaiveDate {
+    DateTime::<Utc>::from_timestamp_millis(timestamp)
+        .unwrap_or_default()
+        .with_timezone(&Local)
+        .date_naive()
+}
+
+pub fn is_within_priority_window(scheduled_date: i64, window_days: i32, now: i64) -> bool {
+    let days = get_days_remaining(scheduled_date, now);
+    days > 0 && days <= window_days
 }
 
 // ============================================================================
 // SYLLABUS PARSING
 // ============================================================================
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ParsedSyllabusEntry {
     pub subject_name: String,
     pub units: Vec<ParsedUnit>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ParsedUnit {
     pub unit_name: String,
     pub topics: Vec<String>,
 }
 
-pub fn parse_syllabus(input: &str) -> Result<Vec<ParsedSyllabusEntry>, String> {
-    let mut entries = Vec::new();
-    let mut current_subject: Option<String> = None;
-    let mut current_units: Vec<ParsedUnit> = Vec::new();
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SyllabusParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for SyllabusParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}: {}", self.line, self.column, self.message)
+    }
+}
+
+pub fn format_parse_errors(errors: &[SyllabusParseError]) -> String {
+    let lines: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+    format!("Invalid syllabus text:\n{}", lines.join("\n"))
+}
+
+pub fn normalize_name(name: &str) -> String {
+    name.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Part of a line, trimmed, with escapes still in place and the 1-based
+/// column of its first non-blank character.
+struct Piece {
+    text: String,
+    column: usize,
+}
+
+/// Splits `chars` on every occurrence of `sep` that isn't preceded by a `\`.
+fn split_unescaped(chars: &[char], first_column: usize, sep: &str) -> Vec<Piece> {
+    let sep: Vec<char> = sep.chars().collect();
+    let mut pieces = Vec::new();
+    let mut start = 0;
+    let mut i = 0;
+
+    let mut push = |from: usize, to: usize| {
+        let raw = &chars[from..to];
+        // An empty piece points just past the separator before it
+        let leading = raw.iter().position(|c| !c.is_whitespace()).unwrap_or(0);
+        let text: String = raw.iter().collect();
+        pieces.push(Piece {
+            text: text.trim().to_string(),
+            column: first_column + from + leading,
+        });
+    };
+
+    while i < chars.len() {
+        if chars[i] == '\\' {
+            i += 2;
+        } else if chars[i..].starts_with(&sep) {
+            push(start, i);
+            i += sep.len();
+            start = i;
+        } else {
+            i += 1;
+        }
+    }
+    push(start, chars.len().max(start));
 
-    for line in input.lines() {
-        let trimmed = line.trim();
-        if trimmed.is_empty() {
+    pieces
+}
+
+/// Resolves `\x` escapes to `x`. A trailing lone `\` is an error.
+fn unescape(piece: &Piece, line: usize) -> Result<String, SyllabusParseError> {
+    let mut out = String::new();
+    let mut chars = piece.text.chars().enumerate();
+    while let Some((idx, c)) = chars.next() {
+        if c != '\\' {
+            out.push(c);
             continue;
         }
+        match chars.next() {
+            Some((_, escaped)) => out.push(escaped),
+            None => {
+                return Err(SyllabusParseError {
+                    line,
+                    column: piece.column + idx,
+                    message: "'\\' must be followed by the character it escapes".to_string(),
+                });
+            }
+        }
+    }
+    Ok(out)
+}
 
-        // Expected format: "Subject Name >>> Unit Name >>> topic1, topic2, topic3"
-        let parts: Vec<&str> = trimmed.split(">>>").map(|p| p.trim()).collect();
+fn indent_width(line: &str) -> usize {
+    line.chars()
+        .take_while(|c| c.is_whitespace())
+        .map(|c| if c == '\t' { 4 } else { 1 })
+        .sum()
+}
+
+/// Collects parsed lines, merging repeated subjects and units into the
+/// first occurrence so a subject's lines don't have to be contiguous.
+#[derive(Default)]
+pub(crate) struct SyllabusBuilder {
+    entries: Vec<ParsedSyllabusEntry>,
+}
+
+impl SyllabusBuilder {
+    pub(crate) fn subject(&mut self, name: String) -> usize {
+        let key = normalize_name(&name);
+        if let Some(idx) = self.entries.iter().position(|e| normalize_name(&e.subject_name) == key) {
+            return idx;
+        }
+        self.entries.push(ParsedSyllabusEntry {
+            subject_name: name,
+            units: Vec::new(),
+        });
+        self.entries.len() - 1
+    }
 
-        if parts.len() < 2 {
-            return Err("Invalid format. Expected: 'Subject Name >>> Unit Name >>> topic1, topic2, topic3'".to_string());
+    pub(crate) fn unit(&mut self, subject: usize, name: String) -> usize {
+        let units = &mut self.entries[subject].units;
+        let key = normalize_name(&name);
+        if let Some(idx) = units.iter().position(|u| normalize_name(&u.unit_name) == key) {
+            return idx;
         }
+        units.push(ParsedUnit {
+            unit_name: name,
+            topics: Vec::new(),
+        });
+        units.len() - 1
+    }
 
-        let subject_name = parts[0].to_string();
-        let unit_name = parts[1].to_string();
+    pub(crate) fn topic(&mut self, subject: usize, unit: usize, name: String) {
+        self.entries[subject].units[unit].topics.push(name);
+    }
 
-        // Parse topics (optional, default to empty)
-        let topics_str = if parts.len() > 2 { parts[2] } else { "" };
-        let topics: Vec<String> = topics_str
-            .split(',')
-            .map(|t| t.trim().to_string())
-            .filter(|t| !t.is_empty())
-            .collect();
+    /// Returns the entries, or all errors sorted by position. Subjects
+    /// without units are kept; the import preview warns about them.
+    pub(crate) fn finish(
+        self,
+        mut errors: Vec<SyllabusParseError>,
+    ) -> Result<Vec<ParsedSyllabusEntry>, Vec<SyllabusParseError>> {
+        if self.entries.is_empty() && errors.is_empty() {
+            errors.push(SyllabusParseError {
+                line: 1,
+                column: 1,
+                message: "No valid entries parsed from syllabus".to_string(),
+            });
+        }
 
-        // If subject changed, save previous subject
-        if let Some(prev_subject) = &current_subject {
-            if prev_subject != &subject_name {
-                entries.push(ParsedSyllabusEntry {
-                    subject_name: prev_subject.clone(),
-                    units: current_units.clone(),
-                });
-                current_units.clear();
-            }
+        if !errors.is_empty() {
+            errors.sort_by_key(|e| (e.line, e.column));
+            return Err(errors);
         }
 
-        current_subject = Some(subject_name);
-        current_units.push(ParsedUnit {
-            unit_name,
-            topics,
+        Ok(self.entries)
+    }
+}
+
+/// Parses one `Subject >>> Unit >>> topic1, topic2` line.
+fn parse_arrow_line(
+    builder: &mut SyllabusBuilder,
+    chars: &[char],
+    line: usize,
+) -> Result<(usize, usize), SyllabusParseError> {
+    let parts = split_unescaped(chars, 1, ">>>");
+    if parts.len() > 3 {
+        return Err(SyllabusParseError {
+            line,
+            column: parts[3].column,
+            message: "too many '>>>' separators, expected 'Subject Name >>> Unit Name >>> topic1, topic2, topic3'".to_string(),
         });
     }
 
-    // Add final subject
-    if let Some(subject_name) = current_subject {
-        entries.push(ParsedSyllabusEntry {
-            subject_name,
-            units: current_units,
+    let subject_name = unescape(&parts[0], line)?;
+    if subject_name.is_empty() {
+        return Err(SyllabusParseError {
+            line,
+            column: parts[0].column,
+            message: "subject name is missing before '>>>'".to_string(),
+        });
+    }
+    let unit_name = unescape(&parts[1], line)?;
+    if unit_name.is_empty() {
+        return Err(SyllabusParseError {
+            line,
+            column: parts[1].column,
+            message: "unit name is missing after '>>>'".to_string(),
         });
     }
 
-    if entries.is_empty() {
-        return Err("No valid entries parsed from syllabus".to_string());
+    let mut topics = Vec::new();
+    if let Some(topics_part) = parts.get(2) {
+        let topic_chars: Vec<char> = topics_part.text.chars().collect();
+        for piece in split_unescaped(&topic_chars, topics_part.column, ",") {
+            let topic = unescape(&piece, line)?;
+            if !topic.is_empty() {
+                topics.push(topic);
+            }
+        }
     }
 
-    Ok(entries)
+    let subject = builder.subject(subject_name);
+    let unit = builder.unit(subject, unit_name);
+    for topic in topics {
+        builder.topic(subject, unit, topic);
+    }
+    Ok((subject, unit))
+}
+
+/// Parses syllabus text into subjects, units and topics.
+///
+/// Two layouts are accepted and may be mixed:
+///
+/// ```text
+/// // One unit per line, topics separated by commas
+/// Mathematics >>> Algebra >>> linear equations, quadratic equations
+///
+/// // An indented outline: subject, then units, then one topic per line
+/// Physics
+///     Mechanics
+///         - Newton's laws
+///         - Forces, moments and torque
+/// ```
+///
+/// A subject line with nothing indented below it is a subject without
+/// units. Lines starting with `//` are comments. `\` escapes the next
+/// character, so `\,` is a comma inside a topic name. Repeated subjects and
+/// units are merged. Every problem is reported with its line and column.
+pub fn parse_syllabus(input: &str) -> Result<Vec<ParsedSyllabusEntry>, Vec<SyllabusParseError>> {
+    let mut builder = SyllabusBuilder::default();
+    let mut errors = Vec::new();
+
+    // Outline state: the current subject and its indent, then the indent
+    // used for units below it and the current unit
+    let mut outline_subject: Option<(usize, usize)> = None;
+    let mut unit_indent: Option<usize> = None;
+    let mut outline_unit: Option<usize> = None;
+
+    for (idx, raw_line) in input.lines().enumerate() {
+        let line = idx + 1;
+        let trimmed = raw_line.trim();
+        if trimmed.is_empty() || trimmed.starts_with("//") {
+            continue;
+        }
+
+        let chars: Vec<char> = raw_line.chars().collect();
+        if split_unescaped(&chars, 1, ">>>").len() > 1 {
+            outline_subject = None;
+            unit_indent = None;
+            outline_unit = None;
+            if let Err(e) = parse_arrow_line(&mut builder, &chars, line) {
+                errors.push(e);
+            }
+            continue;
+        }
+
+        let indent = indent_width(raw_line);
+        let leading = chars.iter().take_while(|c| c.is_whitespace()).count();
+        let mut piece = Piece {
+            text: trimmed.to_string(),
+            column: leading + 1,
+        };
+        for bullet in ["- ", "* ", "+ "] {
+            if let Some(rest) = piece.text.strip_prefix(bullet) {
+                let rest_trimmed = rest.trim_start();
+                piece.column += piece.text.chars().count() - rest_trimmed.chars().count();
+                piece.text = rest_trimmed.to_string();
+                break;
+            }
+        }
+        let name = match unescape(&piece, line) {
+            Ok(name) if !name.is_empty() => name,
+            Ok(_) => {
+                errors.push(SyllabusParseError {
+                    line,
+                    column: piece.column,
+                    message: "expected a name after the bullet".to_string(),
+                });
+                continue;
+            }
+            Err(e) => {
+                errors.push(e);
+                continue;
+            }
+        };
+
+        match outline_subject {
+            Some((subject, subject_indent)) if indent > subject_indent => match unit_indent {
+                None => {
+                    unit_indent = Some(indent);
+                    outline_unit = Some(builder.unit(subject, name));
+                }
+                Some(level) if indent == level => {
+                    outline_unit = Some(builder.unit(subject, name));
+                }
+                Some(level) if indent > level => {
+                    if let Some(unit) = outline_unit {
+                        builder.topic(subject, unit, name);
+                    }
+                }
+                Some(_) => errors.push(SyllabusParseError {
+                    line,
+                    column: piece.column,
+                    message: "indentation doesn't match the subject or unit above".to_string(),
+                }),
+            },
+            _ => {
+                outline_subject = Some((builder.subject(name), indent));
+                unit_indent = None;
+                outline_unit = None;
+            }
+        }
+    }
+
+    builder.finish(errors)
+}
+
+// ============================================================================
+// SYLLABUS TEXT
+// ============================================================================
+
+impl From<&SubjectData> for ParsedSyllabusEntry {
+    fn from(data: &SubjectData) -> Self {
+        ParsedSyllabusEntry {
+            subject_name: data.subject.name.clone(),
+            units: data
+                .units
+                .iter()
+                .map(|u| ParsedUnit {
+                    unit_name: u.unit.name.clone(),
+                    topics: u.topics.iter().map(|t| t.name.clone()).collect(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Escapes a name so `parse_syllabus` reads it back unchanged: separators
+/// and backslashes get a `\`, as does a leading `/` that would otherwise
+/// start a comment.
+fn escape_syllabus_name(name: &str) -> String {
+    let mut out = String::with_capacity(name.len());
+    for (idx, c) in name.chars().enumerate() {
+        if matches!(c, '\\' | ',' | '>') || (idx == 0 && c == '/') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Writes entries as `Subject >>> Unit >>> topic1, topic2` lines, one per
+/// unit, and a line with just the name for a subject without units. This
+/// is the inverse of `parse_syllabus` for everything it returns: names
+/// trimmed and non-empty, and subjects and units unique by name within
+/// their parent.
+pub fn to_syllabus_text(entries: &[ParsedSyllabusEntry]) -> String {
+    let mut lines = Vec::new();
+    for entry in entries {
+        let subject = escape_syllabus_name(&entry.subject_name);
+        if entry.units.is_empty() {
+            // On a line of its own a leading list marker would be dropped
+            if subject.starts_with(['-', '*', '+']) {
+                lines.push(format!("\\{}", subject));
+            } else {
+                lines.push(subject);
+            }
+            continue;
+        }
+        for unit in &entry.units {
+            let mut line = format!("{} >>> {}", subject, escape_syllabus_name(&unit.unit_name));
+            if !unit.topics.is_empty() {
+                let topics: Vec<String> = unit.topics.iter().map(|t| escape_syllabus_name(t)).collect();
+                line.push_str(" >>> ");
+                line.push_str(&topics.join(", "));
+            }
+            lines.push(line);
+        }
+    }
+    lines.join("\n")
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use quickcheck::{Arbitrary, Gen, QuickCheck};
 
     #[test]
     fn test_parse_syllabus() {
@@ -207,5 +604,225 @@ Physics >>> Mechanics >>> newton laws, forces, momentum
         assert_eq!(result[0].subject_name, "Mathematics");
         assert_eq!(result[0].units.len(), 2);
         assert_eq!(result[0].units[0].topics.len(), 3);
+
+        // A subject line with nothing below it is a subject without units
+        let result = parse_syllabus("Electives\nPhysics\n    Optics").unwrap();
+        assert_eq!(result[0].subject_name, "Electives");
+        assert!(result[0].units.is_empty());
+        assert_eq!(result[1].units[0].unit_name, "Optics");
+        assert_eq!(to_syllabus_text(&result), "Electives\nPhysics >>> Optics");
+    }
+
+    #[test]
+    fn test_parse_outline_with_comments() {
+        let input = "
+// Semester 4
+Physics
+    Mechanics
+        - Newton's laws
+        - Forces, moments and torque
+    Optics
+\t\tLenses
+Chemistry >>> Organic >>> alkanes
+";
+        let result = parse_syllabus(input).unwrap();
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].units.len(), 2);
+        assert_eq!(result[0].units[0].topics, vec!["Newton's laws", "Forces, moments and torque"]);
+        assert_eq!(result[0].units[1].topics, vec!["Lenses"]);
+        assert_eq!(result[1].units[0].topics, vec!["alkanes"]);
+    }
+
+    #[test]
+    fn test_parse_escapes_and_merges_subjects() {
+        let input = r#"
+Maths >>> Algebra >>> groups\, rings and fields, a \>>> b
+Physics >>> Mechanics >>> forces
+maths >>> Algebra >>> modules
+Maths >>> Geometry
+"#;
+        let result = parse_syllabus(input).unwrap();
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].subject_name, "Maths");
+        assert_eq!(result[0].units.len(), 2);
+        assert_eq!(result[0].units[0].topics, vec!["groups, rings and fields", "a >>> b", "modules"]);
+    }
+
+    #[test]
+    fn test_parse_errors_have_positions() {
+        let input = "Maths >>> Algebra >>> rings\n  >>> Geometry\nPhysics\nChem >>>  >>> x\\";
+        let errors = parse_syllabus(input).unwrap_err();
+        let positions: Vec<(usize, usize)> = errors.iter().map(|e| (e.line, e.column)).collect();
+        assert_eq!(positions, vec![(2, 1), (4, 9)]);
+    }
+
+    /// Characters that exercise every escape and trimming rule.
+    const NAME_CHARS: &[char] = &[
+        'a', 'b', 'Z', '1', ' ', ' ', '\t', ',', '>', '\\', '/', '-', '#', '*', '\r', 'é', '\u{a0}',
+    ];
+
+    fn arbitrary_name(g: &mut Gen) -> String {
+        let len = usize::arbitrary(g) % 12;
+        let name: String = (0..len).map(|_| *g.choose(NAME_CHARS).unwrap()).collect();
+        match name.trim() {
+            "" => "x".to_string(),
+            trimmed => trimmed.to_string(),
+        }
+    }
+
+    /// A tree in the shape `parse_syllabus` returns.
+    #[derive(Debug, Clone)]
+    struct Syllabus(Vec<ParsedSyllabusEntry>);
+
+    impl Arbitrary for Syllabus {
+        fn arbitrary(g: &mut Gen) -> Self {
+            let mut entries: Vec<ParsedSyllabusEntry> = Vec::new();
+            for _ in 0..1 + usize::arbitrary(g) % 4 {
+                let subject_name = arbitrary_name(g);
+                if entries.iter().any(|e| normalize_name(&e.subject_name) == normalize_name(&subject_name)) {
+                    continue;
+                }
+                let mut units: Vec<ParsedUnit> = Vec::new();
+                for _ in 0..usize::arbitrary(g) % 4 {
+                    let unit_name = arbitrary_name(g);
+                    if units.iter().any(|u| normalize_name(&u.unit_name) == normalize_name(&unit_name)) {
+                        continue;
+                    }
+                    let topics = (0..usize::arbitrary(g) % 5).map(|_| arbitrary_name(g)).collect();
+                    units.push(ParsedUnit { unit_name, topics });
+                }
+                entries.push(ParsedSyllabusEntry { subject_name, units });
+            }
+            Syllabus(entries)
+        }
+    }
+
+    /// Raw lines mixing separators, comments, escapes and indentation.
+    #[derive(Debug, Clone)]
+    struct SyllabusText(String);
+
+    impl Arbitrary for SyllabusText {
+        fn arbitrary(g: &mut Gen) -> Self {
+            const PIECES: &[&str] = &[
+                "Maths", "Algebra", "rings", " >>> ", ">>>", ", ", ",", "\\,", "\\>", "\\\\", "//", "    ", "\t", "- ", "\n",
+            ];
+            let text: String = (0..usize::arbitrary(g) % 30).map(|_| *g.choose(PIECES).unwrap()).collect();
+            SyllabusText(text)
+        }
+    }
+
+    #[test]
+    fn test_text_round_trip() {
+        fn prop(syllabus: Syllabus) -> bool {
+            parse_syllabus(&to_syllabus_text(&syllabus.0)) == Ok(syllabus.0)
+        }
+        QuickCheck::new().tests(500).quickcheck(prop as fn(Syllabus) -> bool);
+    }
+
+    #[test]
+    fn test_text_is_canonical() {
+        fn prop(text: SyllabusText) -> bool {
+            match parse_syllabus(&text.0) {
+                Ok(entries) => parse_syllabus(&to_syllabus_text(&entries)) == Ok(entries),
+                Err(_) => true,
+            }
+        }
+        QuickCheck::new().tests(500).quickcheck(prop as fn(SyllabusText) -> bool);
+    }
+
+    #[test]
+    fn test_to_syllabus_text_escapes() {
+        let entries = parse_syllabus("\\//Notes >>> a\\>b >>> x\\, y, z\\\\\nPhysics >>> Optics").unwrap();
+        assert_eq!(entries[0].subject_name, "//Notes");
+        assert_eq!(
+            to_syllabus_text(&entries),
+            "\\//Notes >>> a\\>b >>> x\\, y, z\\\\\nPhysics >>> Optics"
+        );
+    }
+
+    /// A synthetic tracker with `subjects` x `units` x `topics`, every
+    /// third topic completed.
+    fn large_tracker(subjects: usize, units: usize, topics: usize) -> Database {
+        let db = Database::new(std::path::PathBuf::from(":memory:")).unwrap();
+        db.create_semester("sem".into(), "Sem".into(), 0).unwrap();
+        db.create_tracker("tr".into(), "sem".into(), "Big".into(), None, None, 0).unwrap();
+        db.transaction(|db| {
+            for s in 0..subjects {
+                let subject_id = format!("s{}", s);
+                db.create_subject(subject_id.clone(), "tr".into(), format!("Subject {}", s), s as i64)?;
+                for u in 0..units {
+                    let unit_id = format!("{}u{}", subject_id, u);
+                    // Inserted in reverse so the queries have to sort
+                    db.create_unit(unit_id.clone(), subject_id.clone(), format!("Unit {}", u), (units - u) as i32, 0)?;
+                    for t in 0..topics {
+                        let topic_id = format!("{}t{}", unit_id, t);
+                        db.create_topic(topic_id.clone(), unit_id.clone(), format!("Topic {}", t), t as i32, 0)?;
+                        if t % 3 == 0 {
+                            db.toggle_topic_completion(&topic_id, 0)?;
+                        }
+                    }
+                }
+            }
+            Ok::<_, rusqlite::Error>(())
+        })
+        .unwrap();
+        db
+    }
+
+    /// The old tree walk: one units query per subject and one topics query
+    /// per unit, each prepared from scratch.
+    fn tracker_tree_per_unit(db: &Database, tracker_id: &str) -> Vec<SubjectData> {
+        db.get_subjects_by_tracker(tracker_id)
+            .unwrap()
+            .into_iter()
+            .map(|subject| {
+                db.flush_statement_cache();
+                let units = db
+                    .get_units_by_subject(&subject.id)
+                    .unwrap()
+                    .into_iter()
+                    .map(|unit| {
+                        db.flush_statement_cache();
+                        UnitData { topics: db.get_topics_by_unit(&unit.id).unwrap(), unit }
+                    })
+                    .collect();
+                SubjectData { subject, units }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_tracker_tree_matches_per_unit_queries() {
+        let db = large_tracker(3, 4, 5);
+        let tree = get_tracker_tree(&db, "tr").unwrap();
+        assert_eq!(
+            serde_json::to_value(&tree).unwrap(),
+            serde_json::to_value(tracker_tree_per_unit(&db, "tr")).unwrap()
+        );
+        assert_eq!(tree[0].units[0].unit.name, "Unit 3");
+
+        let progress = calculate_tracker_progress("tr", &tree);
+        assert_eq!((progress.total_topics, progress.completed_topics), (60, 24));
+        assert_eq!(progress.subjects[2].units[1].completed_topics, 2);
+        assert!(get_tracker_tree(&db, "missing").unwrap().is_empty());
+    }
+
+    /// Run with `cargo test --release bench_tracker_tree -- --ignored --nocapture`.
+    #[test]
+    #[ignore]
+    fn bench_tracker_tree() {
+        let db = large_tracker(12, 15, 40);
+        let time = |label: &str, load: &dyn Fn() -> Vec<SubjectData>| {
+            let start = std::time::Instant::now();
+            for _ in 0..20 {
+                assert_eq!(load().len(), 12);
+            }
+            let elapsed = start.elapsed();
+            println!("{}: {:?} per load", label, elapsed / 20);
+            elapsed
+        };
+        let per_unit = time("per-unit queries", &|| tracker_tree_per_unit(&db, "tr"));
+        let joined = time("joined queries", &|| get_tracker_tree(&db, "tr").unwrap());
+        assert!(joined < per_unit);
     }
 }
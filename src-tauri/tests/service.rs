@@ -0,0 +1,290 @@
+//! Drives the app's logic through `StudyService` against in-memory
+//! databases, the way the Tauri commands and `studyapp-cli` do.
+
+use chrono::{Local, TimeZone};
+use serde_json::json;
+use std::path::PathBuf;
+use studyapp_lib::db::Database;
+use studyapp_lib::exporters::ExportFormat;
+use studyapp_lib::models::*;
+use studyapp_lib::service::StudyService;
+
+fn memory_db() -> Database {
+    Database::new(PathBuf::from(":memory:")).unwrap()
+}
+
+/// Local time on a day in March 2025.
+fn at(day: u32, hour: u32) -> i64 {
+    Local.with_ymd_and_hms(2025, 3, day, hour, 0, 0).unwrap().timestamp_millis()
+}
+
+fn topic_ids(service: &StudyService, tracker_id: &str) -> Vec<(String, String)> {
+    service
+        .get_tracker_tree(tracker_id)
+        .unwrap()
+        .into_iter()
+        .flat_map(|s| s.units)
+        .flat_map(|u| u.topics)
+        .map(|t| (t.name, t.id))
+        .collect()
+}
+
+fn planned_topics(service: &StudyService, tracker_id: &str) -> Vec<String> {
+    let plan = service.get_study_plan(tracker_id).unwrap().unwrap();
+    plan.days.into_iter().flat_map(|d| d.topics).map(|t| t.topic_name).collect()
+}
+
+fn os_syllabus() -> serde_json::Value {
+    json!({
+        "version": "1.0",
+        "name": "Operating Systems",
+        "subjects": [
+            {"name": "OS", "units": [
+                {"name": "Processes", "topics": ["Threads", "Scheduling"]},
+                {"name": "Memory", "topics": ["Paging"]}
+            ]}
+        ]
+    })
+}
+
+#[test]
+fn builds_a_tracker_from_text_and_tracks_progress() {
+    let db = memory_db();
+    let service = StudyService::at(&db, at(10, 9));
+    let semester = service.create_semester("Sem 4".into()).unwrap();
+
+    let tracker = service
+        .create_tracker(
+            semester.id.clone(),
+            "Sem 4".into(),
+            "OS >>> Processes >>> Threads, Scheduling\nDBMS >>> SQL >>> Joins",
+            None,
+        )
+        .unwrap();
+    assert_eq!((tracker.total_subjects, tracker.total_units, tracker.total_topics), (2, 2, 3));
+
+    let topics = topic_ids(&service, &tracker.id);
+    let names: Vec<&str> = topics.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(names, vec!["Threads", "Scheduling", "Joins"]);
+
+    let toggled = service.toggle_topic(&topics[0].1).unwrap().unwrap();
+    assert!(toggled.completed);
+    let progress = service.tracker_progress(&tracker.id).unwrap();
+    assert_eq!((progress.completed_topics, progress.total_topics), (1, 3));
+    assert_eq!(progress.subjects[0].percentage, 50.0);
+    assert_eq!(progress.subjects[1].percentage, 0.0);
+
+    let overview = service.semester_overview(&semester.id).unwrap();
+    assert_eq!((overview.completed_topics, overview.total_topics), (1, 3));
+
+    // Editing the outline as text keeps the completed topic's ID
+    let text = service.get_tracker_as_text(&tracker.id).unwrap();
+    let edited = text.replace("Joins", "Joins, Indexes");
+    let plan = service.preview_tracker_text(&tracker.id, &edited).unwrap();
+    let summary = service.apply_tracker_text(&tracker.id, &edited, &plan.fingerprint).unwrap();
+    assert_eq!(summary.inserted, 1);
+    let after = topic_ids(&service, &tracker.id);
+    assert_eq!(after[0], topics[0]);
+    assert_eq!(service.get_tracker(&tracker.id).unwrap().unwrap().total_topics, 4);
+
+    assert!(service.create_tracker(semester.id, "Bad".into(), ">>> Unit >>> topic", None).is_err());
+}
+
+#[test]
+fn picks_out_tests_and_assignments_inside_their_priority_window() {
+    let db = memory_db();
+    let service = StudyService::at(&db, at(10, 9));
+    let semester = service.create_semester("Sem 4".into()).unwrap();
+    let tracker = service.import_syllabus(semester.id, os_syllabus()).unwrap();
+    let tree = service.get_tracker_tree(&tracker.id).unwrap();
+    let processes = tree[0].units[0].unit.id.clone();
+
+    let coverage = vec![TestCoverageInput { unit_id: Some(processes), topic_id: None }];
+    service.schedule_test(tracker.id.clone(), "ISA 1".into(), "isa".into(), at(13, 20), coverage).unwrap();
+    service.schedule_test(tracker.id.clone(), "Quiz".into(), "class_test".into(), at(11, 20), Vec::new()).unwrap();
+    service.schedule_test(tracker.id.clone(), "ESA".into(), "esa".into(), at(28, 20), Vec::new()).unwrap();
+    service.schedule_test(tracker.id.clone(), "Past".into(), "isa".into(), at(8, 20), Vec::new()).unwrap();
+
+    // A type with a wide window pulls a far-off test in
+    let project = service.create_test_type("Project review", None, 30, 1.5).unwrap();
+    service.schedule_test(tracker.id.clone(), "Review".into(), project.id, at(28, 20), Vec::new()).unwrap();
+
+    let assignment = |name: &str, due| AssignmentInput {
+        subject_id: None,
+        name: name.into(),
+        description: None,
+        due_date: due,
+        topic_ids: Vec::new(),
+    };
+    service.create_assignment(tracker.id.clone(), assignment("Report", at(12, 23))).unwrap();
+    let submitted = service.create_assignment(tracker.id.clone(), assignment("Lab", at(12, 23))).unwrap();
+    service.set_assignment_status(&submitted.id, AssignmentStatus::Submitted).unwrap();
+
+    let data = service.get_tracker_data(&tracker.id).unwrap();
+    assert_eq!(data.all_tests.len(), 5);
+    let priority: Vec<(&str, i32)> = data.priority_tests.iter().map(|p| (p.test.name.as_str(), p.days_remaining)).collect();
+    assert_eq!(priority, vec![("Quiz", 2), ("ISA 1", 4), ("Review", 19)]);
+    assert_eq!(data.priority_tests[1].covered_topics, vec!["Threads", "Scheduling"]);
+    assert_eq!(data.priority_tests[0].time_remaining, "1d 11h 0m");
+
+    let assignments: Vec<&str> = data.priority_assignments.iter().map(|p| p.assignment.name.as_str()).collect();
+    assert_eq!(assignments, vec!["Report"]);
+
+    // Two days on, the quiz has passed
+    let later = StudyService::at(&db, at(12, 9));
+    let priority: Vec<String> = later.get_tracker_data(&tracker.id).unwrap().priority_tests.into_iter().map(|p| p.test.name).collect();
+    assert_eq!(priority, vec!["ISA 1", "Review"]);
+}
+
+#[test]
+fn imports_and_exports_syllabi() {
+    let db = memory_db();
+    let service = StudyService::at(&db, at(10, 9));
+    let semester = service.create_semester("Sem 4".into()).unwrap();
+
+    let tracker = service.import_syllabus(semester.id.clone(), os_syllabus()).unwrap();
+    assert_eq!((tracker.name.as_str(), tracker.total_topics), ("Operating Systems", 3));
+
+    // Exporting and importing again gives an identical copy
+    let exported = service.export_syllabus(&tracker.id).unwrap();
+    let copy = service
+        .import_syllabus(semester.id.clone(), serde_json::to_value(&exported).unwrap())
+        .unwrap();
+    let diff = service.diff_trackers(&tracker.id, &copy.id).unwrap();
+    assert!(diff.changes.is_empty());
+    assert_eq!(diff.unchanged_topics, 3);
+
+    let (_, threads) = &topic_ids(&service, &tracker.id)[0];
+    service.toggle_topic(threads).unwrap();
+    let markdown = service.export_tracker(&tracker.id, ExportFormat::Markdown).unwrap();
+    assert!(markdown.content.contains("- [x] Threads\n- [ ] Scheduling\n"));
+    assert!(markdown.file_name.ends_with(".md"));
+
+    // Merging a newer version keeps progress on topics that stayed
+    let mut newer = os_syllabus();
+    newer["subjects"][0]["units"][1]["topics"] = json!(["Paging", "Segmentation"]);
+    let plan = service.preview_merge(&tracker.id, newer.clone()).unwrap();
+    assert!(serde_json::to_string(&plan).unwrap().contains("Segmentation"));
+
+    // A preview the tracker has moved on from is refused
+    let (_, scheduling) = &topic_ids(&service, &tracker.id)[1];
+    service.toggle_topic(scheduling).unwrap();
+    assert!(service.merge_syllabus(&tracker.id, newer.clone(), &plan.fingerprint, false).is_err());
+    assert_eq!(service.get_tracker(&tracker.id).unwrap().unwrap().total_topics, 3);
+    service.toggle_topic(scheduling).unwrap();
+
+    let merged = service.merge_syllabus(&tracker.id, newer, &plan.fingerprint, false).unwrap();
+    assert_eq!(merged.total_topics, 4);
+    assert_eq!(service.tracker_progress(&tracker.id).unwrap().completed_topics, 1);
+
+    let invalid = json!({"version": "1.0", "name": "", "subjects": []});
+    assert!(service.import_syllabus(semester.id, invalid).is_err());
+    assert_eq!(service.import_syllabus("missing".into(), os_syllabus()).unwrap_err(), "Semester not found");
+    assert_eq!(service.export_syllabus("missing").unwrap_err(), "Tracker not found");
+}
+
+#[test]
+fn keeps_the_study_plan_and_agenda_in_step_with_progress() {
+    let db = memory_db();
+    let service = StudyService::at(&db, at(10, 9));
+    let semester = service.create_semester("Sem 4".into()).unwrap();
+    let tracker = service.import_syllabus(semester.id.clone(), os_syllabus()).unwrap();
+    let tree = service.get_tracker_tree(&tracker.id).unwrap();
+    let coverage = tree[0]
+        .units
+        .iter()
+        .map(|u| TestCoverageInput { unit_id: Some(u.unit.id.clone()), topic_id: None })
+        .collect();
+    service.schedule_test(tracker.id.clone(), "ISA 1".into(), "isa".into(), at(14, 20), coverage).unwrap();
+
+    let settings = PlanSettings {
+        capacity_unit: CapacityUnit::Topics,
+        daily_capacity: 1,
+        minutes_per_topic: 30,
+        rest_days: Vec::new(),
+    };
+    assert!(service.generate_study_plan(&tracker.id, PlanSettings { daily_capacity: 0, ..settings.clone() }).is_err());
+    let plan = service.generate_study_plan(&tracker.id, settings.clone()).unwrap();
+    let planned: Vec<(&str, &str)> = plan
+        .days
+        .iter()
+        .flat_map(|d| d.topics.iter().map(move |t| (d.day.as_str(), t.topic_name.as_str())))
+        .collect();
+    assert_eq!(planned, vec![("2025-03-10", "Threads"), ("2025-03-11", "Scheduling"), ("2025-03-12", "Paging")]);
+
+    let agenda = service.get_today(Some(&semester.id)).unwrap();
+    assert_eq!(agenda.today, "2025-03-10");
+    let titles: Vec<&str> = agenda.items.iter().map(|i| i.title.as_str()).collect();
+    assert_eq!(titles, vec!["Threads", "Scheduling", "Paging", "ISA 1"]);
+
+    // Finishing a topic takes it off the plan and the rest move up
+    let (_, threads) = &topic_ids(&service, &tracker.id)[0];
+    service.toggle_topic(threads).unwrap();
+    let plan = service.get_study_plan(&tracker.id).unwrap().unwrap();
+    let first = &plan.days[0];
+    assert_eq!((first.day.as_str(), first.topics[0].topic_name.as_str()), ("2025-03-10", "Scheduling"));
+
+    // Topics added to or deleted from a planned unit rebalance the plan too
+    let swapping = service.create_topic(tree[0].units[1].unit.id.clone(), "Swapping".into()).unwrap();
+    assert_eq!(planned_topics(&service, &tracker.id), vec!["Scheduling", "Paging", "Swapping"]);
+    service.delete_topic(&swapping.id).unwrap();
+    assert_eq!(planned_topics(&service, &tracker.id), vec!["Scheduling", "Paging"]);
+
+    // Rest days are kept with the settings and skipped; the 11th is a Tuesday
+    let resting = PlanSettings { rest_days: vec![chrono::Weekday::Tue], ..settings };
+    let plan = service.generate_study_plan(&tracker.id, resting).unwrap();
+    assert_eq!(plan.settings.rest_days, vec![chrono::Weekday::Tue]);
+    let days: Vec<&str> = plan.days.iter().map(|d| d.day.as_str()).collect();
+    assert_eq!(days, vec!["2025-03-10", "2025-03-12"]);
+
+    // Renaming a test or changing what it covers raises its calendar SEQUENCE
+    let test = &service.get_tests_by_tracker(&tracker.id).unwrap()[0];
+    assert!(service.rename_test(&test.id, " ").is_err());
+    assert_eq!(service.rename_test(&test.id, "Mid term").unwrap().revision, 1);
+    let paging = TestCoverageInput { unit_id: Some(tree[0].units[1].unit.id.clone()), topic_id: None };
+    assert_eq!(service.set_test_coverage(&test.id, vec![paging]).unwrap().revision, 2);
+    assert_eq!(service.get_test_details(&test.id).unwrap().unwrap().covered_topics, vec!["Paging"]);
+    let scope = studyapp_lib::ical::CalendarScope::Tracker { tracker_id: tracker.id.clone() };
+    let ics = service.export_tests_calendar(&scope).unwrap().content;
+    assert!(ics.contains("SEQUENCE:2\r\n"));
+
+    assert!(service.get_today(Some("missing")).is_err());
+}
+
+#[test]
+fn rejects_invalid_input_without_writing() {
+    let db = memory_db();
+    let service = StudyService::at(&db, at(10, 9));
+    let semester = service.create_semester("Sem 4".into()).unwrap();
+    let tracker = service.import_syllabus(semester.id.clone(), os_syllabus()).unwrap();
+    let other = service.import_syllabus(semester.id, os_syllabus()).unwrap();
+
+    let unknown = service.schedule_test(tracker.id.clone(), "Quiz".into(), "pop_quiz".into(), at(12, 9), Vec::new());
+    assert_eq!(unknown.unwrap_err(), "Unknown test type 'pop_quiz'");
+    assert!(service.create_test_type("  ", None, 7, 1.0).is_err());
+    assert!(service.create_test_type("Viva", None, -1, 1.0).is_err());
+
+    let (_, foreign_topic) = &topic_ids(&service, &other.id)[0];
+    let input = AssignmentInput {
+        subject_id: None,
+        name: "Report".into(),
+        description: None,
+        due_date: at(12, 23),
+        topic_ids: vec![foreign_topic.clone()],
+    };
+    assert_eq!(service.create_assignment(tracker.id.clone(), input).unwrap_err(), "Topic not found in this tracker");
+    assert!(service.grade_assignment("missing", 5.0, 10.0).is_err());
+
+    // Test types in use can't be deleted
+    service.schedule_test(tracker.id.clone(), "ISA 1".into(), "isa".into(), at(12, 20), Vec::new()).unwrap();
+    assert!(service.delete_test_type("isa").is_err());
+    // Nor can ones an evaluation scheme weighs, even with no tests
+    assert!(service.delete_test_type("esa").unwrap_err().contains("evaluation scheme"));
+    let viva = service.create_test_type("Viva", None, 7, 1.0).unwrap();
+    service.delete_test_type(&viva.id).unwrap();
+
+    let data = service.get_tracker_data(&tracker.id).unwrap();
+    assert_eq!(data.all_tests.len(), 1);
+    assert!(data.all_assignments.is_empty());
+    assert!(service.check_database().unwrap().healthy);
+}
@@ -4,8 +4,41 @@ import {
   Tracker,
   Topic,
   Test,
+  TestType,
+  Assignment,
+  AssignmentDetails,
+  AssignmentInput,
+  AssignmentStatus,
+  PriorityAssignment,
+  Subject,
+  GradingScale,
+  GradingScaleInput,
+  EvaluationScheme,
+  EvaluationSchemeInput,
+  SemesterGrades,
+  GradeReport,
+  SubjectTarget,
+  SemesterTargets,
+  PlanSettings,
+  StudyPlan,
+  Agenda,
+  DbIncident,
+  DatabaseReport,
+  RepairReport,
   TrackerProgress,
+  SemesterOverview,
   Theme,
+  ImportSource,
+  ImportPreview,
+  MergePlan,
+  MergeSummary,
+  SyllabusDiff,
+  SyllabusFormat,
+  ExportFormat,
+  ExportedFile,
+  CalendarScope,
+  CalendarImportPreview,
+  NewTest,
 } from "../types";
 
 export interface TrackerDataResponse {
@@ -14,6 +47,8 @@ export interface TrackerDataResponse {
       id: string;
       tracker_id: string;
       name: string;
+      credits: number | null;
+      evaluation_scheme_id: string | null;
       created_at: number;
       updated_at: number;
     };
@@ -36,6 +71,8 @@ export interface TrackerDataResponse {
     name: string;
     test_type: string;
     scheduled_date: number;
+    marks_obtained: number | null;
+    max_marks: number | null;
     created_at: number;
     updated_at: number;
   }>;
@@ -46,6 +83,8 @@ export interface TrackerDataResponse {
       name: string;
       test_type: string;
       scheduled_date: number;
+      marks_obtained: number | null;
+      max_marks: number | null;
       created_at: number;
       updated_at: number;
     };
@@ -59,6 +98,9 @@ export interface TrackerDataResponse {
     time_remaining: string;
     covered_topics: string[];
   }>;
+  test_types: TestType[];
+  all_assignments: Assignment[];
+  priority_assignments: PriorityAssignment[];
 }
 
 // SEMESTER COMMANDS
@@ -72,14 +114,20 @@ export const api = {
 
     delete: (id: string) =>
       invoke<void>("delete_semester", { id }),
+
+    getOverview: (semester_id: string) =>
+      invoke<SemesterOverview>("get_semester_overview", {
+        semesterId: semester_id,
+      }),
   },
 
   tracker: {
-    create: (semester_id: string, name: string, syllabus_text: string) =>
+    create: (semester_id: string, name: string, syllabus_text: string, format?: SyllabusFormat) =>
       invoke<Tracker>("create_new_tracker", {
         semesterId: semester_id,
         name: name,
         syllabusText: syllabus_text,
+        format: format ?? null, // detected from the text when null
       }),
 
     getBysemester: (semester_id: string) =>
@@ -94,6 +142,27 @@ export const api = {
       invoke<TrackerDataResponse>("get_tracker_data", { 
         trackerId: tracker_id 
       }),
+
+    // Repairs cached subject/unit/topic counts; resolves to how many were wrong
+    recomputeStatistics: () =>
+      invoke<number>("recompute_all_statistics"),
+
+    getAsText: (tracker_id: string) =>
+      invoke<string>("get_tracker_as_text", { trackerId: tracker_id }),
+
+    previewText: (tracker_id: string, text: string) =>
+      invoke<MergePlan>("preview_tracker_text", {
+        trackerId: tracker_id,
+        text: text,
+      }),
+
+    // fingerprint is the one from the approved previewText plan
+    applyText: (tracker_id: string, text: string, fingerprint: string) =>
+      invoke<MergeSummary>("apply_tracker_text", {
+        trackerId: tracker_id,
+        text: text,
+        fingerprint: fingerprint,
+      }),
   },
 
   subject: {
@@ -159,6 +228,27 @@ export const api = {
         coverageData: coverage_data,
       }),
 
+    reschedule: (test_id: string, scheduled_date: number) =>
+      invoke<Test>("reschedule_test", {
+        testId: test_id,
+        scheduledDate: scheduled_date,
+      }),
+
+    rename: (test_id: string, name: string) =>
+      invoke<Test>("rename_test", {
+        testId: test_id,
+        name: name,
+      }),
+
+    setCoverage: (
+      test_id: string,
+      coverage_data: Array<{ unit_id: string | null; topic_id: string | null }>
+    ) =>
+      invoke<Test>("set_test_coverage", {
+        testId: test_id,
+        coverageData: coverage_data,
+      }),
+
     getByTracker: (tracker_id: string) =>
       invoke<Test[]>("get_tests_by_tracker", { 
         trackerId: tracker_id 
@@ -174,6 +264,193 @@ export const api = {
       } | null>("get_test_details", { 
         testId: test_id 
       }),
+
+    exportCalendar: (scope: CalendarScope) =>
+      invoke<ExportedFile>("export_tests_calendar", { scope }),
+
+    previewCalendarImport: (tracker_id: string, ics: string) =>
+      invoke<CalendarImportPreview>("preview_calendar_import", {
+        trackerId: tracker_id,
+        ics: ics,
+      }),
+
+    importCalendar: (tracker_id: string, tests: NewTest[]) =>
+      invoke<Test[]>("import_calendar_tests", {
+        trackerId: tracker_id,
+        tests: tests,
+      }),
+  },
+
+  testType: {
+    getAll: () =>
+      invoke<TestType[]>("get_test_types"),
+
+    create: (name: string, color: string | null, priority_window_days: number, weight: number) =>
+      invoke<TestType>("create_test_type", {
+        name: name,
+        color: color,
+        priorityWindowDays: priority_window_days,
+        weight: weight,
+      }),
+
+    update: (id: string, name: string, color: string | null, priority_window_days: number, weight: number) =>
+      invoke<TestType>("update_test_type", {
+        id: id,
+        name: name,
+        color: color,
+        priorityWindowDays: priority_window_days,
+        weight: weight,
+      }),
+
+    delete: (id: string) =>
+      invoke<void>("delete_test_type", { id }),
+  },
+
+  assignment: {
+    create: (tracker_id: string, assignment: AssignmentInput) =>
+      invoke<Assignment>("create_assignment", {
+        trackerId: tracker_id,
+        assignment: assignment,
+      }),
+
+    getByTracker: (tracker_id: string) =>
+      invoke<Assignment[]>("get_assignments_by_tracker", {
+        trackerId: tracker_id,
+      }),
+
+    getDetails: (assignment_id: string) =>
+      invoke<AssignmentDetails | null>("get_assignment_details", {
+        assignmentId: assignment_id,
+      }),
+
+    update: (assignment_id: string, assignment: AssignmentInput) =>
+      invoke<Assignment>("update_assignment", {
+        assignmentId: assignment_id,
+        assignment: assignment,
+      }),
+
+    setStatus: (assignment_id: string, status: AssignmentStatus) =>
+      invoke<Assignment>("set_assignment_status", {
+        assignmentId: assignment_id,
+        status: status,
+      }),
+
+    grade: (assignment_id: string, marks_obtained: number, max_marks: number) =>
+      invoke<Assignment>("grade_assignment", {
+        assignmentId: assignment_id,
+        marksObtained: marks_obtained,
+        maxMarks: max_marks,
+      }),
+
+    delete: (assignment_id: string) =>
+      invoke<void>("delete_assignment", {
+        assignmentId: assignment_id,
+      }),
+  },
+
+  grades: {
+    recordTestMarks: (test_id: string, marks_obtained: number | null, max_marks: number | null) =>
+      invoke<Test>("record_test_marks", {
+        testId: test_id,
+        marksObtained: marks_obtained,
+        maxMarks: max_marks,
+      }),
+
+    // Which subject's grade the test's marks count towards; null for none
+    setTestSubject: (test_id: string, subject_id: string | null) =>
+      invoke<Test>("set_test_subject", {
+        testId: test_id,
+        subjectId: subject_id,
+      }),
+
+    setSubjectGrading: (subject_id: string, credits: number | null, evaluation_scheme_id: string | null) =>
+      invoke<Subject>("set_subject_grading", {
+        subjectId: subject_id,
+        credits: credits,
+        evaluationSchemeId: evaluation_scheme_id,
+      }),
+
+    getScales: () =>
+      invoke<GradingScale[]>("get_grading_scales"),
+
+    createScale: (scale: GradingScaleInput) =>
+      invoke<GradingScale>("create_grading_scale", { scale }),
+
+    updateScale: (id: string, scale: GradingScaleInput) =>
+      invoke<GradingScale>("update_grading_scale", { id, scale }),
+
+    deleteScale: (id: string) =>
+      invoke<void>("delete_grading_scale", { id }),
+
+    getSchemes: () =>
+      invoke<EvaluationScheme[]>("get_evaluation_schemes"),
+
+    createScheme: (scheme: EvaluationSchemeInput) =>
+      invoke<EvaluationScheme>("create_evaluation_scheme", { scheme }),
+
+    updateScheme: (id: string, scheme: EvaluationSchemeInput) =>
+      invoke<EvaluationScheme>("update_evaluation_scheme", { id, scheme }),
+
+    deleteScheme: (id: string) =>
+      invoke<void>("delete_evaluation_scheme", { id }),
+
+    getSemester: (semester_id: string) =>
+      invoke<SemesterGrades>("get_semester_grades", {
+        semesterId: semester_id,
+      }),
+
+    getReport: () =>
+      invoke<GradeReport>("get_grade_report"),
+
+    calculateTarget: (subject_id: string, target_grade: string) =>
+      invoke<SubjectTarget>("calculate_grade_target", {
+        subjectId: subject_id,
+        targetGrade: target_grade,
+      }),
+
+    calculateSemesterTargets: (semester_id: string, target_grade: string) =>
+      invoke<SemesterTargets>("calculate_semester_targets", {
+        semesterId: semester_id,
+        targetGrade: target_grade,
+      }),
+  },
+
+  plan: {
+    get: (tracker_id: string) =>
+      invoke<StudyPlan | null>("get_study_plan", {
+        trackerId: tracker_id,
+      }),
+
+    generate: (tracker_id: string, settings: PlanSettings) =>
+      invoke<StudyPlan>("generate_study_plan", {
+        trackerId: tracker_id,
+        settings: settings,
+      }),
+
+    delete: (tracker_id: string) =>
+      invoke<void>("delete_study_plan", {
+        trackerId: tracker_id,
+      }),
+  },
+
+  agenda: {
+    // Across all semesters when semester_id is omitted
+    getToday: (semester_id?: string) =>
+      invoke<Agenda>("get_today", {
+        semesterId: semester_id ?? null,
+      }),
+  },
+
+  database: {
+    // Connections recovered after a command crashed, since the app started
+    getIncidents: () =>
+      invoke<DbIncident[]>("get_database_incidents"),
+
+    check: () =>
+      invoke<DatabaseReport>("check_database"),
+
+    repair: () =>
+      invoke<RepairReport>("repair_database"),
   },
 
   theme: {
@@ -200,10 +477,46 @@ export const api = {
         }>;
       }>("export_syllabus", { trackerId: tracker_id }),
 
+    exportAs: (tracker_id: string, format: ExportFormat) =>
+      invoke<ExportedFile>("export_tracker", {
+        trackerId: tracker_id,
+        format: format,
+      }),
+
     import: (semester_id: string, syllabus: any) =>
       invoke<Tracker>("import_syllabus", {
         semesterId: semester_id,
         syllabus: syllabus,
       }),
+
+    preview: (source: ImportSource) =>
+      invoke<ImportPreview>("preview_import", { source }),
+
+    previewMerge: (tracker_id: string, syllabus: unknown) =>
+      invoke<MergePlan>("preview_merge", {
+        trackerId: tracker_id,
+        syllabus: syllabus,
+      }),
+
+    // fingerprint is the one from the approved previewMerge plan
+    merge: (tracker_id: string, syllabus: unknown, fingerprint: string, delete_removed: boolean) =>
+      invoke<Tracker>("merge_syllabus", {
+        trackerId: tracker_id,
+        syllabus: syllabus,
+        fingerprint: fingerprint,
+        deleteRemoved: delete_removed,
+      }),
+
+    diffTrackers: (old_tracker_id: string, new_tracker_id: string) =>
+      invoke<SyllabusDiff>("diff_trackers", {
+        oldTrackerId: old_tracker_id,
+        newTrackerId: new_tracker_id,
+      }),
+
+    diffWithFile: (tracker_id: string, syllabus: unknown) =>
+      invoke<SyllabusDiff>("diff_tracker_with_syllabus", {
+        trackerId: tracker_id,
+        syllabus: syllabus,
+      }),
   },
 };
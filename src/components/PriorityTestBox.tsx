@@ -25,9 +25,10 @@ interface PriorityTestData {
 
 interface PriorityTestBoxProps {
   test: PriorityTestData;
+  typeName?: string;
 }
 
-export const PriorityTestBox: React.FC<PriorityTestBoxProps> = ({ test }) => {
+export const PriorityTestBox: React.FC<PriorityTestBoxProps> = ({ test, typeName }) => {
   const { colors} = useTheme();
   const [timeRemaining, setTimeRemaining] = useState(test.time_remaining);
 
@@ -105,7 +106,7 @@ export const PriorityTestBox: React.FC<PriorityTestBoxProps> = ({ test }) => {
               opacity: 0.7,
             }}
           >
-            {test.test.test_type}
+            {typeName ?? test.test.test_type}
           </p>
         </div>
       </div>
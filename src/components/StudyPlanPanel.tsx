@@ -0,0 +1,214 @@
+import React, { useEffect, useState } from "react";
+import { useTheme } from "./ThemeProvider";
+import { api } from "../api/client";
+import { CapacityUnit, StudyPlan } from "../types";
+
+interface StudyPlanPanelProps {
+  trackerId: string;
+  refreshKey: number;
+}
+
+export const StudyPlanPanel: React.FC<StudyPlanPanelProps> = ({
+  trackerId,
+  refreshKey,
+}) => {
+  const { colors } = useTheme();
+  const [plan, setPlan] = useState<StudyPlan | null>(null);
+  const [capacityUnit, setCapacityUnit] = useState<CapacityUnit>("topics");
+  const [dailyCapacity, setDailyCapacity] = useState(3);
+  const [minutesPerTopic, setMinutesPerTopic] = useState(30);
+  const [isGenerating, setIsGenerating] = useState(false);
+  const [error, setError] = useState("");
+
+  // The backend rebalances the plan itself; reload it whenever topics or
+  // tests change
+  useEffect(() => {
+    api.plan
+      .get(trackerId)
+      .then((loaded) => {
+        setPlan(loaded);
+        if (loaded) {
+          setCapacityUnit(loaded.settings.capacity_unit);
+          setDailyCapacity(loaded.settings.daily_capacity);
+          setMinutesPerTopic(loaded.settings.minutes_per_topic);
+        }
+      })
+      .catch((err) => setError(String(err)));
+  }, [trackerId, refreshKey]);
+
+  const handleGenerate = async () => {
+    setError("");
+    setIsGenerating(true);
+    try {
+      const generated = await api.plan.generate(trackerId, {
+        capacity_unit: capacityUnit,
+        daily_capacity: dailyCapacity,
+        minutes_per_topic: minutesPerTopic,
+      });
+      setPlan(generated);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setIsGenerating(false);
+    }
+  };
+
+  const handleClear = async () => {
+    try {
+      await api.plan.delete(trackerId);
+      setPlan(null);
+    } catch (err) {
+      setError(String(err));
+    }
+  };
+
+  const inputStyle: React.CSSProperties = {
+    padding: "0.5rem",
+    border: `1px solid ${colors.border}`,
+    borderRadius: "0.375rem",
+    backgroundColor: colors.secondary,
+    color: colors.fg,
+    width: "6rem",
+  };
+
+  const buttonStyle: React.CSSProperties = {
+    backgroundColor: colors.accent,
+    color: colors.bg,
+    border: "none",
+    padding: "0.5rem 1rem",
+    borderRadius: "0.375rem",
+    cursor: "pointer",
+    fontWeight: 500,
+  };
+
+  return (
+    <div>
+      <div
+        style={{
+          display: "flex",
+          flexWrap: "wrap",
+          alignItems: "center",
+          gap: "0.75rem",
+          marginBottom: "1.5rem",
+          color: colors.fg,
+        }}
+      >
+        <span>Study up to</span>
+        <input
+          type="number"
+          min={1}
+          value={dailyCapacity}
+          onChange={(e) => setDailyCapacity(Number(e.target.value))}
+          style={inputStyle}
+        />
+        <select
+          value={capacityUnit}
+          onChange={(e) => setCapacityUnit(e.target.value as CapacityUnit)}
+          style={{ ...inputStyle, width: "auto" }}
+        >
+          <option value="topics">topics</option>
+          <option value="minutes">minutes</option>
+        </select>
+        <span>a day, at about</span>
+        <input
+          type="number"
+          min={1}
+          value={minutesPerTopic}
+          onChange={(e) => setMinutesPerTopic(Number(e.target.value))}
+          style={inputStyle}
+        />
+        <span>minutes per topic</span>
+        <button onClick={handleGenerate} disabled={isGenerating} style={buttonStyle}>
+          {plan ? "Re-plan" : "Generate plan"}
+        </button>
+        {plan && (
+          <button
+            onClick={handleClear}
+            style={{ ...buttonStyle, backgroundColor: "transparent", color: colors.fg, border: `1px solid ${colors.border}` }}
+          >
+            Clear plan
+          </button>
+        )}
+      </div>
+
+      {error && (
+        <div
+          style={{
+            padding: "0.75rem",
+            backgroundColor: "#fee2e2",
+            borderRadius: "0.375rem",
+            color: "#991b1b",
+            marginBottom: "1rem",
+          }}
+        >
+          {error}
+        </div>
+      )}
+
+      {plan && plan.unscheduled.length > 0 && (
+        <div
+          style={{
+            padding: "0.75rem",
+            backgroundColor: "#fef3c7",
+            borderRadius: "0.375rem",
+            color: "#92400e",
+            marginBottom: "1rem",
+          }}
+        >
+          {plan.unscheduled.length} topic(s) don't fit before their test at this pace:{" "}
+          {plan.unscheduled.map((t) => t.topic_name).join(", ")}
+        </div>
+      )}
+
+      {!plan ? (
+        <p style={{ color: colors.fg, opacity: 0.7 }}>
+          No study plan yet. Generate one to spread the topics of upcoming tests over the days before them.
+        </p>
+      ) : plan.days.length === 0 ? (
+        <p style={{ color: colors.fg, opacity: 0.7 }}>Nothing left to plan before upcoming tests.</p>
+      ) : (
+        <div style={{ display: "flex", flexDirection: "column", gap: "1rem" }}>
+          {plan.days.map((day) => (
+            <div
+              key={day.day}
+              style={{
+                backgroundColor: colors.secondary,
+                border: `1px solid ${colors.border}`,
+                borderRadius: "0.5rem",
+                padding: "1rem 1.5rem",
+              }}
+            >
+              <h4 style={{ margin: "0 0 0.5rem 0", color: colors.accent }}>
+                {new Date(`${day.day}T00:00:00`).toLocaleDateString(undefined, {
+                  weekday: "long",
+                  month: "short",
+                  day: "numeric",
+                })}{" "}
+                <span style={{ fontSize: "0.875rem", color: colors.fg, opacity: 0.7 }}>
+                  (~{day.minutes} min)
+                </span>
+              </h4>
+              <ul style={{ margin: 0, paddingLeft: "1.25rem", color: colors.fg }}>
+                {day.topics.map((topic) => (
+                  <li
+                    key={topic.item_id}
+                    style={{
+                      textDecoration: topic.completed ? "line-through" : "none",
+                      opacity: topic.completed ? 0.6 : 1,
+                    }}
+                  >
+                    {topic.topic_name}{" "}
+                    <span style={{ fontSize: "0.875rem", opacity: 0.7 }}>
+                      {topic.subject_name} › {topic.unit_name}
+                      {topic.test_name && ` · for ${topic.test_name}`}
+                    </span>
+                  </li>
+                ))}
+              </ul>
+            </div>
+          ))}
+        </div>
+      )}
+    </div>
+  );
+};
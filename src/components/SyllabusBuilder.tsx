@@ -171,9 +171,9 @@ export const SyllabusBuilder: React.FC<SyllabusBuilderProps> = ({
           .map(unit => {
             const topics = unit.topics
               .filter(t => t.name.trim())
-              .map(t => t.name.trim())
+              .map(t => escapeSyllabusName(t.name.trim()))
               .join(", ");
-            return `${subject.name.trim()} >>> ${unit.name.trim()}${topics ? ` >>> ${topics}` : ""}`;
+            return `${escapeSyllabusName(subject.name.trim())} >>> ${escapeSyllabusName(unit.name.trim())}${topics ? ` >>> ${topics}` : ""}`;
           })
           .join("\n")
       )
@@ -483,21 +483,54 @@ export const SyllabusBuilder: React.FC<SyllabusBuilderProps> = ({
   );
 };
 
+// Escapes characters that the syllabus text format treats as separators
+function escapeSyllabusName(name: string): string {
+  return name.replace(/[\\,>]/g, ch => `\\${ch}`);
+}
+
+// Splits on separators that aren't escaped, leaving escapes in place
+function splitSyllabusText(text: string, separator: string): string[] {
+  const parts: string[] = [];
+  let current = "";
+  for (let i = 0; i < text.length; i++) {
+    if (text[i] === "\\" && i + 1 < text.length) {
+      current += text[i] + text[i + 1];
+      i++;
+    } else if (text.startsWith(separator, i)) {
+      parts.push(current);
+      current = "";
+      i += separator.length - 1;
+    } else {
+      current += text[i];
+    }
+  }
+  parts.push(current);
+  return parts.map(p => p.trim());
+}
+
+function unescapeSyllabusName(name: string): string {
+  return name.replace(/\\(.)/g, "$1");
+}
+
 // Helper function to parse syllabus text back to structure
 function parseSyllabusToStructure(syllabusText: string): Subject[] {
   const subjects: Subject[] = [];
   const subjectMap = new Map<string, Subject>();
 
-  const lines = syllabusText.split('\n').filter(line => line.trim());
+  const lines = syllabusText
+    .split('\n')
+    .filter(line => line.trim() && !line.trim().startsWith('//'));
   
   for (const line of lines) {
-    const parts = line.split('>>>').map(p => p.trim());
+    const parts = splitSyllabusText(line, '>>>');
     if (parts.length < 2) continue;
 
-    const subjectName = parts[0];
-    const unitName = parts[1];
+    const subjectName = unescapeSyllabusName(parts[0]);
+    const unitName = unescapeSyllabusName(parts[1]);
     const topicsStr = parts.length > 2 ? parts[2] : "";
-    const topics = topicsStr ? topicsStr.split(',').map(t => t.trim()).filter(t => t) : [];
+    const topics = topicsStr
+      ? splitSyllabusText(topicsStr, ',').map(unescapeSyllabusName).filter(t => t)
+      : [];
 
     // Get or create subject
     let subject = subjectMap.get(subjectName);
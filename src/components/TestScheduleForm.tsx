@@ -1,7 +1,7 @@
-import React, { useState } from "react";
+import React, { useEffect, useState } from "react";
 import { useTheme } from "./ThemeProvider";
 import { api } from "../api/client";
-import { Tracker } from "../types";
+import { TestType, Tracker } from "../types";
 import { ChevronDown } from "lucide-react";
 import { NeonCheckbox } from "./NeonCheckbox";
 
@@ -49,6 +49,7 @@ export const TestScheduleForm: React.FC<TestScheduleFormProps> = ({
 }) => {
   const { colors } = useTheme();
   const [testName, setTestName] = useState("");
+  const [testTypes, setTestTypes] = useState<TestType[]>([]);
   const [testType, setTestType] = useState("class_test");
   const [scheduledDate, setScheduledDate] = useState(
     new Date().toISOString().split("T")[0]
@@ -63,6 +64,18 @@ export const TestScheduleForm: React.FC<TestScheduleFormProps> = ({
   const [isSubmitting, setIsSubmitting] = useState(false);
   const [error, setError] = useState("");
 
+  useEffect(() => {
+    api.testType
+      .getAll()
+      .then((types) => {
+        setTestTypes(types);
+        if (types.length > 0 && !types.some((t) => t.id === "class_test")) {
+          setTestType(types[0].id);
+        }
+      })
+      .catch((err) => setError(String(err)));
+  }, []);
+
   const filteredSubjects = subjects.filter(s => 
     selectedSubjects.size === 0 || selectedSubjects.has(s.subject.id)
   );
@@ -227,10 +240,11 @@ export const TestScheduleForm: React.FC<TestScheduleFormProps> = ({
             boxSizing: "border-box",
           }}
         >
-          <option value="lab_practical">Lab Practical</option>
-          <option value="class_test">Class Test</option>
-          <option value="isa">ISA</option>
-          <option value="esa">ESA</option>
+          {testTypes.map((type) => (
+            <option key={type.id} value={type.id}>
+              {type.name}
+            </option>
+          ))}
         </select>
       </label>
 
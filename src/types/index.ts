@@ -39,6 +39,8 @@ export interface Subject {
   id: string;
   tracker_id: string;
   name: string;
+  credits: number | null;
+  evaluation_scheme_id: string | null;
   created_at: number;
   updated_at: number;
 }
@@ -66,19 +68,30 @@ export interface Topic {
 // TESTS
 // ============================================================================
 
-export enum TestType {
-  LabPractical = "lab_practical",
-  ClassTest = "class_test",
-  ISA = "isa",
-  ESA = "esa",
+/**
+ * A kind of test, e.g. ISA or Viva. The built-in types use the IDs
+ * lab_practical, class_test, isa and esa.
+ */
+export interface TestType {
+  id: string;
+  name: string;
+  color: string | null;
+  priority_window_days: number; // days before a test it becomes a priority
+  weight: number; // relative importance
+  created_at: number;
+  updated_at: number;
 }
 
 export interface Test {
   id: string;
   tracker_id: string;
   name: string;
-  test_type: string;
+  test_type: string; // TestType ID
   scheduled_date: number; // timestamp in ms (date only, time is 8 PM)
+  marks_obtained: number | null;
+  max_marks: number | null;
+  subject_id: string | null; // subject whose grade the marks count towards
+  revision: number; // raised by changes that show in the calendar
   created_at: number;
   updated_at: number;
 }
@@ -95,6 +108,254 @@ export interface TestCoverage {
   topic_id: string | null; // null if entire unit covered
 }
 
+export interface TestCoverageInput {
+  unit_id: string | null;
+  topic_id: string | null;
+}
+
+export interface NewTest {
+  name: string;
+  test_type: string;
+  scheduled_date: number;
+  coverage: TestCoverageInput[];
+}
+
+export interface CalendarImportCandidate {
+  uid: string | null;
+  name: string;
+  scheduled_date: number;
+  test_type: string; // guessed from keywords, can be changed before importing
+  matched_subjects: string[];
+  coverage: TestCoverageInput[]; // every unit of the matched subjects
+  duplicate_of: Test | null; // existing test on the same day that looks the same
+}
+
+export interface CalendarImportPreview {
+  candidates: CalendarImportCandidate[];
+  warnings: string[];
+}
+
+export type CalendarScope =
+  | { scope: "tracker"; tracker_id: string }
+  | { scope: "semester"; semester_id: string }
+  | { scope: "all" };
+
+// ============================================================================
+// ASSIGNMENTS
+// ============================================================================
+
+export type AssignmentStatus = "todo" | "doing" | "submitted" | "graded";
+
+export interface Assignment {
+  id: string;
+  tracker_id: string;
+  subject_id: string | null; // null if not tied to one subject
+  name: string;
+  description: string | null;
+  due_date: number; // timestamp in ms
+  status: AssignmentStatus;
+  marks_obtained: number | null;
+  max_marks: number | null;
+  created_at: number;
+  updated_at: number;
+}
+
+export interface AssignmentInput {
+  subject_id: string | null;
+  name: string;
+  description: string | null;
+  due_date: number;
+  topic_ids: string[];
+}
+
+export interface PriorityAssignment {
+  assignment: Assignment;
+  days_remaining: number;
+  time_remaining: string;
+  linked_topics: string[];
+}
+
+export interface AssignmentDetails extends PriorityAssignment {
+  topic_ids: string[];
+}
+
+// ============================================================================
+// GRADES
+// ============================================================================
+
+export interface GradeBand {
+  letter: string;
+  min_percentage: number; // lowest percentage earning this grade
+  grade_points: number;
+}
+
+export interface GradingScale {
+  id: string;
+  name: string;
+  bands: GradeBand[]; // highest cut-off first
+  created_at: number;
+  updated_at: number;
+}
+
+export interface GradingScaleInput {
+  name: string;
+  bands: GradeBand[];
+}
+
+export interface EvaluationComponent {
+  test_type_id: string;
+  weight: number; // share of the final mark, relative to the other components
+  best_of: number | null; // only the best N tests of the type count
+}
+
+export interface EvaluationScheme {
+  id: string;
+  name: string;
+  grading_scale_id: string;
+  components: EvaluationComponent[];
+  created_at: number;
+  updated_at: number;
+}
+
+export interface EvaluationSchemeInput {
+  name: string;
+  grading_scale_id: string;
+  components: EvaluationComponent[];
+}
+
+export interface ComponentResult {
+  test_type_id: string;
+  weight: number;
+  best_of: number | null;
+  percentage: number | null; // null until a test of this type is marked
+  marked_tests: number;
+  unmarked_tests: number;
+}
+
+export interface SubjectGrade {
+  subject_id: string;
+  subject_name: string;
+  tracker_id: string;
+  credits: number | null;
+  evaluation_scheme_id: string | null;
+  components: ComponentResult[];
+  percentage: number | null; // weighted over the components marked so far
+  complete: boolean;
+  letter: string | null;
+  grade_points: number | null;
+}
+
+export interface SemesterGrades {
+  semester_id: string;
+  semester_name: string;
+  subjects: SubjectGrade[];
+  credits: number;
+  sgpa: number | null;
+  provisional: boolean; // includes grades that may still change
+  unassigned_tests: Test[]; // marked tests with no subject, so in no grade
+}
+
+export interface GradeReport {
+  semesters: SemesterGrades[]; // oldest first
+  credits: number;
+  cgpa: number | null;
+  provisional: boolean;
+}
+
+export type TargetOutcome = "secured" | "reachable" | "unreachable";
+
+export interface RemainingTest {
+  test_type_id: string;
+  test_id: string | null; // null for a component with no test scheduled yet
+  name: string | null;
+}
+
+export interface SubjectTarget {
+  subject_id: string;
+  subject_name: string;
+  target_letter: string;
+  target_percentage: number;
+  current_percentage: number | null;
+  outcome: TargetOutcome;
+  required_percentage: number | null; // needed on every remaining test, null if unreachable
+  best_possible_percentage: number; // with full marks on everything left
+  remaining: RemainingTest[];
+}
+
+export interface SemesterTargets {
+  semester_id: string;
+  target_letter: string;
+  subjects: SubjectTarget[];
+  skipped: [string, string][]; // [subject name, reason] for subjects without a scheme or whose scale lacks the grade
+}
+
+// ============================================================================
+// STUDY PLAN
+// ============================================================================
+
+export type CapacityUnit = "topics" | "minutes";
+
+export interface PlanSettings {
+  capacity_unit: CapacityUnit;
+  daily_capacity: number; // topics or minutes per day
+  minutes_per_topic: number; // estimate; a topic's cost when capacity is in minutes
+  rest_days?: Weekday[]; // nothing is planned on these days
+}
+
+export type Weekday = "Mon" | "Tue" | "Wed" | "Thu" | "Fri" | "Sat" | "Sun";
+
+export interface PlannedTopic {
+  item_id: string;
+  topic_id: string;
+  topic_name: string;
+  unit_name: string;
+  subject_name: string;
+  test_id: string | null;
+  test_name: string | null;
+  minutes: number;
+  completed: boolean;
+}
+
+export interface PlanDay {
+  day: string; // YYYY-MM-DD, local
+  minutes: number;
+  topics: PlannedTopic[];
+}
+
+export interface StudyPlan {
+  tracker_id: string;
+  settings: PlanSettings;
+  days: PlanDay[];
+  unscheduled: PlannedTopic[]; // don't fit before their test at this capacity
+}
+
+// ============================================================================
+// AGENDA (derived, not persisted)
+// ============================================================================
+
+export type AgendaBucket = "overdue" | "today" | "this_week";
+
+export type AgendaKind = "test" | "assignment" | "planned_topic";
+
+export interface AgendaItem {
+  bucket: AgendaBucket;
+  kind: AgendaKind;
+  id: string; // test or assignment ID, or the topic ID for planned topics
+  title: string;
+  tracker_id: string;
+  tracker_name: string;
+  subject_name: string | null;
+  detail: string | null; // test type, "for <test>", or assignment status
+  day: string; // YYYY-MM-DD
+  days_until: number; // negative when overdue
+  weight: number;
+}
+
+export interface Agenda {
+  today: string; // YYYY-MM-DD
+  items: AgendaItem[]; // already ranked
+}
+
 // ============================================================================
 // PRIORITY TESTS (derived, not persisted)
 // ============================================================================
@@ -118,6 +379,97 @@ export interface ParsedSyllabusEntry {
   }[];
 }
 
+// ============================================================================
+// IMPORT PREVIEW (derived, not persisted)
+// ============================================================================
+
+export type SyllabusFormat = "text" | "markdown" | "csv";
+
+export type ImportSource =
+  | { format: "auto"; text: string } // format detected from the text
+  | { format: "text"; text: string }
+  | { format: "markdown"; text: string }
+  | { format: "csv"; text: string }
+  | { format: "json"; syllabus: unknown };
+
+export interface ValidationIssue {
+  path: string; // JSON path such as "$.subjects[0].units[1].name"
+  message: string;
+}
+
+export interface SyllabusSubject {
+  name: string;
+  units: {
+    name: string;
+    topics: string[];
+  }[];
+}
+
+export interface ImportPreview {
+  name: string | null;
+  subject_count: number;
+  unit_count: number;
+  topic_count: number;
+  errors: ValidationIssue[]; // import will fail
+  warnings: ValidationIssue[]; // suspicious but importable
+  subjects: SyllabusSubject[];
+}
+
+// ============================================================================
+// SYLLABUS EXPORT (derived, not persisted)
+// ============================================================================
+
+export type ExportFormat = "json" | "text" | "markdown" | "csv" | "html";
+
+export interface ExportedFile {
+  file_name: string;
+  mime_type: string;
+  content: string;
+}
+
+// ============================================================================
+// SYLLABUS DIFF & MERGE (derived, not persisted)
+// ============================================================================
+
+export type DiffLevel = "subject" | "unit" | "topic";
+
+export interface NodePath {
+  subject: string;
+  unit: string | null;
+  topic: string | null;
+}
+
+export type SyllabusChange =
+  | { change: "added"; level: DiffLevel; path: NodePath; topic_count: number }
+  | { change: "removed"; level: DiffLevel; path: NodePath; topic_count: number }
+  | { change: "renamed"; level: DiffLevel; from: NodePath; to: NodePath }
+  | { change: "moved"; level: DiffLevel; from: NodePath; to: NodePath };
+
+export interface SyllabusDiff {
+  changes: SyllabusChange[];
+  added_topics: number;
+  removed_topics: number;
+  renamed_topics: number;
+  moved_topics: number;
+  unchanged_topics: number;
+}
+
+export interface MergePlan {
+  tracker_id: string;
+  diff: SyllabusDiff;
+  kept_topics: number; // existing topics that survive (unchanged, renamed or moved)
+  kept_completed: number; // completed topics among kept_topics
+  removed_completed: number; // completed topics lost if removed items are deleted
+  fingerprint: string; // pass back when applying; stale previews are rejected
+}
+
+export interface MergeSummary {
+  inserted: number;
+  renamed: number;
+  moved: number; // units and topics whose parent or position changed
+  deleted: number;
+}
+
 // ============================================================================
 // PROGRESS DATA (derived, not persisted)
 // ============================================================================
@@ -147,6 +499,66 @@ export interface TrackerProgress {
   subjects: SubjectProgress[];
 }
 
+export interface TrackerSummary {
+  tracker: Tracker;
+  total_topics: number;
+  completed_topics: number;
+  percentage: number;
+}
+
+export interface UpcomingTest {
+  test: Test;
+  tracker_name: string;
+  days_remaining: number;
+  time_remaining: string; // "2d 5h 30m"
+}
+
+export interface SemesterOverview {
+  semester_id: string;
+  total_topics: number;
+  completed_topics: number;
+  percentage: number;
+  trackers: TrackerSummary[];
+  upcoming_tests: UpcomingTest[]; // across trackers, soonest first
+}
+
+// ============================================================================
+// DATABASE HEALTH
+// ============================================================================
+
+export type RecoveryAction = "recovered" | "reopened";
+
+export interface DbIncident {
+  occurred_at: number;
+  connection: string; // "writer" or "reader N"
+  action: RecoveryAction;
+  detail: string;
+}
+
+export type IssueKind =
+  | "corruption" // the file is damaged; restore a backup
+  | "missing_parent"
+  | "empty_coverage"
+  | "duplicate_order"
+  | "stale_statistics";
+
+export interface DatabaseIssue {
+  kind: IssueKind;
+  table: string;
+  detail: string;
+  repairable: boolean;
+}
+
+export interface DatabaseReport {
+  healthy: boolean;
+  issues: DatabaseIssue[];
+}
+
+export interface RepairReport {
+  repaired: number;
+  report: DatabaseReport; // re-checked after repairing
+}
+
 // ============================================================================
 // THEME
 // ============================================================================
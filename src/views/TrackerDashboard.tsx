@@ -8,6 +8,7 @@ import { Modal, ConfirmDialog } from "../components/Modal";
 import { TestScheduleForm } from "../components/TestScheduleForm";
 import { PriorityTestBox } from "../components/PriorityTestBox";
 import { SubjectCard } from "../components/SubjectCard";
+import { StudyPlanPanel } from "../components/StudyPlanPanel";
 import { SubjectView } from "./SubjectView";
 
 interface TrackerDashboardProps {
@@ -22,7 +23,7 @@ export const TrackerDashboard: React.FC<TrackerDashboardProps> = ({
   const { colors } = useTheme();
   const [data, setData] = useState<TrackerDataResponse | null>(null);
   const [isLoading, setIsLoading] = useState(true);
-  const [activeTab, setActiveTab] = useState<"subjects" | "tests">("subjects");
+  const [activeTab, setActiveTab] = useState<"subjects" | "tests" | "plan">("subjects");
   const [isScheduleTestOpen, setIsScheduleTestOpen] = useState(false);
   const [refreshKey, setRefreshKey] = useState(0);
   const [selectedSubject, setSelectedSubject] = useState<string | null>(null);
@@ -36,6 +37,10 @@ export const TrackerDashboard: React.FC<TrackerDashboardProps> = ({
     loadTrackerData();
   }, [tracker.id, refreshKey]);
 
+  // Tests of a deleted type show their stored type ID
+  const testTypeName = (id: string) =>
+    data?.test_types.find((t) => t.id === id)?.name ?? id;
+
   const loadTrackerData = async () => {
     try {
       setIsLoading(true);
@@ -57,6 +62,15 @@ export const TrackerDashboard: React.FC<TrackerDashboardProps> = ({
     }
   };
 
+  const handleSubmitAssignment = async (assignmentId: string) => {
+    try {
+      await api.assignment.setStatus(assignmentId, "submitted");
+      setRefreshKey((prev) => prev + 1);
+    } catch (err) {
+      console.error("Failed to update assignment:", err);
+    }
+  };
+
   const handleTestScheduled = () => {
     setIsScheduleTestOpen(false);
     setRefreshKey((prev) => prev + 1);
@@ -267,9 +281,9 @@ export const TrackerDashboard: React.FC<TrackerDashboardProps> = ({
         {/* Priority Tests */}
         <div>
           <h3 style={{ margin: "0 0 1rem 0", color: colors.fg }}>
-            🔥 Upcoming Tests (Next 7 Days)
+            🔥 Coming Up
           </h3>
-          {data.priority_tests.length === 0 ? (
+          {data.priority_tests.length === 0 && data.priority_assignments.length === 0 ? (
             <div
               style={{
                 backgroundColor: colors.secondary,
@@ -280,12 +294,63 @@ export const TrackerDashboard: React.FC<TrackerDashboardProps> = ({
                 opacity: 0.7,
               }}
             >
-              No tests scheduled in the next 7 days
+              No upcoming priority tests
             </div>
           ) : (
             <div style={{ display: "flex", flexDirection: "column", gap: "1rem" }}>
               {data.priority_tests.map((ptest) => (
-                <PriorityTestBox key={ptest.test.id} test={ptest} />
+                <PriorityTestBox
+                  key={ptest.test.id}
+                  test={ptest}
+                  typeName={testTypeName(ptest.test.test_type)}
+                />
+              ))}
+              {data.priority_assignments.map((passignment) => (
+                <div
+                  key={passignment.assignment.id}
+                  style={{
+                    backgroundColor: colors.secondary,
+                    border: `1px solid ${colors.border}`,
+                    borderRadius: "0.5rem",
+                    padding: "1rem 1.5rem",
+                    display: "flex",
+                    justifyContent: "space-between",
+                    alignItems: "center",
+                    gap: "1rem",
+                  }}
+                >
+                  <div>
+                    <h4 style={{ margin: "0 0 0.25rem 0", color: colors.accent }}>
+                      📝 {passignment.assignment.name}
+                    </h4>
+                    <p
+                      style={{
+                        margin: 0,
+                        fontSize: "0.875rem",
+                        color: colors.fg,
+                        opacity: 0.7,
+                      }}
+                    >
+                      Due in {passignment.time_remaining}
+                      {passignment.linked_topics.length > 0 &&
+                        ` · ${passignment.linked_topics.join(", ")}`}
+                    </p>
+                  </div>
+                  <button
+                    onClick={() => handleSubmitAssignment(passignment.assignment.id)}
+                    style={{
+                      padding: "0.5rem 1rem",
+                      backgroundColor: colors.accent,
+                      color: colors.bg,
+                      border: "none",
+                      borderRadius: "0.375rem",
+                      cursor: "pointer",
+                      whiteSpace: "nowrap",
+                    }}
+                  >
+                    Mark submitted
+                  </button>
+                </div>
               ))}
             </div>
           )}
@@ -301,7 +366,7 @@ export const TrackerDashboard: React.FC<TrackerDashboardProps> = ({
           borderBottom: `2px solid ${colors.border}`,
         }}
       >
-        {["subjects", "tests"].map((tab) => (
+        {["subjects", "tests", "plan"].map((tab) => (
           <button
             key={tab}
             onClick={() => setActiveTab(tab as typeof activeTab)}
@@ -318,7 +383,7 @@ export const TrackerDashboard: React.FC<TrackerDashboardProps> = ({
               transition: "all 0.2s",
             }}
           >
-            {tab === "subjects" ? "Subjects" : "All Tests"}
+            {tab === "subjects" ? "Subjects" : tab === "tests" ? "All Tests" : "Study Plan"}
           </button>
         ))}
       </div>
@@ -386,7 +451,7 @@ export const TrackerDashboard: React.FC<TrackerDashboardProps> = ({
             })}
           </div>
         </div>
-      ) : (
+      ) : activeTab === "tests" ? (
         <div>
           <div style={{ marginBottom: "1.5rem" }}>
             <button
@@ -451,7 +516,7 @@ export const TrackerDashboard: React.FC<TrackerDashboardProps> = ({
                       opacity: 0.7,
                     }}
                   >
-                    Type: {test.test_type}
+                    Type: {testTypeName(test.test_type)}
                   </p>
                   <p
                     style={{
@@ -463,11 +528,25 @@ export const TrackerDashboard: React.FC<TrackerDashboardProps> = ({
                   >
                     Date: {new Date(test.scheduled_date).toLocaleDateString()}
                   </p>
+                  {test.marks_obtained !== null && test.max_marks !== null && (
+                    <p
+                      style={{
+                        margin: "0.25rem 0",
+                        fontSize: "0.875rem",
+                        color: colors.fg,
+                        opacity: 0.7,
+                      }}
+                    >
+                      Marks: {test.marks_obtained} / {test.max_marks}
+                    </p>
+                  )}
                 </div>
               ))}
             </div>
           )}
         </div>
+      ) : (
+        <StudyPlanPanel trackerId={tracker.id} refreshKey={refreshKey} />
       )}
 
       {/* Schedule Test Modal */}